@@ -8,7 +8,12 @@ use librespot_core::{
     session::{Session, SessionError},
 };
 
-use crate::config;
+use crate::{config, encryption};
+
+/// the name of the file storing credentials in the cache folder, managed by `librespot`
+const CREDENTIALS_CACHE_FILE: &str = "credentials.json";
+/// the name of the file storing credentials in the cache folder when cache encryption is enabled
+const ENCRYPTED_CREDENTIALS_CACHE_FILE: &str = "credentials.enc";
 
 #[derive(Clone)]
 pub struct AuthConfig {
@@ -27,8 +32,9 @@ impl Default for AuthConfig {
 
 impl AuthConfig {
     pub fn new(configs: &config::Configs) -> Result<AuthConfig> {
-        let audio_cache_folder = if configs.app_config.device.audio_cache {
-            Some(configs.cache_folder.join("audio"))
+        let device = &configs.app_config.device;
+        let audio_cache_folder = if device.audio_cache {
+            Some(configs.audio_cache_folder())
         } else {
             None
         };
@@ -37,7 +43,9 @@ impl AuthConfig {
             Some(configs.cache_folder.clone()),
             None,
             audio_cache_folder,
-            None,
+            device
+                .audio_cache_size_limit_in_mb
+                .map(|size| size * 1024 * 1024),
         )?;
 
         Ok(AuthConfig {
@@ -47,6 +55,127 @@ impl AuthConfig {
     }
 }
 
+/// gets the cached credentials, which are stored in the system keyring if `enable_keyring` is set
+/// or in the application's cache folder otherwise (encrypted if `enable_cache_encryption` is set).
+///
+/// If the keyring is not accessible, fallback to the credentials stored in the cache folder.
+fn cached_credentials(auth_config: &AuthConfig) -> Option<Credentials> {
+    #[cfg(feature = "keyring")]
+    if config::get_config().app_config.enable_keyring {
+        match keyring_store::get_credentials() {
+            Ok(Some(creds)) => return Some(creds),
+            // credentials previously cached in the cache folder are migrated to the keyring
+            // when they are stored again after a successful authentication
+            Ok(None) => {}
+            Err(err) => tracing::warn!(
+                "Failed to get credentials from the system keyring, fallback to the cache folder: {err:#}"
+            ),
+        }
+    }
+
+    if encryption::is_enabled() {
+        let path = config::get_config()
+            .cache_folder
+            .join(ENCRYPTED_CREDENTIALS_CACHE_FILE);
+        if path.exists() {
+            match encryption::read_encrypted_file(&path)
+                .and_then(|data| Ok(serde_json::from_slice(&data)?))
+            {
+                Ok(creds) => return Some(creds),
+                Err(err) => {
+                    tracing::warn!("Failed to read the encrypted credentials cache: {err:#}")
+                }
+            }
+        }
+        // plaintext credentials cached before enabling the encryption are encrypted
+        // when they are stored again after a successful authentication
+    }
+
+    auth_config.cache.credentials()
+}
+
+/// stores the reusable credentials of an authenticated session into the system keyring
+/// if `enable_keyring` is set or into the application's cache folder otherwise
+/// (encrypted if `enable_cache_encryption` is set).
+///
+/// If the keyring is not accessible, fallback to storing the credentials in the cache folder.
+fn store_credentials(auth_config: &AuthConfig, creds: &Credentials) {
+    #[cfg(feature = "keyring")]
+    if config::get_config().app_config.enable_keyring {
+        match keyring_store::set_credentials(creds) {
+            Ok(()) => {
+                remove_plaintext_credentials();
+                return;
+            }
+            Err(err) => tracing::warn!(
+                "Failed to store credentials in the system keyring, fallback to the cache folder: {err:#}"
+            ),
+        }
+    }
+
+    if encryption::is_enabled() {
+        let path = config::get_config()
+            .cache_folder
+            .join(ENCRYPTED_CREDENTIALS_CACHE_FILE);
+        match serde_json::to_vec(creds)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| encryption::write_encrypted_file(&path, &data))
+        {
+            Ok(()) => remove_plaintext_credentials(),
+            // the credentials are not stored in plaintext as a fallback
+            Err(err) => tracing::warn!("Failed to write the encrypted credentials cache: {err:#}"),
+        }
+        return;
+    }
+
+    auth_config.cache.save_credentials(creds);
+}
+
+/// removes the plaintext credentials cached before enabling the keyring or the cache encryption
+fn remove_plaintext_credentials() {
+    let path = config::get_config()
+        .cache_folder
+        .join(CREDENTIALS_CACHE_FILE);
+    if path.exists() {
+        if let Err(err) = std::fs::remove_file(&path) {
+            tracing::warn!("Failed to remove the cached credentials file: {err:#}");
+        }
+    }
+}
+
+#[cfg(feature = "keyring")]
+mod keyring_store {
+    use anyhow::Result;
+    use librespot_core::authentication::Credentials;
+
+    use crate::config;
+
+    /// the name of the service the keyring entry is registered under
+    const KEYRING_SERVICE: &str = "spotify-player";
+
+    /// gets the keyring entry storing the credentials, which is distinguished per profile
+    fn entry() -> Result<keyring::Entry> {
+        let user = config::get_config()
+            .profile
+            .clone()
+            .unwrap_or_else(|| "default".to_string());
+        Ok(keyring::Entry::new(KEYRING_SERVICE, &user)?)
+    }
+
+    pub fn get_credentials() -> Result<Option<Credentials>> {
+        match entry()?.get_password() {
+            Ok(creds) => Ok(Some(serde_json::from_str(&creds)?)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn set_credentials(creds: &Credentials) -> Result<()> {
+        entry()?.set_password(&serde_json::to_string(creds)?)?;
+        Ok(())
+    }
+}
+
 fn read_user_auth_details(user: Option<String>) -> Result<(String, String)> {
     let mut username = String::new();
     let mut stdout = std::io::stdout();
@@ -67,6 +196,17 @@ fn read_user_auth_details(user: Option<String>) -> Result<(String, String)> {
 pub async fn new_session_with_new_creds(auth_config: &AuthConfig) -> Result<Session> {
     tracing::info!("Creating a new session with new authentication credentials");
 
+    // the cached token may belong to the previously authenticated user
+    for file in [
+        crate::client::TOKEN_CACHE_FILE,
+        crate::client::ENCRYPTED_TOKEN_CACHE_FILE,
+    ] {
+        let token_cache_path = config::get_config().cache_folder.join(file);
+        if token_cache_path.exists() {
+            std::fs::remove_file(token_cache_path)?;
+        }
+    }
+
     let mut user: Option<String> = None;
 
     for i in 0..3 {
@@ -76,11 +216,12 @@ pub async fn new_session_with_new_creds(auth_config: &AuthConfig) -> Result<Sess
             auth_config.session_config.clone(),
             Credentials::with_password(username, password),
             Some(auth_config.cache.clone()),
-            true,
+            false,
         )
         .await
         {
-            Ok((session, _)) => {
+            Ok((session, creds)) => {
+                store_credentials(auth_config, &creds);
                 println!("Successfully authenticated as {}", user.unwrap_or_default());
                 return Ok(session);
             }
@@ -101,7 +242,7 @@ pub async fn new_session_with_new_creds(auth_config: &AuthConfig) -> Result<Sess
 /// If `reauth` is true, re-authenticate by asking the user for Spotify's username and password.
 /// The re-authentication process should only happen on the terminal using stdin/stdout.
 pub async fn new_session(auth_config: &AuthConfig, reauth: bool) -> Result<Session> {
-    match auth_config.cache.credentials() {
+    match cached_credentials(auth_config) {
         None => {
             let msg = "No cached credentials found, please authenticate the application first.";
             if reauth {
@@ -116,11 +257,12 @@ pub async fn new_session(auth_config: &AuthConfig, reauth: bool) -> Result<Sessi
                 auth_config.session_config.clone(),
                 creds,
                 Some(auth_config.cache.clone()),
-                true,
+                false,
             )
             .await
             {
-                Ok((session, _)) => {
+                Ok((session, creds)) => {
+                    store_credentials(auth_config, &creds);
                     tracing::info!(
                         "Successfully used the cached credentials to create a new session!"
                     );
@@ -131,7 +273,8 @@ pub async fn new_session(auth_config: &AuthConfig, reauth: bool) -> Result<Sessi
                         anyhow::bail!("Failed to authenticate using cached credentials: {err:#}");
                     }
                     SessionError::IoError(err) => {
-                        anyhow::bail!("{err:#}\nPlease check your internet connection.");
+                        Err(anyhow::Error::new(crate::client::ClientError::Network)
+                            .context(format!("{err:#}\nPlease check your internet connection.")))
                     }
                 },
             }
@@ -25,6 +25,7 @@ use super::*;
 
 pub async fn start_socket(client: Client, socket: UdpSocket, state: Option<SharedState>) {
     let mut buf = [0; MAX_REQUEST_SIZE];
+    let socket = std::sync::Arc::new(socket);
 
     loop {
         match socket.recv_from(&mut buf).await {
@@ -47,7 +48,12 @@ pub async fn start_socket(client: Client, socket: UdpSocket, state: Option<Share
 
                 let span = tracing::info_span!("socket_request", request = ?request, dest_addr = ?dest_addr);
 
-                async {
+                // handle each request in a separate task, so that a slow request
+                // (e.g. fetching a large playlist) doesn't block subsequent requests
+                let client = client.clone();
+                let state = state.clone();
+                let socket = socket.clone();
+                let task = async move {
                     let response = match handle_socket_request(&client, &state, request).await {
                         Err(err) => {
                             tracing::error!("Failed to handle socket request: {err:#}");
@@ -56,14 +62,16 @@ pub async fn start_socket(client: Client, socket: UdpSocket, state: Option<Share
                         }
                         Ok(data) => Response::Ok(data),
                     };
+                    if let Some(ref state) = state {
+                        state.mark_changed();
+                    }
                     send_response(response, &socket, dest_addr)
                         .await
                         .unwrap_or_default();
 
                     tracing::info!("Successfully handled the socket request.",);
-                }
-                .instrument(span)
-                .await;
+                };
+                tokio::task::spawn(task.instrument(span));
             }
         }
     }
@@ -94,13 +102,13 @@ async fn current_playback(
     match state {
         Some(ref state) => Ok(state.player.read().current_playback()),
         None => client
-            .current_playback(None, None::<Vec<_>>)
+            .current_playback(Some(Market::FromToken), None::<Vec<_>>)
             .await
             .context("get current playback"),
     }
 }
 
-async fn handle_socket_request(
+pub async fn handle_socket_request(
     client: &Client,
     state: &Option<SharedState>,
     request: super::Request,
@@ -151,11 +159,11 @@ async fn handle_socket_request(
                 },
             };
 
-            if let Some(id) = track.and_then(|t| t.id.to_owned()) {
+            if let Some((track, id)) = track.and_then(|t| Some((t, t.id.to_owned()?))) {
                 if unlike {
                     client.current_user_saved_tracks_delete([id]).await?;
                 } else {
-                    client.current_user_saved_tracks_add([id]).await?;
+                    client.save_track(id, track.into()).await?;
                 }
             }
 
@@ -169,6 +177,16 @@ async fn handle_socket_request(
             let resp = handle_search_request(client, query).await?;
             Ok(resp)
         }
+        Request::Queue(id_or_name) => {
+            let ItemId::Track(id) = get_spotify_id(client, ItemType::Track, id_or_name).await?
+            else {
+                unreachable!()
+            };
+            client
+                .add_item_to_queue(PlayableId::Track(id), None)
+                .await?;
+            Ok(Vec::new())
+        }
     }
 }
 
@@ -328,7 +346,9 @@ async fn handle_playback_request(
     let playback = match state {
         Some(state) => state.player.read().buffered_playback.clone(),
         None => {
-            let playback = client.current_playback(None, None::<Vec<_>>).await?;
+            let playback = client
+                .current_playback(Some(Market::FromToken), None::<Vec<_>>)
+                .await?;
             playback.as_ref().map(PlaybackMetadata::from_playback)
         }
     };
@@ -412,7 +432,7 @@ async fn handle_playback_request(
             // the function scope is from the application's state (cached) or the `current_playback` API.
             // Therefore, we need to make an additional API request to get the playback's progress.
             let progress = client
-                .current_playback(None, None::<Vec<_>>)
+                .current_playback(Some(Market::FromToken), None::<Vec<_>>)
                 .await?
                 .context("no active playback found!")?
                 .progress
@@ -435,6 +455,7 @@ async fn handle_playback_request(
                     Ok(playback) => {
                         // update application's states
                         state.player.write().buffered_playback = playback;
+                        state.mark_changed();
                         client.update_playback(&state);
                     }
                     Err(err) => {
@@ -514,7 +535,7 @@ async fn handle_playlist_request(client: &Client, command: PlaylistCommand) -> R
         } => playlist_import(client, import_from, import_to, delete).await,
         PlaylistCommand::Fork { id } => {
             let from = client
-                .playlist(id.to_owned(), None, None)
+                .playlist(id.to_owned(), None, Some(Market::FromToken))
                 .await
                 .context(format!("Cannot import from {}.", id.id()))?;
             let from_desc = from.description.unwrap_or_default();
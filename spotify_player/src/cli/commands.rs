@@ -1,7 +1,10 @@
 use clap::{builder::EnumValueParser, value_parser, Arg, ArgAction, ArgGroup, Command};
 use clap_complete::Shell;
 
-use super::{ContextType, ItemType, Key};
+use super::{
+    status::{DEFAULT_STATUS_FORMAT, DEFAULT_TMUX_STATUS_FORMAT},
+    ContextType, ItemType, Key,
+};
 
 pub fn init_connect_subcommand() -> Command {
     add_id_or_name_group(Command::new("connect").about("Connect to a Spotify device"))
@@ -94,9 +97,26 @@ pub fn init_playback_subcommand() -> Command {
         .subcommand(Command::new("play").about("Resume the current playback if stopped"))
         .subcommand(Command::new("pause").about("Pause the current playback if playing"))
         .subcommand(Command::new("next").about("Skip to the next track"))
-        .subcommand(Command::new("previous").about("Skip to the previous track"))
+        .subcommand(
+            Command::new("previous")
+                .visible_alias("prev")
+                .about("Skip to the previous track"),
+        )
         .subcommand(Command::new("shuffle").about("Toggle the shuffle mode"))
         .subcommand(Command::new("repeat").about("Cycle the repeat mode"))
+        .subcommand(add_id_or_name_group(
+            Command::new("queue").about("Add a track to the playback's queue"),
+        ))
+        .subcommand(
+            Command::new("status")
+                .about("Show the current playback's status")
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .action(ArgAction::SetTrue)
+                        .help("Print the current playback's data in JSON format"),
+                ),
+        )
         .subcommand(
             Command::new("volume")
                 .about("Set the volume percentage")
@@ -129,6 +149,56 @@ pub fn init_search_command() -> Command {
         .arg(Arg::new("query").help("Search query").required(true))
 }
 
+pub fn init_status_command() -> Command {
+    Command::new("status")
+        .about("Print the current playback's status, e.g. for status bars")
+        .arg(
+            Arg::new("format")
+                .short('f')
+                .long("format")
+                .default_value(DEFAULT_STATUS_FORMAT)
+                .help("Output template with {state}, {title}, {artist}, {album}, {progress}, {duration}, {percentage}, {device}, {volume}, {shuffle} and {repeat} placeholders"),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Print the status in the JSON format of waybar's custom modules"),
+        )
+        .arg(
+            Arg::new("follow")
+                .long("follow")
+                .action(ArgAction::SetTrue)
+                .help("Keep running and print a new line whenever the status is changed"),
+        )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .default_value("1")
+                .value_parser(value_parser!(u64).range(1..))
+                .help("The interval (in seconds) to check the status in follow mode"),
+        )
+}
+
+pub fn init_tmux_status_command() -> Command {
+    Command::new("tmux-status")
+        .about("Print a compact, colorized status of the running application's playback for tmux's status line")
+        .arg(
+            Arg::new("format")
+                .short('f')
+                .long("format")
+                .default_value(DEFAULT_TMUX_STATUS_FORMAT)
+                .help("Output template with the same placeholders as the `status` command"),
+        )
+        .arg(
+            Arg::new("max_width")
+                .long("max-width")
+                .default_value("50")
+                .value_parser(value_parser!(usize))
+                .help("The maximum number of characters of the formatted status, which is truncated if longer"),
+        )
+}
+
 pub fn init_like_command() -> Command {
     Command::new("like")
         .about("Like currently playing track")
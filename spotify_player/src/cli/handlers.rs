@@ -27,6 +27,94 @@ fn receive_response(socket: &UdpSocket) -> Result<Response> {
     Ok(serde_json::from_slice(&data)?)
 }
 
+/// Send a request to the client's socket and receive its response
+fn send_request(socket: &UdpSocket, request: &Request) -> Result<Response> {
+    let request_buf = serde_json::to_vec(request)?;
+    assert!(request_buf.len() <= MAX_REQUEST_SIZE);
+    socket.send(&request_buf)?;
+    receive_response(socket)
+}
+
+/// Handle the `status` command, which prints the current playback's status and,
+/// in follow mode, prints a new status whenever it's changed
+fn handle_status_command(socket: &UdpSocket, args: &ArgMatches) -> Result<()> {
+    let format = args
+        .get_one::<String>("format")
+        .expect("format should have a default value");
+    let json = args.get_flag("json");
+    let interval = std::time::Duration::from_secs(
+        *args
+            .get_one::<u64>("interval")
+            .expect("interval should have a default value"),
+    );
+
+    let request = Request::Get(GetRequest::Key(Key::Playback));
+    let mut last_output = None;
+    loop {
+        let output = match send_request(socket, &request)? {
+            Response::Err(err) => anyhow::bail!("{}", String::from_utf8_lossy(&err)),
+            Response::Ok(data) => {
+                let status = PlaybackStatus::from_response(&data)?;
+                if json {
+                    status::to_waybar_json(status.as_ref(), format)?
+                } else {
+                    status.map(|s| s.format(format)).unwrap_or_default()
+                }
+            }
+        };
+
+        if last_output.as_ref() != Some(&output) {
+            println!("{output}");
+            std::io::Write::flush(&mut std::io::stdout())?;
+            last_output = Some(output);
+        }
+
+        if !args.get_flag("follow") {
+            return Ok(());
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Handle the `tmux-status` command, which prints the running application's playback status.
+///
+/// Unlike other commands, no new client is started if the application is not running
+/// because the command is run on every refresh of tmux's status line.
+fn handle_tmux_status_command(
+    socket: &UdpSocket,
+    configs: &config::Configs,
+    args: &ArgMatches,
+) -> Result<()> {
+    let format = args
+        .get_one::<String>("format")
+        .expect("format should have a default value");
+    let max_width = *args
+        .get_one::<usize>("max_width")
+        .expect("max_width should have a default value");
+
+    socket.connect(("127.0.0.1", configs.app_config.client_port))?;
+    // avoid blocking tmux's status line if the application doesn't respond
+    socket.set_read_timeout(Some(std::time::Duration::from_secs(1)))?;
+    socket.send(&[])?;
+    if socket.recv(&mut [0; 1]).is_err() {
+        // no running `spotify_player` instance found
+        return Ok(());
+    }
+
+    let request = Request::Get(GetRequest::Key(Key::Playback));
+    match send_request(socket, &request)? {
+        Response::Err(err) => anyhow::bail!("{}", String::from_utf8_lossy(&err)),
+        Response::Ok(data) => {
+            let status = PlaybackStatus::from_response(&data)?;
+            println!(
+                "{}",
+                status::to_tmux_status(status.as_ref(), format, max_width)
+            );
+        }
+    }
+    Ok(())
+}
+
 fn get_id_or_name(args: &ArgMatches) -> IdOrName {
     match args
         .get_one::<Id>("id_or_name")
@@ -75,6 +163,9 @@ fn handle_get_subcommand(args: &ArgMatches) -> Result<Request> {
 fn handle_playback_subcommand(args: &ArgMatches) -> Result<Request> {
     let (cmd, args) = args.subcommand().expect("playback subcommand is required");
     let command = match cmd {
+        // the playback's status is retrieved from the client's current playback data
+        "status" => return Ok(Request::Get(GetRequest::Key(Key::Playback))),
+        "queue" => return Ok(Request::Queue(get_id_or_name(args))),
         "start" => match args.subcommand() {
             Some(("context", args)) => {
                 let context_type = args
@@ -158,8 +249,11 @@ fn try_connect_to_client(socket: &UdpSocket, configs: &config::Configs) -> Resul
             let session = rt.block_on(new_session(&auth_config, false))?;
 
             // create a Spotify API client
-            let client =
-                client::Client::new(session, auth_config, configs.app_config.client_id.clone());
+            let client = client::Client::new(
+                Some(session),
+                auth_config,
+                configs.app_config.client_id.clone(),
+            );
             rt.block_on(client.refresh_token())?;
 
             // create a client socket for handling CLI commands
@@ -182,9 +276,19 @@ pub fn handle_cli_subcommand(cmd: &str, args: &ArgMatches) -> Result<()> {
     // handle commands that don't require a client separately
     match cmd {
         "authenticate" => {
-            let auth_config = AuthConfig::new(configs)?;
+            let auth_config = AuthConfig::new(&configs)?;
             let rt = tokio::runtime::Runtime::new()?;
-            rt.block_on(new_session_with_new_creds(&auth_config))?;
+            let session = rt.block_on(new_session_with_new_creds(&auth_config))?;
+
+            // retrieve an access token to populate the token cache, so that
+            // the application can later start without any user interaction
+            let client = client::Client::new(
+                Some(session),
+                auth_config,
+                configs.app_config.client_id.clone(),
+            );
+            rt.block_on(client.refresh_token())
+                .context("failed to retrieve an access token")?;
             std::process::exit(0);
         }
         "generate" => {
@@ -199,7 +303,17 @@ pub fn handle_cli_subcommand(cmd: &str, args: &ArgMatches) -> Result<()> {
         _ => {}
     }
 
-    try_connect_to_client(&socket, configs).context("try to connect to a client")?;
+    if cmd == "tmux-status" {
+        handle_tmux_status_command(&socket, &configs, args)?;
+        std::process::exit(0);
+    }
+
+    try_connect_to_client(&socket, &configs).context("try to connect to a client")?;
+
+    if cmd == "status" {
+        handle_status_command(&socket, args)?;
+        std::process::exit(0);
+    }
 
     // construct a socket request based on the CLI command and its arguments
     let request = match cmd {
@@ -219,18 +333,22 @@ pub fn handle_cli_subcommand(cmd: &str, args: &ArgMatches) -> Result<()> {
         _ => unreachable!(),
     };
 
-    // send the request to the client's socket
-    let request_buf = serde_json::to_vec(&request)?;
-    assert!(request_buf.len() <= MAX_REQUEST_SIZE);
-    socket.send(&request_buf)?;
-
-    // receive and handle a response from the client's socket
-    match receive_response(&socket)? {
+    // send the request to the client's socket and handle its response
+    match send_request(&socket, &request)? {
         Response::Err(err) => {
             eprintln!("{}", String::from_utf8_lossy(&err));
             std::process::exit(1);
         }
         Response::Ok(data) => {
+            if let ("playback", Some(("status", args))) = (cmd, args.subcommand()) {
+                if !args.get_flag("json") {
+                    match PlaybackStatus::from_response(&data)? {
+                        Some(status) => println!("{}", status.summary()),
+                        None => println!("No playback found"),
+                    }
+                    std::process::exit(0);
+                }
+            }
             println!("{}", String::from_utf8_lossy(&data).replace("\\n", "\n"));
             std::process::exit(0);
         }
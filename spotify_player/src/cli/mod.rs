@@ -1,15 +1,20 @@
 mod client;
 mod commands;
 mod handlers;
+mod status;
 
-use crate::config;
+use crate::{
+    config,
+    state::{ContextId, USER_LIKED_TRACKS_ID},
+};
 use rspotify::model::*;
 use serde::{Deserialize, Serialize};
 
 const MAX_REQUEST_SIZE: usize = 4096;
 
-pub use client::start_socket;
+pub use client::{handle_socket_request, start_socket};
 pub use handlers::handle_cli_subcommand;
+use status::PlaybackStatus;
 
 #[derive(Debug, Serialize, Deserialize, clap::ValueEnum, Clone)]
 pub enum Key {
@@ -118,6 +123,7 @@ pub enum Request {
     Like { unlike: bool },
     Playlist(PlaylistCommand),
     Search { query: String },
+    Queue(IdOrName),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -147,6 +153,22 @@ impl ItemId {
     }
 }
 
+/// gets the context to open on startup, which is specified by the `--uri`, `--album` or `--liked` arguments
+pub fn get_start_context(args: &clap::ArgMatches) -> anyhow::Result<Option<ContextId>> {
+    if let Some(uri) = args.get_one::<String>("uri") {
+        return Ok(Some(ContextId::from_uri(uri)?));
+    }
+    if let Some(id) = args.get_one::<String>("album") {
+        return Ok(Some(ContextId::Album(
+            AlbumId::from_id_or_uri(id)?.into_static(),
+        )));
+    }
+    if args.get_flag("liked") {
+        return Ok(Some(ContextId::Tracks(USER_LIKED_TRACKS_ID.to_owned())));
+    }
+    Ok(None)
+}
+
 pub fn init_cli() -> anyhow::Result<clap::Command> {
     let default_cache_folder = config::get_cache_folder_path()?;
     let default_config_folder = config::get_config_folder_path()?;
@@ -163,6 +185,8 @@ pub fn init_cli() -> anyhow::Result<clap::Command> {
         .subcommand(commands::init_playlist_subcommand())
         .subcommand(commands::init_generate_command())
         .subcommand(commands::init_search_command())
+        .subcommand(commands::init_status_command())
+        .subcommand(commands::init_tmux_status_command())
         .arg(
             clap::Arg::new("theme")
                 .short('t')
@@ -174,6 +198,7 @@ pub fn init_cli() -> anyhow::Result<clap::Command> {
             clap::Arg::new("config-folder")
                 .short('c')
                 .long("config-folder")
+                .visible_alias("config")
                 .value_name("FOLDER")
                 .default_value(default_config_folder.into_os_string())
                 .help("Path to the application's config folder"),
@@ -182,9 +207,87 @@ pub fn init_cli() -> anyhow::Result<clap::Command> {
             clap::Arg::new("cache-folder")
                 .short('C')
                 .long("cache-folder")
+                .visible_alias("cache-dir")
                 .value_name("FOLDER")
                 .default_value(default_cache_folder.into_os_string())
                 .help("Path to the application's cache folder"),
+        )
+        .arg(
+            clap::Arg::new("profile")
+                .short('p')
+                .long("profile")
+                .value_name("NAME")
+                .value_parser(config::validate_profile_name)
+                .help("Name of the profile to use, whose configurations and caches are stored separately"),
+        )
+        .arg(
+            clap::Arg::new("log-level")
+                .long("log-level")
+                .value_name("LEVEL")
+                .value_parser(["error", "warn", "info", "debug", "trace"])
+                .help("Level of the application's logs, which overrides the `RUST_LOG` environment variable"),
+        )
+        .arg(
+            clap::Arg::new("uri")
+                .long("uri")
+                .value_name("URI")
+                .help("URI of a playlist, album or artist context to open on startup"),
+        )
+        .arg(
+            clap::Arg::new("album")
+                .long("album")
+                .value_name("ID")
+                .help("ID of an album to open on startup"),
+        )
+        .arg(
+            clap::Arg::new("liked")
+                .long("liked")
+                .action(clap::ArgAction::SetTrue)
+                .help("Open the user's liked tracks on startup"),
+        )
+        .group(
+            clap::ArgGroup::new("start-context")
+                .args(["uri", "album", "liked"])
+                .multiple(false),
+        )
+        .arg(
+            clap::Arg::new("play")
+                .long("play")
+                .action(clap::ArgAction::SetTrue)
+                .requires("start-context")
+                .help("Start playing the context opened on startup"),
+        )
+        .arg(
+            clap::Arg::new("offline")
+                .long("offline")
+                .action(clap::ArgAction::SetTrue)
+                .help("Start the application without connecting to Spotify, browsing the cached data"),
+        )
+        .arg(
+            clap::Arg::new("mock-data")
+                .long("mock-data")
+                .value_name("FILE")
+                .value_parser(clap::value_parser!(std::path::PathBuf))
+                .help("Start the application without connecting to Spotify, browsing the data stored in a JSON file"),
+        )
+        .arg(
+            clap::Arg::new("record")
+                .long("record")
+                .value_name("FOLDER")
+                .value_parser(clap::value_parser!(std::path::PathBuf))
+                .help("Record the fetched data into fixture files inside a folder"),
+        )
+        .arg(
+            clap::Arg::new("replay")
+                .long("replay")
+                .value_name("FOLDER")
+                .value_parser(clap::value_parser!(std::path::PathBuf))
+                .help("Start the application without connecting to Spotify, replaying the data recorded inside a folder"),
+        )
+        .group(
+            clap::ArgGroup::new("data-source")
+                .args(["mock-data", "record", "replay"])
+                .multiple(false),
         );
 
     #[cfg(feature = "daemon")]
@@ -0,0 +1,172 @@
+use anyhow::Result;
+use rspotify::model::{CurrentPlaybackContext, PlayableItem};
+
+use crate::utils::{format_duration, map_join};
+
+/// the default template of the `status` command's output
+pub const DEFAULT_STATUS_FORMAT: &str = "{artist} - {title} [{progress}/{duration}]";
+
+/// the default template of the `tmux-status` command's output
+pub const DEFAULT_TMUX_STATUS_FORMAT: &str = "{title} - {artist}";
+
+/// The current playback's status, which is printed by the `status` command
+/// to integrate the application with status bars (waybar, polybar, etc)
+pub struct PlaybackStatus {
+    /// `playing`, `paused` or `stopped` if no track or episode is loaded
+    state: &'static str,
+    title: String,
+    artist: String,
+    album: String,
+    progress: chrono::Duration,
+    duration: chrono::Duration,
+    device: String,
+    volume: Option<u32>,
+    shuffle: bool,
+    repeat: String,
+}
+
+impl PlaybackStatus {
+    /// parses the current playback's data returned by the client's socket
+    pub fn from_response(data: &[u8]) -> Result<Option<Self>> {
+        let playback: Option<CurrentPlaybackContext> = serde_json::from_slice(data)?;
+        Ok(playback.map(Self::from_playback))
+    }
+
+    fn from_playback(playback: CurrentPlaybackContext) -> Self {
+        let state = match playback.item {
+            None => "stopped",
+            Some(_) if playback.is_playing => "playing",
+            Some(_) => "paused",
+        };
+        let (title, artist, album, duration) = match playback.item {
+            Some(PlayableItem::Track(track)) => (
+                track.name,
+                map_join(&track.artists, |a| &a.name, ", "),
+                track.album.name,
+                track.duration,
+            ),
+            Some(PlayableItem::Episode(episode)) => (
+                episode.name,
+                episode.show.publisher,
+                episode.show.name,
+                episode.duration,
+            ),
+            None => Default::default(),
+        };
+
+        Self {
+            state,
+            title,
+            artist,
+            album,
+            progress: playback.progress.unwrap_or_default(),
+            duration,
+            device: playback.device.name,
+            volume: playback.device.volume_percent,
+            shuffle: playback.shuffle_state,
+            repeat: format!("{:?}", playback.repeat_state).to_lowercase(),
+        }
+    }
+
+    /// formats the status based on a template, in which `{field}` placeholders are replaced
+    /// by the status's fields
+    pub fn format(&self, template: &str) -> String {
+        let percentage = self.percentage().to_string();
+        let volume = self.volume.map(|v| v.to_string()).unwrap_or_default();
+        let fields = [
+            ("{state}", self.state),
+            ("{title}", &self.title),
+            ("{artist}", &self.artist),
+            ("{album}", &self.album),
+            ("{progress}", &format_duration(&self.progress)),
+            ("{duration}", &format_duration(&self.duration)),
+            ("{percentage}", &percentage),
+            ("{device}", &self.device),
+            ("{volume}", &volume),
+            ("{shuffle}", if self.shuffle { "on" } else { "off" }),
+            ("{repeat}", &self.repeat),
+        ];
+        fields
+            .into_iter()
+            .fold(template.to_string(), |s, (k, v)| s.replace(k, v))
+    }
+
+    /// gets the playback's progress in percentage
+    fn percentage(&self) -> i64 {
+        let duration = self.duration.num_milliseconds();
+        if duration > 0 {
+            (self.progress.num_milliseconds() * 100 / duration).clamp(0, 100)
+        } else {
+            0
+        }
+    }
+
+    /// gets a human-readable summary of the status
+    pub fn summary(&self) -> String {
+        let volume = self
+            .volume
+            .map(|v| format!(", volume {v}%"))
+            .unwrap_or_default();
+        format!(
+            "{}: {} - {} ({}) [{}/{}]\nDevice: {}{volume}, shuffle {}, repeat {}",
+            match self.state {
+                "playing" => "Playing",
+                "paused" => "Paused",
+                _ => "Stopped",
+            },
+            self.title,
+            self.artist,
+            self.album,
+            format_duration(&self.progress),
+            format_duration(&self.duration),
+            self.device,
+            if self.shuffle { "on" } else { "off" },
+            self.repeat,
+        )
+    }
+}
+
+/// formats an optional playback's status into a tmux status line's string, in which
+/// the formatted status is prefixed by a play/pause icon colored by the playback's state
+pub fn to_tmux_status(status: Option<&PlaybackStatus>, template: &str, max_width: usize) -> String {
+    let Some(status) = status else {
+        return String::new();
+    };
+
+    let mut text = status.format(template);
+    if text.chars().count() > max_width {
+        text = text.chars().take(max_width.saturating_sub(1)).collect();
+        text.push('…');
+    }
+    // `#` starts a tmux format sequence, so it needs to be escaped
+    let text = text.replace('#', "##");
+
+    let configs = crate::config::get_config();
+    let (color, icon) = if status.state == "playing" {
+        ("green", &configs.app_config.play_icon)
+    } else {
+        ("yellow", &configs.app_config.pause_icon)
+    };
+    format!("#[fg={color}]{icon}#[default] {text}")
+}
+
+/// formats an optional playback's status into a waybar custom module's JSON output
+pub fn to_waybar_json(status: Option<&PlaybackStatus>, template: &str) -> Result<String> {
+    let value = match status {
+        Some(status) => serde_json::json!({
+            "text": status.format(template),
+            "tooltip": format!("{}\n{}\n{}", status.title, status.artist, status.album),
+            "alt": status.state,
+            "class": status.state,
+            "percentage": status.percentage(),
+        }),
+        None => serde_json::json!({
+            "text": "",
+            "tooltip": "",
+            "alt": "stopped",
+            "class": "stopped",
+            "percentage": 0,
+        }),
+    };
+    Ok(serde_json::to_string(&value)?)
+}
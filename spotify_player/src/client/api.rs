@@ -0,0 +1,253 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use serde::Deserialize;
+
+use crate::state::*;
+
+use super::{Client, RecordingSpotifyApi, ReplaySpotifyApi};
+
+#[async_trait::async_trait]
+/// An interface to fetch the user's data and contexts from Spotify, which is used by
+/// the client's request handlers. It's implemented by the application's `Client` and
+/// by alternative data sources, e.g. an in-memory `MockSpotifyApi`.
+pub trait SpotifyApi: Send + Sync {
+    /// checks if the data is served without connecting to Spotify
+    fn is_offline(&self) -> bool;
+    async fn current_user_playlists(&self) -> Result<Vec<Playlist>>;
+    async fn current_user_saved_albums(&self) -> Result<Vec<Album>>;
+    async fn current_user_followed_artists(&self) -> Result<Vec<Artist>>;
+    async fn current_user_saved_tracks(&self) -> Result<Vec<Track>>;
+    async fn current_user_top_tracks(&self) -> Result<Vec<Track>>;
+    async fn current_user_recently_played_tracks(&self) -> Result<Vec<Track>>;
+    async fn context(&self, state: &SharedState, id: ContextId) -> Result<Context>;
+    async fn search(&self, query: &str) -> Result<SearchResults>;
+}
+
+#[async_trait::async_trait]
+impl SpotifyApi for Client {
+    fn is_offline(&self) -> bool {
+        false
+    }
+
+    async fn current_user_playlists(&self) -> Result<Vec<Playlist>> {
+        Client::current_user_playlists(self).await
+    }
+
+    async fn current_user_saved_albums(&self) -> Result<Vec<Album>> {
+        Client::current_user_saved_albums(self).await
+    }
+
+    async fn current_user_followed_artists(&self) -> Result<Vec<Artist>> {
+        Client::current_user_followed_artists(self).await
+    }
+
+    async fn current_user_saved_tracks(&self) -> Result<Vec<Track>> {
+        Client::current_user_saved_tracks(self).await
+    }
+
+    async fn current_user_top_tracks(&self) -> Result<Vec<Track>> {
+        Client::current_user_top_tracks(self).await
+    }
+
+    async fn current_user_recently_played_tracks(&self) -> Result<Vec<Track>> {
+        Client::current_user_recently_played_tracks(self).await
+    }
+
+    async fn context(&self, state: &SharedState, id: ContextId) -> Result<Context> {
+        self.fetch_context(state, id).await
+    }
+
+    async fn search(&self, query: &str) -> Result<SearchResults> {
+        Client::search(self, query).await
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+/// The data served by a `MockSpotifyApi`
+pub struct MockData {
+    pub playlists: Vec<Playlist>,
+    pub saved_albums: Vec<Album>,
+    pub followed_artists: Vec<Artist>,
+    pub saved_tracks: Vec<Track>,
+    pub top_tracks: Vec<Track>,
+    pub recently_played_tracks: Vec<Track>,
+    /// contexts keyed by their URIs
+    pub contexts: HashMap<String, Context>,
+}
+
+/// An in-memory implementation of `SpotifyApi`, which allows running the application
+/// without Spotify credentials, e.g. to develop the application's UI
+pub struct MockSpotifyApi {
+    data: MockData,
+}
+
+impl MockSpotifyApi {
+    pub fn new(data: MockData) -> Self {
+        Self { data }
+    }
+
+    /// constructs a mock API serving the data stored in a JSON file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let f = std::fs::File::open(path)
+            .with_context(|| format!("failed to open the mock data file {}", path.display()))?;
+        let data = serde_json::from_reader(std::io::BufReader::new(f))
+            .with_context(|| format!("failed to parse the mock data file {}", path.display()))?;
+        Ok(Self::new(data))
+    }
+}
+
+#[async_trait::async_trait]
+impl SpotifyApi for MockSpotifyApi {
+    fn is_offline(&self) -> bool {
+        true
+    }
+
+    async fn current_user_playlists(&self) -> Result<Vec<Playlist>> {
+        Ok(self.data.playlists.clone())
+    }
+
+    async fn current_user_saved_albums(&self) -> Result<Vec<Album>> {
+        Ok(self.data.saved_albums.clone())
+    }
+
+    async fn current_user_followed_artists(&self) -> Result<Vec<Artist>> {
+        Ok(self.data.followed_artists.clone())
+    }
+
+    async fn current_user_saved_tracks(&self) -> Result<Vec<Track>> {
+        Ok(self.data.saved_tracks.clone())
+    }
+
+    async fn current_user_top_tracks(&self) -> Result<Vec<Track>> {
+        Ok(self.data.top_tracks.clone())
+    }
+
+    async fn current_user_recently_played_tracks(&self) -> Result<Vec<Track>> {
+        Ok(self.data.recently_played_tracks.clone())
+    }
+
+    async fn context(&self, _state: &SharedState, id: ContextId) -> Result<Context> {
+        let uri = id.uri();
+        self.data
+            .contexts
+            .get(&uri)
+            .cloned()
+            .with_context(|| format!("context {uri} not found in the mock data"))
+    }
+
+    async fn search(&self, query: &str) -> Result<SearchResults> {
+        let query = query.to_lowercase();
+        let matches = |name: &str| name.to_lowercase().contains(&query);
+
+        let mut tracks = vec![];
+        let mut albums = self.data.saved_albums.clone();
+        for context in self.data.contexts.values() {
+            match context {
+                Context::Playlist { tracks: t, .. }
+                | Context::Album { tracks: t, .. }
+                | Context::Tracks { tracks: t, .. } => tracks.extend(t.iter().cloned()),
+                Context::Artist {
+                    top_tracks,
+                    albums: a,
+                    ..
+                } => {
+                    tracks.extend(top_tracks.iter().cloned());
+                    albums.extend(a.iter().cloned());
+                }
+            }
+        }
+        tracks.extend(self.data.saved_tracks.iter().cloned());
+
+        let mut results = SearchResults {
+            tracks: tracks.into_iter().filter(|t| matches(&t.name)).collect(),
+            artists: self
+                .data
+                .followed_artists
+                .iter()
+                .filter(|a| matches(&a.name))
+                .cloned()
+                .collect(),
+            albums: albums.into_iter().filter(|a| matches(&a.name)).collect(),
+            playlists: self
+                .data
+                .playlists
+                .iter()
+                .filter(|p| matches(&p.name))
+                .cloned()
+                .collect(),
+        };
+        // an item can be found in several contexts, which are not necessarily next to each other
+        let mut seen_tracks = HashSet::new();
+        results.tracks.retain(|t| seen_tracks.insert(t.id.clone()));
+        let mut seen_albums = HashSet::new();
+        results.albums.retain(|a| seen_albums.insert(a.id.clone()));
+        Ok(results)
+    }
+}
+
+/// An alternative source of the application's data, which is specified by CLI arguments
+pub enum DataSource {
+    /// data stored in a JSON file
+    Mock(MockSpotifyApi),
+    /// responses recorded into a folder by a previous run
+    Replay(ReplaySpotifyApi),
+    /// Spotify API, whose responses are recorded into a folder
+    Record(PathBuf),
+}
+
+impl DataSource {
+    /// checks if the data source doesn't require connecting to Spotify
+    pub fn is_offline(&self) -> bool {
+        !matches!(self, Self::Record(_))
+    }
+
+    pub fn into_api(self, client: &Client) -> Arc<dyn SpotifyApi> {
+        match self {
+            Self::Mock(api) => Arc::new(api),
+            Self::Replay(api) => Arc::new(api),
+            Self::Record(folder) => Arc::new(RecordingSpotifyApi::new(client.clone(), folder)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn album(id: &str) -> Album {
+        Album {
+            id: AlbumId::from_id(id.to_string()).unwrap(),
+            release_date: String::new(),
+            name: "album".to_string(),
+            artists: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn search_removes_non_adjacent_duplicates() {
+        let (first, second) = (Track::unavailable(0, 0), Track::unavailable(1, 0));
+        let api = MockSpotifyApi::new(MockData {
+            saved_tracks: vec![first.clone(), second.clone(), first.clone()],
+            saved_albums: vec![album("a"), album("b"), album("a")],
+            ..Default::default()
+        });
+
+        let results = api.search("unavailable").await.unwrap();
+        let track_ids = results.tracks.iter().map(|t| &t.id).collect::<Vec<_>>();
+        assert_eq!(track_ids, [&first.id, &second.id]);
+
+        let results = api.search("album").await.unwrap();
+        let album_ids = results
+            .albums
+            .iter()
+            .map(|a| a.id.id().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(album_ids, ["a", "b"]);
+    }
+}
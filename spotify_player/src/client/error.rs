@@ -0,0 +1,159 @@
+use std::fmt;
+
+/// The class of an error returned by a client request,
+/// which allows callers and the UI to react differently per error class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientError {
+    /// the access token is expired or revoked
+    Unauthorized,
+    /// the request is rejected because of rate limiting, with the response's
+    /// `Retry-After` value (in seconds) if specified
+    RateLimited { retry_after_in_secs: Option<u64> },
+    /// no active device to handle a player request
+    NoActiveDevice,
+    /// the requested item is not found
+    NotFound,
+    /// Spotify API server fails to handle the request
+    Server(reqwest::StatusCode),
+    /// the request cannot reach Spotify API server (e.g. connection errors, timeouts)
+    Network,
+    /// Spotify API server returns an unsuccessful response not covered by other classes
+    Status(reqwest::StatusCode),
+    /// other errors
+    Other,
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unauthorized => write!(f, "the access token is expired or revoked"),
+            Self::RateLimited {
+                retry_after_in_secs: Some(secs),
+            } => write!(f, "rate limited by Spotify API, retry after {secs}s"),
+            Self::RateLimited {
+                retry_after_in_secs: None,
+            } => write!(f, "rate limited by Spotify API"),
+            Self::NoActiveDevice => write!(f, "no active device found"),
+            Self::NotFound => write!(f, "the requested item is not found"),
+            Self::Server(status) => write!(f, "Spotify API server error ({status})"),
+            Self::Network => write!(f, "failed to connect to Spotify API"),
+            Self::Status(status) => write!(f, "unsuccessful response ({status})"),
+            Self::Other => write!(f, "unexpected error"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl ClientError {
+    /// classifies an error returned by a client request
+    pub fn classify(err: &anyhow::Error) -> Self {
+        if let Some(err) = err.downcast_ref::<ClientError>() {
+            return err.clone();
+        }
+        if let Some(err) = err.downcast_ref::<rspotify::ClientError>() {
+            return match err {
+                rspotify::ClientError::Http(err) => match err.as_ref() {
+                    rspotify::http::HttpError::StatusCode(response) => {
+                        Self::from_response(response)
+                    }
+                    rspotify::http::HttpError::Client(err) => Self::from_reqwest_error(err),
+                },
+                rspotify::ClientError::InvalidToken => Self::Unauthorized,
+                _ => Self::Other,
+            };
+        }
+        if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+            return Self::from_reqwest_error(err);
+        }
+        Self::Other
+    }
+
+    /// checks if the error is transient, i.e. retrying the request may succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Network | Self::Server(_))
+    }
+
+    /// classifies an unsuccessful response from Spotify API
+    pub fn from_response(response: &reqwest::Response) -> Self {
+        let retry_after_in_secs = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+        Self::from_status(response.status(), retry_after_in_secs)
+    }
+
+    fn from_status(status: reqwest::StatusCode, retry_after_in_secs: Option<u64>) -> Self {
+        match status {
+            reqwest::StatusCode::UNAUTHORIZED => Self::Unauthorized,
+            reqwest::StatusCode::TOO_MANY_REQUESTS => Self::RateLimited {
+                retry_after_in_secs,
+            },
+            reqwest::StatusCode::NOT_FOUND => Self::NotFound,
+            status if status.is_server_error() => Self::Server(status),
+            status => Self::Status(status),
+        }
+    }
+
+    fn from_reqwest_error(err: &reqwest::Error) -> Self {
+        if err.is_connect() || err.is_timeout() || err.is_request() {
+            Self::Network
+        } else if let Some(status) = err.status() {
+            Self::from_status(status, None)
+        } else {
+            Self::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_errors_are_classified() {
+        let err = anyhow::Error::new(ClientError::NoActiveDevice).context("start playback");
+        assert_eq!(ClientError::classify(&err), ClientError::NoActiveDevice);
+        assert_eq!(
+            ClientError::classify(&anyhow::anyhow!("invalid argument")),
+            ClientError::Other
+        );
+        assert_eq!(
+            ClientError::classify(&rspotify::ClientError::InvalidToken.into()),
+            ClientError::Unauthorized
+        );
+
+        assert_eq!(
+            ClientError::from_status(reqwest::StatusCode::TOO_MANY_REQUESTS, Some(3)),
+            ClientError::RateLimited {
+                retry_after_in_secs: Some(3)
+            }
+        );
+        assert_eq!(
+            ClientError::from_status(reqwest::StatusCode::NOT_FOUND, None),
+            ClientError::NotFound
+        );
+        assert_eq!(
+            ClientError::from_status(reqwest::StatusCode::BAD_GATEWAY, None),
+            ClientError::Server(reqwest::StatusCode::BAD_GATEWAY)
+        );
+        assert_eq!(
+            ClientError::from_status(reqwest::StatusCode::FORBIDDEN, None),
+            ClientError::Status(reqwest::StatusCode::FORBIDDEN)
+        );
+    }
+
+    #[test]
+    fn only_network_and_server_errors_are_transient() {
+        assert!(ClientError::Network.is_transient());
+        assert!(ClientError::Server(reqwest::StatusCode::SERVICE_UNAVAILABLE).is_transient());
+        assert!(!ClientError::Unauthorized.is_transient());
+        assert!(!ClientError::NoActiveDevice.is_transient());
+        assert!(!ClientError::RateLimited {
+            retry_after_in_secs: None
+        }
+        .is_transient());
+        assert!(!ClientError::Other.is_transient());
+    }
+}
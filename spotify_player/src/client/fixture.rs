@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::state::*;
+
+use super::SpotifyApi;
+
+/// the maximum length of a fixture file's name without the extension,
+/// which keeps the name within the usual 255-byte limit of file systems
+const MAX_FIXTURE_NAME_LENGTH: usize = 200;
+
+/// gets the path of a fixture file inside a fixture folder.
+///
+/// The key is percent-encoded into the file's name, so that distinct keys
+/// (e.g. search queries) are always stored into distinct files. A long name is
+/// truncated and suffixed with the key's hash to keep the names distinct.
+fn fixture_path(folder: &Path, key: &str) -> PathBuf {
+    let mut name = key
+        .bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => char::from(b).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect::<String>();
+    if name.len() > MAX_FIXTURE_NAME_LENGTH {
+        let digest = ring::digest::digest(&ring::digest::SHA256, key.as_bytes());
+        let hash = digest.as_ref()[..8]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+        // the encoded name only consists of ASCII characters
+        name.truncate(MAX_FIXTURE_NAME_LENGTH - hash.len() - 1);
+        name = format!("{name}-{hash}");
+    }
+    folder.join(format!("{name}.json"))
+}
+
+fn write_fixture<T: Serialize>(folder: &Path, key: &str, value: &T) -> Result<()> {
+    std::fs::create_dir_all(folder).context("failed to create the fixture folder")?;
+    let f = std::fs::File::create(fixture_path(folder, key))?;
+    serde_json::to_writer_pretty(std::io::BufWriter::new(f), value)?;
+    Ok(())
+}
+
+fn read_fixture<T: DeserializeOwned>(folder: &Path, key: &str) -> Result<T> {
+    let path = fixture_path(folder, key);
+    let f = std::fs::File::open(&path)
+        .with_context(|| format!("no recorded response for {key} ({})", path.display()))?;
+    Ok(serde_json::from_reader(std::io::BufReader::new(f))?)
+}
+
+/// A `SpotifyApi` recording the responses of another API into fixture files,
+/// which can be replayed later by a `ReplaySpotifyApi`
+pub struct RecordingSpotifyApi<A> {
+    api: A,
+    folder: PathBuf,
+}
+
+impl<A: SpotifyApi> RecordingSpotifyApi<A> {
+    pub fn new(api: A, folder: PathBuf) -> Self {
+        Self { api, folder }
+    }
+
+    fn record<T: Serialize>(&self, key: &str, result: Result<T>) -> Result<T> {
+        let value = result?;
+        if let Err(err) = write_fixture(&self.folder, key, &value) {
+            tracing::error!("Failed to record the response for {key}: {err:#}");
+        }
+        Ok(value)
+    }
+}
+
+#[async_trait::async_trait]
+impl<A: SpotifyApi> SpotifyApi for RecordingSpotifyApi<A> {
+    fn is_offline(&self) -> bool {
+        self.api.is_offline()
+    }
+
+    async fn current_user_playlists(&self) -> Result<Vec<Playlist>> {
+        self.record("playlists", self.api.current_user_playlists().await)
+    }
+
+    async fn current_user_saved_albums(&self) -> Result<Vec<Album>> {
+        self.record("saved_albums", self.api.current_user_saved_albums().await)
+    }
+
+    async fn current_user_followed_artists(&self) -> Result<Vec<Artist>> {
+        self.record(
+            "followed_artists",
+            self.api.current_user_followed_artists().await,
+        )
+    }
+
+    async fn current_user_saved_tracks(&self) -> Result<Vec<Track>> {
+        self.record("saved_tracks", self.api.current_user_saved_tracks().await)
+    }
+
+    async fn current_user_top_tracks(&self) -> Result<Vec<Track>> {
+        self.record("top_tracks", self.api.current_user_top_tracks().await)
+    }
+
+    async fn current_user_recently_played_tracks(&self) -> Result<Vec<Track>> {
+        self.record(
+            "recently_played_tracks",
+            self.api.current_user_recently_played_tracks().await,
+        )
+    }
+
+    async fn context(&self, state: &SharedState, id: ContextId) -> Result<Context> {
+        let key = format!("context {}", id.uri());
+        self.record(&key, self.api.context(state, id).await)
+    }
+
+    async fn search(&self, query: &str) -> Result<SearchResults> {
+        self.record(&format!("search {query}"), self.api.search(query).await)
+    }
+}
+
+/// A `SpotifyApi` replaying the responses recorded by a `RecordingSpotifyApi`,
+/// which allows running the application deterministically without connecting to Spotify
+pub struct ReplaySpotifyApi {
+    folder: PathBuf,
+}
+
+impl ReplaySpotifyApi {
+    pub fn new(folder: PathBuf) -> Self {
+        Self { folder }
+    }
+}
+
+#[async_trait::async_trait]
+impl SpotifyApi for ReplaySpotifyApi {
+    fn is_offline(&self) -> bool {
+        true
+    }
+
+    async fn current_user_playlists(&self) -> Result<Vec<Playlist>> {
+        read_fixture(&self.folder, "playlists")
+    }
+
+    async fn current_user_saved_albums(&self) -> Result<Vec<Album>> {
+        read_fixture(&self.folder, "saved_albums")
+    }
+
+    async fn current_user_followed_artists(&self) -> Result<Vec<Artist>> {
+        read_fixture(&self.folder, "followed_artists")
+    }
+
+    async fn current_user_saved_tracks(&self) -> Result<Vec<Track>> {
+        read_fixture(&self.folder, "saved_tracks")
+    }
+
+    async fn current_user_top_tracks(&self) -> Result<Vec<Track>> {
+        read_fixture(&self.folder, "top_tracks")
+    }
+
+    async fn current_user_recently_played_tracks(&self) -> Result<Vec<Track>> {
+        read_fixture(&self.folder, "recently_played_tracks")
+    }
+
+    async fn context(&self, _state: &SharedState, id: ContextId) -> Result<Context> {
+        read_fixture(&self.folder, &format!("context {}", id.uri()))
+    }
+
+    async fn search(&self, query: &str) -> Result<SearchResults> {
+        read_fixture(&self.folder, &format!("search {query}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_paths_are_distinct_for_distinct_keys() {
+        let folder = Path::new("fixtures");
+        let keys = [
+            "search a b",
+            "search a_b",
+            "search a%20b",
+            "search a/b",
+            "search ä",
+            "context spotify:playlist:abc",
+        ];
+        let paths = keys
+            .iter()
+            .map(|key| fixture_path(folder, key))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(paths.len(), keys.len());
+
+        assert_eq!(
+            fixture_path(folder, "context spotify:playlist:abc"),
+            folder.join("context%20spotify%3Aplaylist%3Aabc.json")
+        );
+        // a key can't escape the fixture folder
+        assert_eq!(fixture_path(folder, "../x"), folder.join("%2E%2E%2Fx.json"));
+    }
+
+    #[test]
+    fn long_fixture_names_are_truncated_and_hashed() {
+        let folder = Path::new("fixtures");
+        let query = "ä".repeat(200);
+        let paths = [
+            fixture_path(folder, &format!("search {query}")),
+            fixture_path(folder, &format!("search {query}a")),
+        ];
+        for path in &paths {
+            let name = path.file_name().unwrap().to_str().unwrap();
+            assert_eq!(name.len(), MAX_FIXTURE_NAME_LENGTH + ".json".len());
+        }
+        assert_ne!(paths[0], paths[1]);
+    }
+
+    #[test]
+    fn fixtures_round_trip() {
+        let folder = std::env::temp_dir().join(format!(
+            "spotify-player-fixtures-test-{}",
+            std::process::id()
+        ));
+        write_fixture(&folder, "search a/b", &vec!["x".to_string()]).unwrap();
+        let value: Vec<String> = read_fixture(&folder, "search a/b").unwrap();
+        assert_eq!(value, ["x"]);
+        assert!(read_fixture::<Vec<String>>(&folder, "search a_b").is_err());
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
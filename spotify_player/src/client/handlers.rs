@@ -1,5 +1,8 @@
-use anyhow::Context;
-use rspotify::model::PlayableItem;
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Context as _;
+use rand::Rng;
+use rspotify::{clients::BaseClient, model::PlayableItem};
 use tracing::Instrument;
 
 use crate::{config, state::*};
@@ -7,44 +10,300 @@ use crate::{config, state::*};
 #[cfg(feature = "lyric-finder")]
 use crate::utils::map_join;
 
-use super::ClientRequest;
+use super::{ClientError, ClientRequest};
 
 struct PlayerEventHandlerState {
     add_track_to_queue_req_timer: std::time::Instant,
 }
 
+/// the delay before the first retry of a client request failed because of a transient error
+const REQUEST_RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// handles a client request, retrying the request with jittered exponential backoff
+/// (up to `client_request_max_attempts` attempts) upon transient failures
+async fn handle_request_with_retries(
+    client: &super::Client,
+    state: &SharedState,
+    request: &ClientRequest,
+) -> anyhow::Result<()> {
+    let max_attempts = config::get_config().app_config.client_request_max_attempts;
+    let mut attempt = 1;
+    loop {
+        let span = tracing::info_span!("client_request_attempt", attempt);
+        match client
+            .handle_request(state, request.clone())
+            .instrument(span)
+            .await
+        {
+            Err(err)
+                if attempt < max_attempts
+                    && request.is_idempotent()
+                    && ClientError::classify(&err).is_transient() =>
+            {
+                let delay = request_retry_delay(attempt);
+                tracing::warn!(
+                    "Failed to handle client request (attempt {attempt}/{max_attempts}), retrying in {}ms: {err:#}",
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// gets the jittered delay before retrying a request whose `attempt`-th attempt failed,
+/// which doubles with each attempt
+fn request_retry_delay(attempt: u32) -> std::time::Duration {
+    let delay = REQUEST_RETRY_BASE_DELAY * 2_u32.saturating_pow(attempt.saturating_sub(1));
+    delay.mul_f64(rand::thread_rng().gen_range(0.5..1.5))
+}
+
+/// A guard removing a fetch request's key from the in-flight requests when dropped,
+/// including when the request's task is aborted
+struct InFlightRequestGuard {
+    in_flight_requests: Arc<Mutex<HashSet<String>>>,
+    key: String,
+}
+
+impl Drop for InFlightRequestGuard {
+    fn drop(&mut self) {
+        self.in_flight_requests.lock().remove(&self.key);
+    }
+}
+
 /// starts the client's request handler
 pub async fn start_client_handler(
     state: SharedState,
     client: super::Client,
     client_sub: flume::Receiver<ClientRequest>,
 ) {
+    // the keys of the fetch requests being handled, an identical fetch request received
+    // while another one is in-flight is skipped as both update the same application's state
+    let in_flight_requests = Arc::new(Mutex::new(HashSet::new()));
+    // the task fetching a context's data, which is aborted upon fetching another context
+    // (e.g. the user navigates to another context page while a large playlist is being fetched)
+    let mut context_fetch_task: Option<tokio::task::JoinHandle<()>> = None;
+
     while let Ok(request) = client_sub.recv_async().await {
+        // requests for the user's data served by an offline data source (e.g. mock data)
+        // are handled as usual in offline mode
+        if state.is_offline() && !client.is_served_offline(&request) {
+            handle_offline_request(&client, &state, request);
+            continue;
+        }
+
+        // pause outgoing requests while being rate limited by Spotify API
+        let rate_limited_until = state.diagnostics.lock().rate_limited_until;
+        if let Some(until) = rate_limited_until {
+            if until > std::time::Instant::now() {
+                tracing::info!("Rate limited by Spotify API, pausing outgoing requests...");
+                tokio::time::sleep_until(until.into()).await;
+            }
+        }
+
+        // block outgoing requests while Spotify API is unreachable,
+        // the UI keeps serving the data cached in memory
+        if !state.diagnostics.lock().try_send_request() {
+            tracing::warn!("Spotify API is unreachable, skipped the client request: {request:?}");
+            state
+                .ui
+                .lock()
+                .report_error("Spotify unreachable, please try again later".to_string());
+            state.mark_changed();
+            continue;
+        }
+
         if let Err(err) = client.check_valid_session(&state).await {
             tracing::error!("{err:#}");
             continue;
         }
 
+        state.diagnostics.lock().pending_requests = client_sub.len();
+
+        let in_flight_guard = match request.dedup_key() {
+            Some(key) => {
+                if !in_flight_requests.lock().insert(key.clone()) {
+                    tracing::debug!("Skipped a duplicate of an in-flight request: {key}");
+                    continue;
+                }
+                Some(InFlightRequestGuard {
+                    in_flight_requests: in_flight_requests.clone(),
+                    key,
+                })
+            }
+            None => None,
+        };
+
+        let is_context_fetch = matches!(request, ClientRequest::GetContext(_));
+        if is_context_fetch {
+            if let Some(task) = context_fetch_task.take() {
+                if !task.is_finished() {
+                    tracing::info!("Aborted the in-flight context fetch");
+                    task.abort();
+                }
+            }
+        }
+
         let state = state.clone();
         let client = client.clone();
         let span = tracing::info_span!("client_request", request = ?request);
 
-        tokio::task::spawn(
+        let task = tokio::task::spawn(
             async move {
-                if let Err(err) = client.handle_request(&state, request).await {
+                // the request's variant name without its data
+                let request_name = format!("{request:?}")
+                    .split(['(', ' '])
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                let timer = std::time::Instant::now();
+
+                let mut result = handle_request_with_retries(&client, &state, &request).await;
+                // refresh the access token and retry the request once upon an unauthorized response
+                if result
+                    .as_ref()
+                    .is_err_and(|err| ClientError::classify(err) == ClientError::Unauthorized)
+                {
+                    tracing::warn!("Got an unauthorized response, refreshing the access token and retrying the request...");
+                    match client.refresh_token().await {
+                        Ok(()) => {
+                            result = handle_request_with_retries(&client, &state, &request).await
+                        }
+                        Err(err) => tracing::error!("Failed to refresh the access token: {err:#}"),
+                    }
+                }
+
+                drop(in_flight_guard);
+
+                let token_expires_at = client.token_expires_at().await;
+                let mut diagnostics = state.diagnostics.lock();
+                diagnostics.token_expires_at = token_expires_at;
+                diagnostics.record_request(RequestStats {
+                    request: request_name.clone(),
+                    latency: timer.elapsed(),
+                    succeeded: result.is_ok(),
+                });
+                if let Err(err) = result {
+                    let class = ClientError::classify(&err);
+                    if let ClientError::RateLimited {
+                        retry_after_in_secs,
+                    } = class
+                    {
+                        diagnostics.record_rate_limited_response(retry_after_in_secs);
+                    }
+                    if class.is_transient() {
+                        diagnostics.record_transient_failure();
+                    }
                     tracing::error!("Failed to handle client request: {err:#}");
+                    drop(diagnostics);
+
+                    // show the error's class instead of the raw error if possible
+                    let message = match class {
+                        ClientError::Other => err.to_string(),
+                        class => class.to_string(),
+                    };
+                    state
+                        .ui
+                        .lock()
+                        .report_error(format!("{request_name} failed: {message}"));
+                } else {
+                    drop(diagnostics);
                 }
+
+                state.mark_changed();
             }
             .instrument(span),
         );
+        if is_context_fetch {
+            context_fetch_task = Some(task);
+        }
     }
 }
 
+/// handles a client request in offline mode: fetch requests are served from the data stored
+/// in the cache folder (if any), other requests are queued to be handled once reconnected
+fn handle_offline_request(client: &super::Client, state: &SharedState, request: ClientRequest) {
+    match request {
+        ClientRequest::GetContext(id) => {
+            let uri = id.uri();
+            if state.data.read().caches.context.contains_key(&uri) {
+                return;
+            }
+            match load_context_from_file_cache(&config::get_config().cache_folder, &uri) {
+                Some(context) => state.data.write().insert_context(&id, context),
+                None => state
+                    .ui
+                    .lock()
+                    .report_error(format!("Offline: {uri} is not available in the cache")),
+            }
+        }
+        ClientRequest::GetUserSavedTracks => {
+            let mut data = state.data.write();
+            let mut tracks = data
+                .user_data
+                .saved_tracks
+                .values()
+                .cloned()
+                .collect::<Vec<_>>();
+            tracks.sort_by(|x, y| x.name.cmp(&y.name));
+            data.caches.context.insert(
+                USER_LIKED_TRACKS_ID.uri.to_owned(),
+                Context::Tracks {
+                    tracks,
+                    desc: "User's liked tracks".to_string(),
+                },
+                *TTL_CACHE_DURATION,
+            );
+        }
+        request if request.is_fetch() => {
+            tracing::debug!("Skipped a fetch request in offline mode: {request:?}");
+            return;
+        }
+        request => {
+            tracing::info!("Queued a client request received in offline mode: {request:?}");
+            state
+                .ui
+                .lock()
+                .report_error("Offline: the request will be sent once reconnected".to_string());
+            client.offline_requests.push(request);
+        }
+    }
+    state.mark_changed();
+}
+
+/// replays the client requests queued in offline mode (if any)
+pub(super) fn replay_offline_requests(client: &super::Client, state: &SharedState) {
+    let requests = client.offline_requests.take();
+    if requests.is_empty() {
+        return;
+    }
+
+    tracing::info!(
+        "Replaying {} client requests received in offline mode",
+        requests.len()
+    );
+    tokio::task::spawn({
+        let client = client.clone();
+        let state = state.clone();
+        async move {
+            for request in requests {
+                if let Err(err) = handle_request_with_retries(&client, &state, &request).await {
+                    tracing::error!("Failed to handle client request: {err:#}");
+                }
+            }
+            state.mark_changed();
+        }
+    });
+}
+
 fn handle_playback_change_event(
     state: &SharedState,
     client_pub: &flume::Sender<ClientRequest>,
     handler_state: &mut PlayerEventHandlerState,
 ) -> anyhow::Result<()> {
+    let is_visualizer_pane_visible = state.ui.lock().is_visualizer_pane_visible;
     let player = state.player.read();
     let (playback, track) = match (
         player.buffered_playback.as_ref(),
@@ -72,6 +331,22 @@ fn handle_playback_change_event(
         client_pub.send(ClientRequest::GetCurrentUserQueue)?;
     }
 
+    // the visualizer's spectrum is approximated from the track's audio analysis
+    // unless the track is played by the integrated player
+    if is_visualizer_pane_visible && crate::visualizer::played_spectrum().is_none() {
+        if let Some(id) = &track.id {
+            if !state
+                .data
+                .read()
+                .caches
+                .audio_analysis
+                .contains_key(&id.uri())
+            {
+                client_pub.send(ClientRequest::GetAudioAnalysis(id.clone()))?;
+            }
+        }
+    }
+
     // handle fake track repeat mode
     if playback.fake_track_repeat_state {
         if let Some(progress) = player.playback_progress() {
@@ -99,7 +374,15 @@ fn handle_page_change_event(
     state: &SharedState,
     client_pub: &flume::Sender<ClientRequest>,
 ) -> anyhow::Result<()> {
-    match state.ui.lock().current_page_mut() {
+    let playing_context_id = state.player.read().playing_context_id();
+    let mut ui = state.ui.lock();
+    // the context of the context page (if any), whose data is read after releasing the UI lock
+    let mut context_id = None;
+    // the last browsed context to be stored after releasing the UI lock
+    let mut last_context = None;
+    let mut is_context_changed = false;
+
+    match ui.current_page_mut() {
         PageState::Context {
             id,
             context_page_type,
@@ -107,7 +390,7 @@ fn handle_page_change_event(
         } => {
             let expected_id = match context_page_type {
                 ContextPageType::Browsing(context_id) => Some(context_id.clone()),
-                ContextPageType::CurrentPlaying => state.player.read().playing_context_id(),
+                ContextPageType::CurrentPlaying => playing_context_id,
             };
 
             // update the context state and request new data when moving to a new context page
@@ -115,6 +398,15 @@ fn handle_page_change_event(
                 tracing::info!("Current context ID ({:?}) is different from the expected ID ({:?}), update the context state", id, expected_id);
 
                 *id = expected_id;
+                is_context_changed = true;
+                state.mark_changed();
+
+                // remember the last browsed context to be re-opened by the `LastContext` start page
+                if let (ContextPageType::Browsing(_), Some(id)) = (&context_page_type, &id) {
+                    if !matches!(id, ContextId::Tracks(_)) {
+                        last_context = Some(id.uri());
+                    }
+                }
 
                 // update the UI page state based on the context's type
                 match id {
@@ -132,14 +424,7 @@ fn handle_page_change_event(
                 }
             }
 
-            // request new context's data if not found in memory
-            if let Some(id) = id {
-                if !matches!(id, ContextId::Tracks(_))
-                    && !state.data.read().caches.context.contains_key(&id.uri())
-                {
-                    client_pub.send(ClientRequest::GetContext(id.clone()))?;
-                }
-            }
+            context_id = id.clone();
         }
 
         #[cfg(feature = "lyric-finder")]
@@ -154,6 +439,7 @@ fn handle_page_change_event(
                     track.clone_from(&current_track.name);
                     *artists = map_join(&current_track.artists, |a| &a.name, ", ");
                     *scroll_offset = 0;
+                    state.mark_changed();
 
                     client_pub.send(ClientRequest::GetLyric {
                         track: track.clone(),
@@ -165,6 +451,54 @@ fn handle_page_change_event(
         _ => {}
     }
 
+    drop(ui);
+
+    if let Some(id) = context_id {
+        // request new context's data if not found in memory
+        if !matches!(id, ContextId::Tracks(_))
+            && !state.data.read().caches.context.contains_key(&id.uri())
+        {
+            client_pub.send(ClientRequest::GetContext(id.clone()))?;
+        }
+
+        // restore the last filter of the new context page (if any)
+        let restored_filter = if is_context_changed {
+            state
+                .data
+                .read()
+                .user_data
+                .context_presets
+                .get(&id.uri())
+                .and_then(|preset| preset.filter.clone())
+        } else {
+            None
+        };
+        if let Some(query) = restored_filter {
+            let mut ui = state.ui.lock();
+            // the page may be changed while the UI lock is released
+            let is_same_page =
+                matches!(ui.current_page(), PageState::Context { id: Some(i), .. } if *i == id);
+            if is_same_page && ui.popup.is_none() {
+                ui.popup = Some(PopupState::Search { query });
+            }
+        }
+    }
+
+    // the presets changed in the previous context (e.g. by sorting its tracks) are persisted
+    // when moving to another context
+    if is_context_changed {
+        state.flush_context_presets();
+    }
+    if let Some(uri) = last_context {
+        if let Err(err) = store_data_into_file_cache(
+            FileCacheKey::LastContext,
+            &config::get_config().cache_folder,
+            &uri,
+        ) {
+            tracing::error!("Failed to store the last context into cache: {err:#}");
+        }
+    }
+
     Ok(())
 }
 
@@ -180,6 +514,236 @@ fn handle_player_event(
     Ok(())
 }
 
+/// the maximum exponent of the backoff multiplier of the playback polling interval
+const MAX_POLLING_BACKOFF_EXPONENT: u32 = 5;
+/// the multiplier of the playback polling interval while the playback is paused
+const PAUSED_POLLING_MULTIPLIER: u32 = 4;
+/// the delay after the current track's end before polling the playback,
+/// which gives Spotify some time to switch to the next track
+const TRACK_END_POLLING_MARGIN: std::time::Duration = std::time::Duration::from_millis(500);
+/// the interval to check if something starts playing while playback polling is suspended
+const IDLE_PLAYBACK_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// gets the delay before the next playback poll based on the playback's state, which is
+/// - shorter than `interval` if the current track ends before the next regular poll
+/// - longer than `interval` if the playback is paused
+/// - `None` if nothing is playing, during which polling is suspended
+fn playback_polling_delay(
+    state: &SharedState,
+    interval: std::time::Duration,
+) -> Option<std::time::Duration> {
+    let player = state.player.read();
+    let playback = player.playback.as_ref()?;
+    if !playback.is_playing {
+        return Some(interval * PAUSED_POLLING_MULTIPLIER);
+    }
+
+    let remaining = match (player.current_playing_track(), player.playback_progress()) {
+        (Some(track), Some(progress)) => (track.duration - progress).to_std().ok(),
+        _ => None,
+    };
+    Some(match remaining {
+        Some(remaining) if remaining < interval => remaining + TRACK_END_POLLING_MARGIN,
+        _ => interval,
+    })
+}
+
+/// the duration before the access token's expiry to refresh the token
+const TOKEN_REFRESH_MARGIN_IN_SECS: i64 = 60;
+/// the initial and the maximum delays between retries of a failed token refresh
+const TOKEN_REFRESH_RETRY_DELAYS_IN_SECS: (u64, u64) = (5, 300);
+
+/// Starts a task that refreshes the access token shortly before it expires,
+/// retrying with exponential backoff if the refresh fails
+pub async fn start_token_refresher(client: super::Client) {
+    let mut retry_delay = std::time::Duration::from_secs(TOKEN_REFRESH_RETRY_DELAYS_IN_SECS.0);
+    loop {
+        let expires_at = client.token_expires_at().await;
+        let wait_duration = expires_at
+            .map(|t| t - chrono::Duration::try_seconds(TOKEN_REFRESH_MARGIN_IN_SECS).unwrap())
+            .and_then(|t| (t - chrono::Utc::now()).to_std().ok())
+            .unwrap_or_default();
+        tokio::time::sleep(wait_duration).await;
+
+        tracing::info!("Refreshing the access token before it expires (expires_at={expires_at:?})");
+        // a failed refresh keeps the old token, so the refresh is considered
+        // successful only if the token's expiry time is updated
+        let refreshed = match client.refresh_token().await {
+            Ok(()) => client.token_expires_at().await != expires_at,
+            Err(err) => {
+                tracing::error!("Failed to refresh the access token: {err:#}");
+                false
+            }
+        };
+        if refreshed {
+            retry_delay = std::time::Duration::from_secs(TOKEN_REFRESH_RETRY_DELAYS_IN_SECS.0);
+        } else {
+            tracing::warn!("Failed to refresh the access token, retrying in {retry_delay:?}");
+            tokio::time::sleep(retry_delay).await;
+            retry_delay = std::cmp::min(
+                retry_delay * 2,
+                std::time::Duration::from_secs(TOKEN_REFRESH_RETRY_DELAYS_IN_SECS.1),
+            );
+        }
+    }
+}
+
+/// the delay to coalesce a burst of Spotify Connect notifications into a single playback update
+const CONNECT_NOTIFICATION_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(300);
+/// the delay before re-subscribing to Spotify Connect notifications after the subscription ends
+const CONNECT_RESUBSCRIBE_DELAY: std::time::Duration = std::time::Duration::from_secs(10);
+/// the playback polling interval while the playback is updated upon Spotify Connect notifications,
+/// which catches changes whose notifications are missed
+const CONNECT_FALLBACK_POLLING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Starts a watcher subscribing to the user's Spotify Connect notifications, which are pushed
+/// whenever the playback changes on any device (track changes, pause/resume, volume changes, etc),
+/// so that the playback is updated instantly without polling
+pub async fn start_connect_notification_watcher(
+    client: super::Client,
+    state: SharedState,
+    client_pub: flume::Sender<ClientRequest>,
+) {
+    fn url_encode(s: &str) -> String {
+        s.bytes()
+            .map(|b| match b {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'*' => {
+                    (b as char).to_string()
+                }
+                b' ' => "+".to_string(),
+                b => format!("%{b:02X}"),
+            })
+            .collect()
+    }
+
+    loop {
+        // a subscription requires a session, which is not available in offline mode
+        let Some(session) = client.try_session().await else {
+            tokio::time::sleep(CONNECT_RESUBSCRIBE_DELAY).await;
+            continue;
+        };
+
+        let uri = format!("hm://remote/user/{}/", url_encode(&session.username()));
+        match session.mercury().subscribe(uri).await {
+            Ok(mut notifications) => {
+                tracing::info!("Subscribed to Spotify Connect notifications");
+                state.diagnostics.lock().is_connect_subscribed = true;
+
+                while notifications.recv().await.is_some() {
+                    // a single playback change usually results in multiple notifications
+                    tokio::time::sleep(CONNECT_NOTIFICATION_DEBOUNCE).await;
+                    while notifications.try_recv().is_ok() {}
+
+                    client_pub
+                        .send_async(ClientRequest::GetCurrentPlayback)
+                        .await
+                        .unwrap_or_default();
+                    if state.ui.lock().is_queue_pane_visible {
+                        client_pub
+                            .send_async(ClientRequest::GetCurrentUserQueue)
+                            .await
+                            .unwrap_or_default();
+                    }
+                }
+
+                // the subscription ends when the session is closed (e.g. replaced by a new session)
+                tracing::warn!("Spotify Connect notifications' subscription ended");
+                state.diagnostics.lock().is_connect_subscribed = false;
+            }
+            Err(err) => {
+                tracing::warn!("Failed to subscribe to Spotify Connect notifications: {err:?}");
+            }
+        }
+        tokio::time::sleep(CONNECT_RESUBSCRIBE_DELAY).await;
+    }
+}
+
+/// the duration the selection must stay unchanged before prefetching the selected contexts
+const PREFETCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// gets the contexts likely to be opened next based on the current page's selection:
+/// - the selected playlist in the library page
+/// - the selected album and its neighbours in an artist page
+fn prefetch_candidates(state: &SharedState) -> Vec<ContextId> {
+    let ui = state.ui.lock();
+    let data = state.data.read();
+    match ui.current_page() {
+        PageState::Library {
+            state:
+                LibraryPageUIState {
+                    playlist_list,
+                    focus: LibraryFocusState::Playlists,
+                    ..
+                },
+        } => {
+            let playlists = ui.search_filtered_items(&data.user_data.playlists);
+            playlist_list
+                .selected()
+                .and_then(|id| playlists.get(id))
+                .map(|p| vec![ContextId::Playlist(p.id.clone())])
+                .unwrap_or_default()
+        }
+        PageState::Context {
+            id: Some(id),
+            state:
+                Some(ContextPageUIState::Artist {
+                    album_list,
+                    focus: ArtistFocusState::Albums,
+                    ..
+                }),
+            ..
+        } => {
+            let (Some(Context::Artist { albums, .. }), Some(selected)) =
+                (data.caches.context.get(&id.uri()), album_list.selected())
+            else {
+                return vec![];
+            };
+            let albums = ui.search_filtered_items(albums);
+            [selected.checked_sub(1), Some(selected), Some(selected + 1)]
+                .into_iter()
+                .flatten()
+                .filter_map(|id| albums.get(id))
+                .map(|a| ContextId::Album(a.id.clone()))
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Starts a watcher prefetching the contexts likely to be opened next in the background,
+/// the contexts are prefetched once the selection stays unchanged for `PREFETCH_DEBOUNCE`
+pub async fn start_context_prefetcher(
+    state: SharedState,
+    client_pub: flume::Sender<ClientRequest>,
+) {
+    let mut last_candidates = vec![];
+    let mut prefetched = vec![];
+    loop {
+        tokio::time::sleep(PREFETCH_DEBOUNCE).await;
+
+        // don't compete with user requests while outgoing requests are paused or blocked
+        let is_paused = state.is_offline() || {
+            let diagnostics = state.diagnostics.lock();
+            diagnostics.rate_limit_remaining_secs().is_some() || diagnostics.is_api_unreachable()
+        };
+        if is_paused {
+            continue;
+        }
+
+        let candidates = prefetch_candidates(&state);
+        if candidates == last_candidates && candidates != prefetched {
+            for id in &candidates {
+                client_pub
+                    .send_async(ClientRequest::PrefetchContext(id.clone()))
+                    .await
+                    .unwrap_or_default();
+            }
+            prefetched.clone_from(&candidates);
+        }
+        last_candidates = candidates;
+    }
+}
+
 /// Starts multiple event watchers listening to events and
 /// notifying the client to make update requests if needed
 pub async fn start_player_event_watchers(
@@ -196,13 +760,54 @@ pub async fn start_player_event_watchers(
             let playback_refresh_duration = std::time::Duration::from_millis(
                 configs.app_config.playback_refresh_duration_in_ms,
             );
+            let state = state.clone();
             async move {
                 loop {
+                    // skip polling while outgoing requests are paused because of rate limiting,
+                    // blocked because Spotify API is unreachable or in offline mode
+                    let is_paused = state.is_offline() || {
+                        let diagnostics = state.diagnostics.lock();
+                        diagnostics.rate_limit_remaining_secs().is_some()
+                            || diagnostics.is_api_unreachable()
+                    };
+                    if is_paused {
+                        tokio::time::sleep(playback_refresh_duration).await;
+                        continue;
+                    }
+
+                    let Some(delay) = playback_polling_delay(&state, playback_refresh_duration)
+                    else {
+                        // nothing is playing, suspend polling until the playback is
+                        // updated by other events (e.g. user's commands)
+                        tokio::time::sleep(IDLE_PLAYBACK_CHECK_INTERVAL).await;
+                        continue;
+                    };
+                    // poll rarely while the playback is updated upon Spotify Connect notifications
+                    let delay = if state.diagnostics.lock().is_connect_subscribed {
+                        delay.max(CONNECT_FALLBACK_POLLING_INTERVAL)
+                    } else {
+                        delay
+                    };
+                    // back off when client requests keep failing because of network or server
+                    // issues to avoid flooding the logs and Spotify API with failed requests.
+                    // Errors caused by the user's requests (e.g. no active device) don't count.
+                    let n_failures = state.diagnostics.lock().consecutive_transient_failures;
+                    tokio::time::sleep(
+                        delay * 2_u32.pow(n_failures.min(MAX_POLLING_BACKOFF_EXPONENT)),
+                    )
+                    .await;
+
                     client_pub
                         .send_async(ClientRequest::GetCurrentPlayback)
                         .await
                         .unwrap_or_default();
-                    tokio::time::sleep(playback_refresh_duration).await;
+                    // keep the queue pane up-to-date with the playback
+                    if state.ui.lock().is_queue_pane_visible {
+                        client_pub
+                            .send_async(ClientRequest::GetCurrentUserQueue)
+                            .await
+                            .unwrap_or_default();
+                    }
                 }
             }
         });
@@ -215,8 +820,38 @@ pub async fn start_player_event_watchers(
 
     loop {
         tokio::time::sleep(refresh_duration).await;
+
+        // periodically probe Spotify API while it's unreachable to detect when it's healthy again
+        if state.diagnostics.lock().is_probe_due() {
+            tracing::info!("Probing Spotify API...");
+            client_pub
+                .send(ClientRequest::GetCurrentPlayback)
+                .unwrap_or_default();
+        }
+
+        let _enter = tracing::debug_span!("player_event_watcher").entered();
         if let Err(err) = handle_player_event(&state, &client_pub, &mut handler_state) {
             tracing::error!("Encounter error when handling player event: {err:#}");
+            state
+                .ui
+                .lock()
+                .report_error(format!("Failed to handle player event: {err}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_retry_delays_grow_exponentially_with_jitter() {
+        for attempt in 1..=5 {
+            let base = REQUEST_RETRY_BASE_DELAY * 2_u32.pow(attempt - 1);
+            for _ in 0..100 {
+                let delay = request_retry_delay(attempt);
+                assert!(delay >= base / 2 && delay < base * 3 / 2, "{delay:?}");
+            }
         }
     }
 }
@@ -0,0 +1,105 @@
+use crate::{config, state::*, utils::map_join};
+
+#[derive(Debug, Clone, Copy)]
+/// A player event triggering a user-defined hook command
+/// configured under the `[event_hooks]` section
+pub enum HookEvent {
+    TrackChanged,
+    PlaybackStarted,
+    PlaybackStopped,
+    TrackLiked,
+}
+
+impl HookEvent {
+    fn name(self) -> &'static str {
+        match self {
+            Self::TrackChanged => "TrackChanged",
+            Self::PlaybackStarted => "PlaybackStarted",
+            Self::PlaybackStopped => "PlaybackStopped",
+            Self::TrackLiked => "TrackLiked",
+        }
+    }
+
+    fn command(self, hooks: &config::EventHooks) -> Option<&config::Command> {
+        match self {
+            Self::TrackChanged => hooks.track_changed.as_ref(),
+            Self::PlaybackStarted => hooks.playback_started.as_ref(),
+            Self::PlaybackStopped => hooks.playback_stopped.as_ref(),
+            Self::TrackLiked => hooks.track_liked.as_ref(),
+        }
+    }
+}
+
+/// The metadata of a track passed to hook commands via environment variables
+pub struct HookTrack {
+    uri: String,
+    name: String,
+    artists: String,
+    album: String,
+    duration_ms: i64,
+}
+
+impl From<&rspotify_model::FullTrack> for HookTrack {
+    fn from(track: &rspotify_model::FullTrack) -> Self {
+        Self {
+            uri: track.id.as_ref().map(|id| id.uri()).unwrap_or_default(),
+            name: track.name.clone(),
+            artists: map_join(&track.artists, |a| &a.name, ", "),
+            album: track.album.name.clone(),
+            duration_ms: track.duration.num_milliseconds(),
+        }
+    }
+}
+
+impl From<&Track> for HookTrack {
+    fn from(track: &Track) -> Self {
+        Self {
+            uri: track.uri(),
+            name: track.name.clone(),
+            artists: track.artists_info(),
+            album: track.album_info(),
+            duration_ms: track.duration.as_millis() as i64,
+        }
+    }
+}
+
+/// Run the hook command configured for a player event (if any) in the background.
+///
+/// The event's name and the track's metadata are passed via `SPOTIFY_PLAYER_*` environment variables.
+pub fn run_event_hook(event: HookEvent, track: Option<HookTrack>) {
+    let configs = config::get_config();
+    let Some(cmd) = event.command(&configs.app_config.event_hooks) else {
+        return;
+    };
+
+    let mut command = std::process::Command::new(&cmd.command);
+    command
+        .args(&cmd.args)
+        .env("SPOTIFY_PLAYER_EVENT", event.name());
+    if let Some(track) = track {
+        command
+            .env("SPOTIFY_PLAYER_TRACK_URI", track.uri)
+            .env("SPOTIFY_PLAYER_TRACK_NAME", track.name)
+            .env("SPOTIFY_PLAYER_TRACK_ARTISTS", track.artists)
+            .env("SPOTIFY_PLAYER_TRACK_ALBUM", track.album)
+            .env(
+                "SPOTIFY_PLAYER_TRACK_DURATION_MS",
+                track.duration_ms.to_string(),
+            );
+    }
+
+    tracing::info!("Running the hook command for {event:?} event: {command:?}");
+    tokio::task::spawn_blocking(move || match command.output() {
+        Err(err) => {
+            tracing::warn!("Failed to run the hook command for {event:?} event: {err:#}");
+        }
+        // running the hook command failed, report the command's stderr
+        Ok(output) if !output.status.success() => {
+            tracing::warn!(
+                "Hook command for {event:?} event failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Ok(_) => {}
+    });
+}
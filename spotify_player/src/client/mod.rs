@@ -8,22 +8,57 @@ use std::io::Write;
 
 use anyhow::Context as _;
 use anyhow::Result;
+use futures::StreamExt;
 use librespot_core::session::Session;
 use rspotify::{
     http::Query,
-    model::{FullPlaylist, Market, Page, SimplifiedPlaylist},
+    model::{AdditionalType, Market, Page, SimplifiedPlaylist},
     prelude::*,
 };
 
+mod api;
+mod error;
+mod fixture;
 mod handlers;
+mod hooks;
+mod partial;
 mod request;
 mod spotify;
 
+pub use api::*;
+pub use error::ClientError;
+pub use fixture::*;
 pub use handlers::*;
+use hooks::{run_event_hook, HookEvent};
+use partial::*;
 pub use request::*;
 use serde::Deserialize;
+pub use spotify::{ENCRYPTED_TOKEN_CACHE_FILE, TOKEN_CACHE_FILE};
 
 const SPOTIFY_API_ENDPOINT: &str = "https://api.spotify.com/v1";
+/// the maximum number of requests sent concurrently when fetching paginated or batched data
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+/// the interval between two consecutive attempts to connect to Spotify in offline mode
+const OFFLINE_RECONNECT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+/// the maximum number of responses cached for conditional requests
+const MAX_ETAG_CACHE_ENTRIES: usize = 64;
+/// the maximum number of IDs accepted by a batch lookup endpoint (e.g. `/tracks?ids=`)
+const MAX_BATCH_SIZE: usize = 50;
+/// the duration an idle connection is kept in the HTTP client's connection pool
+const HTTP_POOL_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(90);
+/// the interval of TCP keep-alive probes sent on the HTTP client's connections
+const HTTP_TCP_KEEPALIVE: std::time::Duration = std::time::Duration::from_secs(60);
+/// the maximum duration to establish a connection to a server
+const HTTP_CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// the maximum duration of a request, from sending the request until its response's body is received
+const HTTP_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+/// the maximum number of prefetched playlists' first pages kept in memory
+const MAX_PREFETCHED_PLAYLISTS: usize = 16;
+/// the duration a prefetched playlist's first page is kept in memory
+const PREFETCHED_PLAYLIST_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// A playlist's data along with the first page of its tracks
+type PrefetchedPlaylist = (Playlist, Page<PartialPlaylistItem>);
 
 /// The application's Spotify client
 #[derive(Clone)]
@@ -31,6 +66,14 @@ pub struct Client {
     http: reqwest::Client,
     spotify: Arc<spotify::Spotify>,
     auth_config: AuthConfig,
+    /// the `ETag` values and bodies of the responses of `http_get` requests, keyed by the requests' URLs
+    etag_cache: Arc<Mutex<ttl_cache::TtlCache<String, (String, String)>>>,
+    /// the data and first pages of tracks of prefetched playlists, keyed by the playlists' URIs
+    prefetched_playlists: Arc<Mutex<ttl_cache::TtlCache<String, PrefetchedPlaylist>>>,
+    /// the requests received in offline mode, which are handled once reconnected to Spotify
+    offline_requests: Arc<OfflineQueue>,
+    /// the alternative source of the user's data, contexts and search results (if any)
+    data_source: Option<Arc<dyn SpotifyApi>>,
     #[cfg(feature = "streaming")]
     stream_conn: Arc<Mutex<Option<librespot_connect::spirc::Spirc>>>,
 }
@@ -42,23 +85,79 @@ impl Deref for Client {
     }
 }
 
+/// Construct the HTTP client shared by all requests sent by the application outside `rspotify`.
+///
+/// The client is kept for the application's lifetime (the access token is attached to each request),
+/// so its connections are reused across token refreshes. Responses are requested compressed,
+/// and a request exceeding `HTTP_REQUEST_TIMEOUT` fails instead of hanging.
+fn new_http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .gzip(true)
+        .brotli(true)
+        .pool_idle_timeout(HTTP_POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(HTTP_TCP_KEEPALIVE)
+        .connect_timeout(HTTP_CONNECT_TIMEOUT)
+        .timeout(HTTP_REQUEST_TIMEOUT)
+        .http2_adaptive_window(true)
+        .build()
+        .expect("build the HTTP client")
+}
+
 fn market_query() -> Query<'static> {
     Query::from([("market", "from_token")])
 }
 
+fn playlist_tracks_query() -> Query<'static> {
+    Query::from([
+        ("market", "from_token"),
+        ("fields", PLAYLIST_TRACKS_PAGE_FIELDS),
+        ("additional_types", "track,episode"),
+    ])
+}
+
 impl Client {
-    /// Construct a new client
-    pub fn new(session: Session, auth_config: AuthConfig, client_id: String) -> Self {
+    /// Construct a new client, `session` is `None` if the application starts in offline mode
+    pub fn new(session: Option<Session>, auth_config: AuthConfig, client_id: String) -> Self {
         Self {
             spotify: Arc::new(spotify::Spotify::new(session, client_id)),
-            http: reqwest::Client::new(),
+            http: new_http_client(),
             auth_config,
+            etag_cache: Arc::new(Mutex::new(ttl_cache::TtlCache::new(MAX_ETAG_CACHE_ENTRIES))),
+            prefetched_playlists: Arc::new(Mutex::new(ttl_cache::TtlCache::new(
+                MAX_PREFETCHED_PLAYLISTS,
+            ))),
+            offline_requests: Arc::default(),
+            data_source: None,
 
             #[cfg(feature = "streaming")]
             stream_conn: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Serve the user's data, contexts and search results from an alternative data source
+    pub fn with_data_source(mut self, data_source: DataSource) -> Self {
+        self.data_source = Some(data_source.into_api(&self));
+        self
+    }
+
+    /// Get the API serving the user's data, contexts and search results
+    fn api(&self) -> &dyn SpotifyApi {
+        self.data_source.as_deref().unwrap_or(self)
+    }
+
+    /// Check if the user's data is served without connecting to Spotify (e.g. mock data),
+    /// in which case the served data isn't stored into the file cache
+    fn is_offline_data_source(&self) -> bool {
+        self.data_source
+            .as_ref()
+            .is_some_and(|api| api.is_offline())
+    }
+
+    /// Check if a request is handled by an offline data source even in offline mode
+    pub fn is_served_offline(&self, request: &ClientRequest) -> bool {
+        self.is_offline_data_source() && request.is_data_source_request()
+    }
+
     /// Create a new client session
     // unused variables:
     // - `state` when the `streaming` feature is not enabled
@@ -88,7 +187,12 @@ impl Client {
 
     /// Check if the current session is valid and if invalid, create a new session
     pub async fn check_valid_session(&self, state: &SharedState) -> Result<()> {
-        if self.session().await.is_invalid() {
+        // no session is created in offline mode or when serving mock or replayed data,
+        // the session is created by `reconnect_when_online` once Spotify is reachable
+        if state.is_offline() || self.is_offline_data_source() {
+            return Ok(());
+        }
+        if self.try_session().await.is_none_or(|s| s.is_invalid()) {
             tracing::info!("Client's current session is invalid, creating a new session...");
             self.new_session(state)
                 .await
@@ -97,6 +201,27 @@ impl Client {
         Ok(())
     }
 
+    /// Try to connect to Spotify periodically until succeeded, then leave the offline mode
+    pub async fn reconnect_when_online(&self, state: &SharedState) {
+        loop {
+            tokio::time::sleep(OFFLINE_RECONNECT_INTERVAL).await;
+            match crate::auth::new_session(&self.auth_config, false).await {
+                Ok(session) => {
+                    *self.session.lock().await = Some(session);
+                    break;
+                }
+                Err(err) => tracing::debug!("Failed to reconnect to Spotify: {err:#}"),
+            }
+        }
+        if let Err(err) = self.refresh_token().await {
+            tracing::error!("Failed to refresh the access token after reconnecting: {err:#}");
+        }
+
+        tracing::info!("Reconnected to Spotify, leaving the offline mode...");
+        state.set_offline(false);
+        handlers::replay_offline_requests(self, state);
+    }
+
     /// Create a new streaming connection
     #[cfg(feature = "streaming")]
     pub async fn new_streaming_connection(&self, state: &SharedState) {
@@ -110,6 +235,29 @@ impl Client {
         *stream_conn = Some(new_conn);
     }
 
+    #[cfg(feature = "streaming")]
+    /// Fade out the volume of the integrated player if it's the playing device
+    async fn fade_out_integrated_player(&self, device_id: Option<&str>) {
+        if let Some(session) = self.try_session().await {
+            if device_id == Some(session.device_id()) {
+                crate::streaming::fade(0.0).await;
+            }
+        }
+    }
+
+    /// Pause the playback, fading out the integrated player's volume beforehand if it's the playing device
+    async fn pause_playback_with_fade(&self, device_id: Option<&str>) -> Result<()> {
+        #[cfg(feature = "streaming")]
+        self.fade_out_integrated_player(device_id).await;
+        let result = self.pause_playback(device_id).await;
+        // the playback continues if it fails to be paused, so the faded out volume is restored
+        #[cfg(feature = "streaming")]
+        if result.is_err() {
+            crate::streaming::fade(1.0).await;
+        }
+        Ok(result?)
+    }
+
     /// Handle a player request, return a new playback metadata on success
     pub async fn handle_player_request(
         &self,
@@ -142,7 +290,7 @@ impl Client {
             _ => {}
         }
 
-        let mut playback = playback.context("no playback found")?;
+        let mut playback = playback.ok_or(ClientError::NoActiveDevice)?;
         let device_id = playback.device_id.as_deref();
 
         match request {
@@ -157,7 +305,7 @@ impl Client {
 
             PlayerRequest::Pause => {
                 if playback.is_playing {
-                    self.pause_playback(device_id).await?;
+                    self.pause_playback_with_fade(device_id).await?;
                     playback.is_playing = false;
                 }
             }
@@ -165,7 +313,7 @@ impl Client {
                 if !playback.is_playing {
                     self.resume_playback(device_id, None).await?
                 } else {
-                    self.pause_playback(device_id).await?
+                    self.pause_playback_with_fade(device_id).await?
                 }
                 playback.is_playing = !playback.is_playing;
             }
@@ -246,7 +394,10 @@ impl Client {
                 let client = lyric_finder::Client::from_http_client(&self.http);
                 let query = format!("{track} {artists}");
 
-                if !state.data.read().caches.lyrics.contains_key(&query) {
+                if !state.record_cache_lookup(
+                    "lyrics",
+                    state.data.read().caches.lyrics.contains_key(&query),
+                ) {
                     let result = client.get_lyric(&query).await.context(format!(
                         "failed to get lyric for track {track} - artists {artists}"
                     ))?;
@@ -259,6 +410,62 @@ impl Client {
                         .insert(query, result, *TTL_CACHE_DURATION);
                 }
             }
+            ClientRequest::GetTrackDetails(track_id) => {
+                let key = track_id.uri();
+                if !state.record_cache_lookup(
+                    "track_details",
+                    state.data.read().caches.track_details.contains_key(&key),
+                ) {
+                    let track = self
+                        .spotify
+                        .track(track_id.clone(), Some(Market::FromToken))
+                        .await
+                        .context(format!("failed to get track {key}"))?;
+                    // the audio features endpoint may be unavailable for some applications,
+                    // so a failure there should not prevent the other details from showing
+                    let features = match self.spotify.track_features(track_id).await {
+                        Ok(features) => Some(features),
+                        Err(err) => {
+                            tracing::warn!("failed to get audio features for track {key}: {err:#}");
+                            None
+                        }
+                    };
+                    let details = TrackDetails {
+                        album: track.album.name,
+                        release_date: track.album.release_date.unwrap_or_default(),
+                        popularity: track.popularity,
+                        features,
+                    };
+
+                    state.data.write().caches.track_details.insert(
+                        key,
+                        details,
+                        *TTL_CACHE_DURATION,
+                    );
+                }
+            }
+            ClientRequest::GetAudioAnalysis(track_id) => {
+                let key = track_id.uri();
+                if !state.record_cache_lookup(
+                    "audio_analysis",
+                    state.data.read().caches.audio_analysis.contains_key(&key),
+                ) {
+                    // the audio analysis endpoint may be unavailable for some applications,
+                    // in which case no segment is stored to avoid requesting the analysis again
+                    let segments = match self.spotify.track_analysis(track_id).await {
+                        Ok(analysis) => analysis.segments,
+                        Err(err) => {
+                            tracing::warn!("failed to get audio analysis for track {key}: {err:#}");
+                            vec![]
+                        }
+                    };
+                    state.data.write().caches.audio_analysis.insert(
+                        key,
+                        segments,
+                        *TTL_CACHE_DURATION,
+                    );
+                }
+            }
             ClientRequest::ConnectDevice => {
                 self.connect_device(state).await;
             }
@@ -266,6 +473,16 @@ impl Client {
             ClientRequest::RestartIntegratedClient => {
                 self.new_session(state).await?;
             }
+            #[cfg(feature = "streaming")]
+            ClientRequest::ClearAudioCache => {
+                let cache = self.auth_config.cache.clone();
+                let folder = config::get_config().audio_cache_folder();
+                let n_files = tokio::task::spawn_blocking(move || {
+                    crate::streaming::clear_audio_cache(&cache, &folder)
+                })
+                .await??;
+                tracing::info!("Removed {n_files} files from the audio cache");
+            }
             ClientRequest::GetCurrentUser => {
                 let user = self.current_user().await?;
                 state.data.write().user_data.user = Some(user);
@@ -287,39 +504,48 @@ impl Client {
                     .collect();
             }
             ClientRequest::GetUserPlaylists => {
-                let playlists = self.current_user_playlists().await?;
-                store_data_into_file_cache(
-                    FileCacheKey::Playlists,
-                    &config::get_config().cache_folder,
-                    &playlists,
-                )
-                .context("store user's playlists into the cache folder")?;
+                let playlists = self.api().current_user_playlists().await?;
+                if !self.is_offline_data_source() {
+                    store_data_into_file_cache(
+                        FileCacheKey::Playlists,
+                        &config::get_config().cache_folder,
+                        &playlists,
+                    )
+                    .context("store user's playlists into the cache folder")?;
+                }
                 state.data.write().user_data.playlists = playlists;
             }
             ClientRequest::GetUserFollowedArtists => {
-                let artists = self.current_user_followed_artists().await?;
-                store_data_into_file_cache(
-                    FileCacheKey::FollowedArtists,
-                    &config::get_config().cache_folder,
-                    &artists,
-                )
-                .context("store user's followed artists into the cache folder")?;
+                let artists = self.api().current_user_followed_artists().await?;
+                if !self.is_offline_data_source() {
+                    store_data_into_file_cache(
+                        FileCacheKey::FollowedArtists,
+                        &config::get_config().cache_folder,
+                        &artists,
+                    )
+                    .context("store user's followed artists into the cache folder")?;
+                }
                 state.data.write().user_data.followed_artists = artists;
             }
             ClientRequest::GetUserSavedAlbums => {
-                let albums = self.current_user_saved_albums().await?;
-                store_data_into_file_cache(
-                    FileCacheKey::SavedAlbums,
-                    &config::get_config().cache_folder,
-                    &albums,
-                )
-                .context("store user's saved albums into the cache folder")?;
+                let albums = self.api().current_user_saved_albums().await?;
+                if !self.is_offline_data_source() {
+                    store_data_into_file_cache(
+                        FileCacheKey::SavedAlbums,
+                        &config::get_config().cache_folder,
+                        &albums,
+                    )
+                    .context("store user's saved albums into the cache folder")?;
+                }
                 state.data.write().user_data.saved_albums = albums;
             }
             ClientRequest::GetUserTopTracks => {
                 let uri = &USER_TOP_TRACKS_ID.uri;
-                if !state.data.read().caches.context.contains_key(uri) {
-                    let tracks = self.current_user_top_tracks().await?;
+                if !state.record_cache_lookup(
+                    "context",
+                    state.data.read().caches.context.contains_key(uri),
+                ) {
+                    let tracks = self.api().current_user_top_tracks().await?;
                     state.data.write().caches.context.insert(
                         uri.to_owned(),
                         Context::Tracks {
@@ -331,17 +557,19 @@ impl Client {
                 }
             }
             ClientRequest::GetUserSavedTracks => {
-                let tracks = self.current_user_saved_tracks().await?;
+                let tracks = self.api().current_user_saved_tracks().await?;
                 let tracks_hm = tracks
                     .iter()
                     .map(|t| (t.id.uri(), t.clone()))
                     .collect::<HashMap<_, _>>();
-                store_data_into_file_cache(
-                    FileCacheKey::SavedTracks,
-                    &config::get_config().cache_folder,
-                    &tracks_hm,
-                )
-                .context("store user's saved tracks into the cache folder")?;
+                if !self.is_offline_data_source() {
+                    store_data_into_file_cache(
+                        FileCacheKey::SavedTracks,
+                        &config::get_config().cache_folder,
+                        &tracks_hm,
+                    )
+                    .context("store user's saved tracks into the cache folder")?;
+                }
 
                 let mut data = state.data.write();
                 data.user_data.saved_tracks = tracks_hm;
@@ -356,8 +584,11 @@ impl Client {
             }
             ClientRequest::GetUserRecentlyPlayedTracks => {
                 let uri = &USER_RECENTLY_PLAYED_TRACKS_ID.uri;
-                if !state.data.read().caches.context.contains_key(uri) {
-                    let tracks = self.current_user_recently_played_tracks().await?;
+                if !state.record_cache_lookup(
+                    "context",
+                    state.data.read().caches.context.contains_key(uri),
+                ) {
+                    let tracks = self.api().current_user_recently_played_tracks().await?;
                     state.data.write().caches.context.insert(
                         uri.to_owned(),
                         Context::Tracks {
@@ -369,32 +600,54 @@ impl Client {
                 }
             }
             ClientRequest::GetContext(context) => {
+                let context_id = context.clone();
                 let uri = context.uri();
-                if !state.data.read().caches.context.contains_key(&uri) {
-                    let context = match context {
-                        ContextId::Playlist(playlist_id) => {
-                            self.playlist_context(playlist_id).await?
+                if !state.record_cache_lookup(
+                    "context",
+                    state.data.read().caches.context.contains_key(&uri),
+                ) {
+                    let cache_folder = &config::get_config().cache_folder;
+                    let use_file_cache = !self.is_offline_data_source();
+                    let cached_context = use_file_cache
+                        .then(|| load_context_from_file_cache(cache_folder, &uri))
+                        .flatten();
+                    let context = match cached_context {
+                        Some(context) => {
+                            state.record_cache_lookup("context file", true);
+                            context
                         }
-                        ContextId::Album(album_id) => self.album_context(album_id).await?,
-                        ContextId::Artist(artist_id) => self.artist_context(artist_id).await?,
-                        ContextId::Tracks(_) => {
-                            anyhow::bail!(
-                                "`GetContext` request for `tracks` context is not supported!"
-                            );
+                        None => {
+                            state.record_cache_lookup("context file", false);
+                            let context = self.api().context(state, context).await?;
+                            let result = if use_file_cache {
+                                store_context_into_file_cache(cache_folder, &uri, &context)
+                            } else {
+                                Ok(())
+                            };
+                            if let Err(err) = result {
+                                tracing::error!(
+                                    "Failed to store context {uri} into the file cache: {err:#}"
+                                );
+                            }
+                            context
                         }
                     };
 
-                    state
-                        .data
-                        .write()
-                        .caches
-                        .context
-                        .insert(uri, context, *TTL_CACHE_DURATION);
+                    state.data.write().insert_context(&context_id, context);
+                }
+            }
+            ClientRequest::PrefetchContext(context) => {
+                // prefetching is best-effort, a failure doesn't affect the user
+                if let Err(err) = self.prefetch_context(state, context).await {
+                    tracing::warn!("Failed to prefetch context: {err:#}");
                 }
             }
             ClientRequest::Search(query) => {
-                if !state.data.read().caches.search.contains_key(&query) {
-                    let results = self.search(&query).await?;
+                if !state.record_cache_lookup(
+                    "search",
+                    state.data.read().caches.search.contains_key(&query),
+                ) {
+                    let results = self.api().search(&query).await?;
 
                     state
                         .data
@@ -409,7 +662,10 @@ impl Client {
                 seed_name: name,
             } => {
                 let radio_uri = format!("radio:{uri}");
-                if !state.data.read().caches.context.contains_key(&radio_uri) {
+                if !state.record_cache_lookup(
+                    "context",
+                    state.data.read().caches.context.contains_key(&radio_uri),
+                ) {
                     let tracks = self.radio_tracks(uri).await?;
 
                     state.data.write().caches.context.insert(
@@ -718,7 +974,7 @@ impl Client {
             .current_user_saved_albums_manual(Some(Market::FromToken), Some(50), None)
             .await?;
 
-        let albums = self.all_paging_items(first_page, &Query::new()).await?;
+        let albums = self.all_paging_items(first_page, &market_query()).await?;
 
         // converts `rspotify_model::SavedAlbum` into `state::Album`
         Ok(albums.into_iter().map(|a| a.album.into()).collect())
@@ -843,12 +1099,22 @@ impl Client {
             .filter_map(|t| TrackId::from_id(t.original_gid).ok());
 
         // Retrieve tracks based on IDs
-        let tracks = self.tracks(track_ids, Some(Market::FromToken)).await?;
-        let tracks = tracks
-            .into_iter()
-            .filter_map(Track::try_from_full_track)
-            .collect();
+        self.tracks_in_batches(track_ids.collect()).await
+    }
+
+    /// Get tracks' data from their IDs using the batch lookup endpoint.
+    /// The IDs are split into chunks of `MAX_BATCH_SIZE` to satisfy the endpoint's limit.
+    async fn tracks_in_batches(&self, ids: Vec<TrackId<'static>>) -> Result<Vec<Track>> {
+        let requests = ids
+            .chunks(MAX_BATCH_SIZE)
+            .map(|ids| self.tracks(ids.iter().map(|id| id.as_ref()), Some(Market::FromToken)))
+            .collect::<Vec<_>>();
+        let mut batches = futures::stream::iter(requests).buffered(MAX_CONCURRENT_REQUESTS);
 
+        let mut tracks = vec![];
+        while let Some(batch) = batches.next().await {
+            tracks.extend(batch?.into_iter().filter_map(Track::try_from_full_track));
+        }
         Ok(tracks)
     }
 
@@ -908,7 +1174,7 @@ impl Client {
     ) -> Result<rspotify_model::SearchResult> {
         Ok(self
             .spotify
-            .search(query, _type, None, None, None, None)
+            .search(query, _type, Some(Market::FromToken), None, None, None)
             .await?)
     }
 
@@ -936,6 +1202,8 @@ impl Client {
 
         // After adding a new track to a playlist, remove the cache of that playlist to force refetching new data
         state.data.write().caches.context.remove(&playlist_id.uri());
+        remove_context_from_file_cache(&config::get_config().cache_folder, &playlist_id.uri());
+        self.prefetched_playlists.lock().remove(&playlist_id.uri());
 
         Ok(())
     }
@@ -965,6 +1233,8 @@ impl Client {
         {
             tracks.retain(|t| t.id != track_id);
         }
+        remove_context_from_file_cache(&config::get_config().cache_folder, &playlist_id.uri());
+        self.prefetched_playlists.lock().remove(&playlist_id.uri());
 
         Ok(())
     }
@@ -1004,7 +1274,19 @@ impl Client {
             let track = tracks.remove(range_start);
             tracks.insert(insert_index, track);
         }
+        remove_context_from_file_cache(&config::get_config().cache_folder, &playlist_id.uri());
+        self.prefetched_playlists.lock().remove(&playlist_id.uri());
+
+        Ok(())
+    }
 
+    /// Save a track to current user's library and run the `TrackLiked` event hook.
+    ///
+    /// All the ways of liking a track (UI, CLI and remote control servers) go through this method,
+    /// so that the hook is run regardless of where the track is liked from.
+    pub async fn save_track(&self, id: TrackId<'_>, track: hooks::HookTrack) -> Result<()> {
+        self.current_user_saved_tracks_add([id]).await?;
+        run_event_hook(HookEvent::TrackLiked, Some(track));
         Ok(())
     }
 
@@ -1017,8 +1299,7 @@ impl Client {
                     .current_user_saved_tracks_contains([track.id.as_ref()])
                     .await?;
                 if !contains[0] {
-                    self.current_user_saved_tracks_add([track.id.as_ref()])
-                        .await?;
+                    self.save_track(track.id.as_ref(), (&track).into()).await?;
                     // update the in-memory `user_data`
                     state
                         .data
@@ -1130,36 +1411,208 @@ impl Client {
         let playlist_uri = playlist_id.uri();
         tracing::info!("Get playlist context: {}", playlist_uri);
 
+        let (playlist, first_page) = self.playlist_with_first_page(playlist_id).await?;
+        let tracks = self
+            .all_paging_items(first_page, &playlist_tracks_query())
+            .await?
+            .into_iter()
+            .enumerate()
+            .map(|(position, item)| item.into_track(position))
+            .collect::<Vec<_>>();
+
+        Ok(Context::Playlist { playlist, tracks })
+    }
+
+    /// Get a playlist's data along with the first page of its tracks
+    async fn playlist_with_first_page(
+        &self,
+        playlist_id: PlaylistId<'_>,
+    ) -> Result<(Playlist, Page<PartialPlaylistItem>)> {
         // TODO: this should use `rspotify::playlist` API instead of `internal_call`
         // See: https://github.com/ramsayleung/rspotify/issues/459
         // let playlist = self
         //     .playlist(playlist_id, None, Some(Market::FromToken))
         //     .await?;
+        let mut payload = market_query();
+        payload.insert("fields", PLAYLIST_FIELDS);
+        payload.insert("additional_types", "track,episode");
         let playlist = self
-            .http_get::<FullPlaylist>(
+            .http_get::<PartialPlaylist>(
                 &format!("{SPOTIFY_API_ENDPOINT}/playlists/{}", playlist_id.id()),
-                &market_query(),
+                &payload,
             )
             .await?;
+        Ok(playlist.split())
+    }
 
-        // get the playlist's tracks
-        let first_page = playlist.tracks.clone();
-        let tracks = self
-            .all_paging_items(first_page, &market_query())
-            .await?
-            .into_iter()
-            .filter_map(|item| match item.track {
-                Some(rspotify_model::PlayableItem::Track(track)) => {
-                    Track::try_from_full_track(track)
+    /// Prefetch a context data in the background so that the context can be opened instantly.
+    /// Only the first page of a playlist's tracks is prefetched, the remaining pages are fetched
+    /// upon opening the playlist.
+    async fn prefetch_context(&self, state: &SharedState, id: ContextId) -> Result<()> {
+        let uri = id.uri();
+        if state.data.read().caches.context.contains_key(&uri) {
+            return Ok(());
+        }
+        let cache_folder = &config::get_config().cache_folder;
+        if let Some(context) = load_context_from_file_cache(cache_folder, &uri) {
+            state.data.write().insert_context(&id, context);
+            return Ok(());
+        }
+
+        match &id {
+            ContextId::Playlist(playlist_id) => {
+                if self.prefetched_playlists.lock().contains_key(&uri) {
+                    return Ok(());
                 }
-                _ => None,
-            })
-            .collect::<Vec<_>>();
+                tracing::info!("Prefetch playlist: {uri}");
+                let playlist = self.playlist_with_first_page(playlist_id.as_ref()).await?;
+                self.prefetched_playlists
+                    .lock()
+                    .insert(uri, playlist, PREFETCHED_PLAYLIST_TTL);
+            }
+            ContextId::Album(album_id) => {
+                tracing::info!("Prefetch album: {uri}");
+                let context = self.album_context(album_id.as_ref()).await?;
+                if let Err(err) = store_context_into_file_cache(cache_folder, &uri, &context) {
+                    tracing::error!("Failed to store context {uri} into the file cache: {err:#}");
+                }
+                state.data.write().insert_context(&id, context);
+            }
+            ContextId::Artist(_) | ContextId::Tracks(_) => {}
+        }
+        Ok(())
+    }
 
-        Ok(Context::Playlist {
-            playlist: playlist.into(),
-            tracks,
+    /// Fetch a context data from Spotify API
+    async fn fetch_context(&self, state: &SharedState, id: ContextId) -> Result<Context> {
+        match id {
+            ContextId::Playlist(playlist_id) => {
+                // failing to revalidate the cached context only costs a full playlist fetch
+                match self.revalidate_playlist_context(playlist_id.as_ref()).await {
+                    Ok(Some(context)) => return Ok(context),
+                    Ok(None) => {}
+                    Err(err) => {
+                        tracing::warn!(
+                            "Failed to revalidate the cached context of {}: {err:#}",
+                            playlist_id.uri()
+                        );
+                    }
+                }
+                self.stream_playlist_context(state, playlist_id).await
+            }
+            ContextId::Album(album_id) => self.album_context(album_id).await,
+            ContextId::Artist(artist_id) => self.artist_context(artist_id).await,
+            ContextId::Tracks(_) => {
+                anyhow::bail!("`GetContext` request for `tracks` context is not supported!");
+            }
+        }
+    }
+
+    /// Get a playlist context from its expired file cache if the playlist hasn't been modified
+    /// since the context was cached, which is determined by comparing the playlist's snapshot IDs.
+    /// This avoids refetching all pages of the playlist's tracks when nothing changed.
+    async fn revalidate_playlist_context(
+        &self,
+        playlist_id: PlaylistId<'_>,
+    ) -> Result<Option<Context>> {
+        #[derive(Deserialize)]
+        struct PlaylistSnapshot {
+            snapshot_id: String,
+        }
+
+        let uri = playlist_id.uri();
+        let cache_folder = &config::get_config().cache_folder;
+        let Some(Context::Playlist { playlist, tracks }) =
+            load_stale_context_from_file_cache(cache_folder, &uri)
+        else {
+            return Ok(None);
+        };
+        if playlist.snapshot_id.is_empty() {
+            return Ok(None);
+        }
+
+        let snapshot = self
+            .http_get::<PlaylistSnapshot>(
+                &format!("{SPOTIFY_API_ENDPOINT}/playlists/{}", playlist_id.id()),
+                &Query::from([("fields", "snapshot_id")]),
+            )
+            .await?;
+        if snapshot.snapshot_id != playlist.snapshot_id {
+            return Ok(None);
+        }
+
+        tracing::info!("Playlist {uri} is unchanged since cached, reusing its cached tracks");
+        Ok(Some(Context::Playlist { playlist, tracks }))
+    }
+
+    /// Get a playlist context data while making its tracks available in the context cache
+    /// page by page as they arrive, so the playlist can be browsed before it's fully fetched
+    async fn stream_playlist_context(
+        &self,
+        state: &SharedState,
+        playlist_id: PlaylistId<'_>,
+    ) -> Result<Context> {
+        /// A guard removing a partially fetched context from the cache unless
+        /// the context is completely fetched, e.g. when the fetch fails or is aborted
+        struct PartialContextGuard<'a> {
+            state: &'a SharedState,
+            uri: String,
+            completed: bool,
+        }
+
+        impl Drop for PartialContextGuard<'_> {
+            fn drop(&mut self) {
+                if !self.completed {
+                    self.state.data.write().caches.context.remove(&self.uri);
+                }
+            }
+        }
+
+        let playlist_uri = playlist_id.uri();
+        tracing::info!("Get playlist context: {}", playlist_uri);
+
+        // reuse the playlist's first page if it has been prefetched
+        let prefetched = self.prefetched_playlists.lock().remove(&playlist_uri);
+        let (playlist, first_page) = match prefetched {
+            Some(playlist) => playlist,
+            None => self.playlist_with_first_page(playlist_id).await?,
+        };
+
+        state.data.write().caches.context.insert(
+            playlist_uri.clone(),
+            Context::Playlist {
+                playlist,
+                tracks: vec![],
+            },
+            *TTL_CACHE_DURATION,
+        );
+        let mut guard = PartialContextGuard {
+            state,
+            uri: playlist_uri,
+            completed: false,
+        };
+
+        self.for_each_page(first_page, &playlist_tracks_query(), |items| {
+            if let Some(Context::Playlist { tracks, .. }) =
+                state.data.write().caches.context.get_mut(&guard.uri)
+            {
+                // pages are received in order, so an item's position follows the stored tracks
+                let offset = tracks.len();
+                tracks.extend(
+                    items
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, item)| item.into_track(offset + i)),
+                );
+            }
+            state.mark_changed();
         })
+        .await?;
+
+        // the complete context is re-inserted into the cache by the caller
+        let context = state.data.write().caches.context.get(&guard.uri).cloned();
+        guard.completed = true;
+        context.context("playlist context was removed from the cache while being fetched")
     }
 
     /// Get an album context data
@@ -1175,7 +1628,7 @@ impl Client {
 
         // get the album's tracks
         let tracks = self
-            .all_paging_items(first_page, &Query::new())
+            .all_paging_items(first_page, &market_query())
             .await?
             .into_iter()
             .filter_map(|t| {
@@ -1243,7 +1696,7 @@ impl Client {
 
         tracing::debug!("{access_token} {url}");
 
-        let response = self
+        let mut request = self
             .http
             .get(url)
             .query(payload)
@@ -1251,11 +1704,47 @@ impl Client {
                 reqwest::header::AUTHORIZATION,
                 format!("Bearer {access_token}"),
             )
-            .send()
-            .await?;
+            .build()?;
+
+        // issue a conditional request if the resource has been fetched before,
+        // an unchanged resource is responded with `304 Not Modified` without a body
+        let key = request.url().to_string();
+        let cached = self.etag_cache.lock().get(&key).cloned();
+        if let Some((etag, _)) = &cached {
+            if let Ok(etag) = reqwest::header::HeaderValue::from_str(etag) {
+                request
+                    .headers_mut()
+                    .insert(reqwest::header::IF_NONE_MATCH, etag);
+            }
+        }
 
-        let text = process_spotify_api_response(response.text().await?);
-        tracing::debug!("{text}");
+        let response = self.http.execute(request).await?;
+        let text = match cached {
+            Some((_, text)) if response.status() == reqwest::StatusCode::NOT_MODIFIED => {
+                tracing::debug!("{url} is not modified, reusing the cached response");
+                text
+            }
+            _ => {
+                if !response.status().is_success() {
+                    return Err(ClientError::from_response(&response))
+                        .with_context(|| format!("failed to get {url}"));
+                }
+
+                let etag = response
+                    .headers()
+                    .get(reqwest::header::ETAG)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|v| v.to_string());
+                let text = process_spotify_api_response(response.text().await?);
+                tracing::debug!("{text}");
+                if let Some(etag) = etag {
+                    self.etag_cache
+                        .lock()
+                        .insert(key, (etag, text.clone()), *TTL_CACHE_DURATION);
+                }
+                text
+            }
+        };
 
         Ok(serde_json::from_str(&text)?)
     }
@@ -1269,17 +1758,76 @@ impl Client {
     where
         T: serde::de::DeserializeOwned,
     {
-        let mut items = first_page.items;
-        let mut maybe_next = first_page.next;
+        let mut items = vec![];
+        self.for_each_page(first_page, payload, |mut page| items.append(&mut page))
+            .await?;
+        Ok(items)
+    }
 
-        while let Some(url) = maybe_next {
-            let mut next_page = self
-                .http_get::<rspotify_model::Page<T>>(&url, payload)
-                .await?;
-            items.append(&mut next_page.items);
-            maybe_next = next_page.next;
+    /// Process all paging items page by page (in order) starting from a pagination object of the first page
+    async fn for_each_page<T>(
+        &self,
+        first_page: rspotify_model::Page<T>,
+        payload: &Query<'_>,
+        mut f: impl FnMut(Vec<T>),
+    ) -> Result<()>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        f(first_page.items);
+        let Some(next) = first_page.next else {
+            return Ok(());
+        };
+
+        // The total number of items and the page size are known from the first page,
+        // so the URLs of the remaining pages can be constructed upfront by updating
+        // the `offset` parameter of the next page's URL.
+        let urls = match reqwest::Url::parse(&next) {
+            Ok(url) if first_page.limit > 0 => {
+                let offsets = (first_page.offset + first_page.limit..first_page.total)
+                    .step_by(first_page.limit as usize);
+                offsets
+                    .map(|offset| {
+                        let mut url = url.clone();
+                        let pairs = url
+                            .query_pairs()
+                            .filter(|(k, _)| k != "offset" && !payload.contains_key(k.as_ref()))
+                            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                            .collect::<Vec<_>>();
+                        url.query_pairs_mut()
+                            .clear()
+                            .extend_pairs(pairs)
+                            .append_pair("offset", &offset.to_string());
+                        url.to_string()
+                    })
+                    .collect::<Vec<_>>()
+            }
+            _ => {
+                // fallback to follow the `next` URLs sequentially
+                let mut maybe_next = Some(next);
+                while let Some(url) = maybe_next {
+                    let next_page = self
+                        .http_get::<rspotify_model::Page<T>>(&url, payload)
+                        .await?;
+                    f(next_page.items);
+                    maybe_next = next_page.next;
+                }
+                return Ok(());
+            }
+        };
+
+        // fetch the remaining pages concurrently, `buffered` yields
+        // the pages in the same order as their URLs
+        let mut pages = futures::stream::iter(urls)
+            .map(|url| async move {
+                self.http_get::<rspotify_model::Page<T>>(&url, payload)
+                    .await
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS);
+        while let Some(page) = pages.next().await {
+            f(page?.items);
         }
-        Ok(items)
+        Ok(())
     }
 
     /// Get all cursor-based paging items starting from a pagination object of the first page
@@ -1310,17 +1858,40 @@ impl Client {
     ) -> Result<()> {
         let new_track = {
             // update the playback state
-            let playback = self.current_playback(None, None::<Vec<_>>).await?;
+            let playback = self
+                .current_playback(
+                    Some(Market::FromToken),
+                    Some(&[AdditionalType::Track, AdditionalType::Episode]),
+                )
+                .await?;
             let mut player = state.player.write();
 
             let prev_track_name = player
                 .current_playing_track()
                 .map(|t| t.name.to_owned())
                 .unwrap_or_default();
+            let prev_track_id = player.current_playing_track().and_then(|t| t.id.clone());
+            let was_playing = player.playback.as_ref().is_some_and(|p| p.is_playing);
 
             player.playback = playback;
             player.playback_last_updated_time = Some(std::time::Instant::now());
 
+            // run the hook commands configured for player events
+            let track = player.current_playing_track();
+            let track_id = track.and_then(|t| t.id.clone());
+            if track_id.is_some() && track_id != prev_track_id {
+                run_event_hook(HookEvent::TrackChanged, track.map(Into::into));
+            }
+            let is_playing = player.playback.as_ref().is_some_and(|p| p.is_playing);
+            if is_playing != was_playing {
+                let event = if is_playing {
+                    HookEvent::PlaybackStarted
+                } else {
+                    HookEvent::PlaybackStopped
+                };
+                run_event_hook(event, track.map(Into::into));
+            }
+
             let curr_track_name = player
                 .current_playing_track()
                 .map(|t| t.name.to_owned())
@@ -1353,11 +1924,13 @@ impl Client {
 
             new_track
         };
+        state.mark_changed();
 
         if !new_track {
             return Ok(());
         }
         self.handle_new_track_event(state).await?;
+        state.mark_changed();
 
         Ok(())
     }
@@ -1389,7 +1962,7 @@ impl Client {
         }
 
         #[cfg(feature = "image")]
-        if !state.data.read().caches.images.contains_key(url) {
+        if !state.record_cache_lookup("images", state.data.read().caches.images.contains_key(url)) {
             let bytes = self.retrieve_image(url, &path, false).await?;
             let image =
                 image::load_from_memory(&bytes).context("Failed to load image from memory")?;
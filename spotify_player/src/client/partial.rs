@@ -0,0 +1,261 @@
+use serde::Deserialize;
+
+use crate::state::*;
+
+/// the fields of a playlist's tracks page, only the track fields used by the application are requested
+macro_rules! playlist_tracks_page_fields {
+    () => {
+        "href,limit,next,offset,previous,total,items(added_at,track(type,id,name,duration_ms,\
+         explicit,disc_number,track_number,is_playable,linked_from(id),artists(id,name),\
+         album(id,name,release_date,artists(id,name)),show(name)))"
+    };
+}
+
+/// the value of the `fields` parameter when getting a playlist's tracks page
+pub const PLAYLIST_TRACKS_PAGE_FIELDS: &str = playlist_tracks_page_fields!();
+/// the value of the `fields` parameter when getting a playlist
+pub const PLAYLIST_FIELDS: &str = concat!(
+    "id,name,collaborative,description,public,snapshot_id,owner(id,display_name),tracks(",
+    playlist_tracks_page_fields!(),
+    ")"
+);
+
+#[derive(Debug, Deserialize)]
+/// A playlist object with the fields specified by `PLAYLIST_FIELDS`
+pub struct PartialPlaylist {
+    pub id: PlaylistId<'static>,
+    pub name: String,
+    pub collaborative: bool,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    pub snapshot_id: String,
+    pub owner: PartialUser,
+    pub tracks: rspotify_model::Page<PartialPlaylistItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PartialUser {
+    pub id: UserId<'static>,
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+/// A playlist item with the fields specified by `PLAYLIST_TRACKS_PAGE_FIELDS`
+pub struct PartialPlaylistItem {
+    pub added_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub track: Option<PartialPlayableItem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PartialPlayableItem {
+    Track(Box<PartialTrack>),
+    Episode(Box<PartialEpisode>),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PartialTrack {
+    pub id: Option<TrackId<'static>>,
+    pub name: String,
+    pub duration_ms: u64,
+    pub explicit: bool,
+    pub disc_number: i32,
+    pub track_number: u32,
+    #[serde(default)]
+    pub is_playable: Option<bool>,
+    #[serde(default)]
+    pub linked_from: Option<PartialTrackLink>,
+    pub artists: Vec<PartialArtist>,
+    pub album: PartialAlbum,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PartialEpisode {
+    pub id: EpisodeId<'static>,
+    pub name: String,
+    pub duration_ms: u64,
+    pub explicit: bool,
+    #[serde(default)]
+    pub is_playable: Option<bool>,
+    pub show: PartialShow,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PartialShow {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PartialTrackLink {
+    pub id: TrackId<'static>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PartialArtist {
+    pub id: Option<ArtistId<'static>>,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PartialAlbum {
+    pub id: Option<AlbumId<'static>>,
+    pub name: String,
+    pub release_date: Option<String>,
+    pub artists: Vec<PartialArtist>,
+}
+
+fn into_artists(artists: Vec<PartialArtist>) -> Vec<Artist> {
+    artists
+        .into_iter()
+        .filter_map(|a| {
+            Some(Artist {
+                id: a.id?,
+                name: a.name,
+            })
+        })
+        .collect()
+}
+
+impl PartialPlaylistItem {
+    /// converts the playlist item at `position` in its playlist into a `Track`.
+    ///
+    /// An unplayable track is kept and marked as such. An item without track data (e.g. a removed
+    /// track or a local file) is kept as an unavailable placeholder, so that the playlist's
+    /// track count and positions match the official client.
+    pub fn into_track(self, position: usize) -> Track {
+        let added_at = self
+            .added_at
+            .map(|t| t.timestamp().max(0) as u64)
+            .unwrap_or_default();
+        let track = match self.track {
+            Some(PartialPlayableItem::Track(track)) => *track,
+            Some(PartialPlayableItem::Episode(episode)) => {
+                return Track::from_episode(
+                    episode.id,
+                    episode.name,
+                    episode.show.name,
+                    std::time::Duration::from_millis(episode.duration_ms),
+                    episode.explicit,
+                    episode.is_playable.unwrap_or(true),
+                    added_at,
+                )
+            }
+            Some(PartialPlayableItem::Other) | None => {
+                return Track::unavailable(position, added_at)
+            }
+        };
+
+        let id = match (track.linked_from, track.id) {
+            (Some(d), _) => d.id,
+            (None, Some(id)) => id,
+            (None, None) => return Track::unavailable(position, added_at),
+        };
+        let album = track.album.id.map(|id| Album {
+            id,
+            name: track.album.name,
+            release_date: track.album.release_date.unwrap_or_default(),
+            artists: into_artists(track.album.artists),
+        });
+        Track {
+            id,
+            name: track.name,
+            artists: into_artists(track.artists),
+            album,
+            duration: std::time::Duration::from_millis(track.duration_ms),
+            explicit: track.explicit,
+            disc_number: track.disc_number,
+            track_number: track.track_number,
+            added_at,
+            is_playable: track.is_playable.unwrap_or(true),
+            episode: None,
+            search_text: Default::default(),
+        }
+    }
+}
+
+impl PartialPlaylist {
+    /// splits the playlist object into the playlist's data and the first page of its tracks
+    pub fn split(self) -> (Playlist, rspotify_model::Page<PartialPlaylistItem>) {
+        let playlist = Playlist {
+            id: self.id,
+            name: self.name,
+            collaborative: self.collaborative,
+            owner: (self.owner.display_name.unwrap_or_default(), self.owner.id),
+            desc: remove_html_tags(&self.description.unwrap_or_default()),
+            public: self.public,
+            n_tracks: self.tracks.total,
+            snapshot_id: self.snapshot_id,
+        };
+        (playlist, self.tracks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unavailable_playlist_items_are_kept_in_place() {
+        let page: rspotify_model::Page<PartialPlaylistItem> =
+            serde_json::from_value(serde_json::json!({
+                "href": "https://api.spotify.com/v1/playlists/id/tracks",
+                "limit": 100,
+                "next": null,
+                "offset": 0,
+                "previous": null,
+                "total": 4,
+                "items": [
+                    {
+                        "added_at": "2024-01-01T00:00:00Z",
+                        "track": {
+                            "type": "track",
+                            "id": "4uLU6hMCjMI75M1A2tKUQC",
+                            "name": "first",
+                            "duration_ms": 1000,
+                            "explicit": false,
+                            "disc_number": 1,
+                            "track_number": 1,
+                            "artists": [],
+                            "album": { "id": null, "name": "", "release_date": null, "artists": [] }
+                        }
+                    },
+                    { "added_at": null, "track": null },
+                    {
+                        "added_at": "2024-01-02T00:00:00Z",
+                        "track": {
+                            "type": "track",
+                            "id": null,
+                            "name": "local file",
+                            "duration_ms": 1000,
+                            "explicit": false,
+                            "disc_number": 0,
+                            "track_number": 0,
+                            "artists": [],
+                            "album": { "id": null, "name": "", "release_date": null, "artists": [] }
+                        }
+                    },
+                    { "added_at": null, "track": { "type": "ad" } }
+                ]
+            }))
+            .expect("valid playlist tracks page");
+
+        let tracks = page
+            .items
+            .into_iter()
+            .enumerate()
+            .map(|(position, item)| item.into_track(position))
+            .collect::<Vec<_>>();
+
+        assert_eq!(tracks.len(), 4);
+        assert_eq!(tracks[0].name, "first");
+        assert!(tracks[0].is_playable);
+        for (position, track) in tracks.iter().enumerate().skip(1) {
+            assert_eq!(track.name, "Unavailable");
+            assert!(!track.is_playable);
+            assert_eq!(track.track_number, position as u32 + 1);
+        }
+        assert_ne!(tracks[1].id, tracks[2].id);
+    }
+}
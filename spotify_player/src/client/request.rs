@@ -31,6 +31,8 @@ pub enum ClientRequest {
     GetUserTopTracks,
     GetUserRecentlyPlayedTracks,
     GetContext(ContextId),
+    /// fetches a context's data in the background before it's browsed
+    PrefetchContext(ContextId),
     GetCurrentPlayback,
     GetRadioTracks {
         seed_uri: String,
@@ -53,6 +55,8 @@ pub enum ClientRequest {
     ConnectDevice,
     Player(PlayerRequest),
     GetCurrentUserQueue,
+    GetTrackDetails(TrackId<'static>),
+    GetAudioAnalysis(TrackId<'static>),
     #[cfg(feature = "lyric-finder")]
     GetLyric {
         track: String,
@@ -60,6 +64,8 @@ pub enum ClientRequest {
     },
     #[cfg(feature = "streaming")]
     RestartIntegratedClient,
+    #[cfg(feature = "streaming")]
+    ClearAudioCache,
     CreatePlaylist {
         playlist_name: String,
         public: bool,
@@ -67,3 +73,143 @@ pub enum ClientRequest {
         desc: String,
     },
 }
+
+impl ClientRequest {
+    /// checks if the request can be safely retried, i.e. handling the request
+    /// more than once has the same effect as handling it once
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(
+            self,
+            Self::AddTrackToQueue(_)
+                | Self::AddAlbumToQueue(_)
+                | Self::AddTrackToPlaylist(..)
+                | Self::ReorderPlaylistItems { .. }
+                | Self::CreatePlaylist { .. }
+                | Self::Player(
+                    PlayerRequest::NextTrack
+                        | PlayerRequest::PreviousTrack
+                        | PlayerRequest::ResumePause
+                        | PlayerRequest::Repeat
+                        | PlayerRequest::Shuffle
+                        | PlayerRequest::ToggleMute
+                )
+        )
+    }
+
+    /// checks if the request fetches the user's data, a context or search results,
+    /// which can be served by an alternative data source (e.g. mock data)
+    pub fn is_data_source_request(&self) -> bool {
+        matches!(
+            self,
+            Self::GetUserPlaylists
+                | Self::GetUserSavedAlbums
+                | Self::GetUserFollowedArtists
+                | Self::GetUserSavedTracks
+                | Self::GetUserTopTracks
+                | Self::GetUserRecentlyPlayedTracks
+                | Self::GetContext(_)
+                | Self::Search(_)
+        )
+    }
+
+    /// checks if the request only fetches data without modifying anything
+    pub fn is_fetch(&self) -> bool {
+        self.dedup_key().is_some()
+    }
+
+    /// gets the key identifying a request fetching data, which is used to
+    /// deduplicate identical fetch requests handled concurrently.
+    /// Returns `None` if the request modifies data and must not be deduplicated.
+    pub fn dedup_key(&self) -> Option<String> {
+        match self {
+            Self::GetCurrentUser
+            | Self::GetDevices
+            | Self::GetBrowseCategories
+            | Self::GetBrowseCategoryPlaylists(_)
+            | Self::GetUserPlaylists
+            | Self::GetUserSavedAlbums
+            | Self::GetUserFollowedArtists
+            | Self::GetUserSavedTracks
+            | Self::GetUserTopTracks
+            | Self::GetUserRecentlyPlayedTracks
+            | Self::GetContext(_)
+            | Self::PrefetchContext(_)
+            | Self::GetCurrentPlayback
+            | Self::GetRadioTracks { .. }
+            | Self::Search(_)
+            | Self::GetCurrentUserQueue
+            | Self::GetTrackDetails(_)
+            | Self::GetAudioAnalysis(_) => Some(format!("{self:?}")),
+            #[cfg(feature = "lyric-finder")]
+            Self::GetLyric { .. } => Some(format!("{self:?}")),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+/// The client requests received in offline mode, which are replayed once reconnected to Spotify
+pub struct OfflineQueue(parking_lot::Mutex<Vec<ClientRequest>>);
+
+impl OfflineQueue {
+    /// queues a request, returns whether the request is queued.
+    /// Fetch requests are not queued because the application's data is refreshed once reconnected.
+    pub fn push(&self, request: ClientRequest) -> bool {
+        if request.is_fetch() {
+            return false;
+        }
+        self.0.lock().push(request);
+        true
+    }
+
+    /// takes the queued requests in the order they were received
+    pub fn take(&self) -> Vec<ClientRequest> {
+        std::mem::take(&mut *self.0.lock())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_queue_keeps_modifying_requests_in_order() {
+        let queue = OfflineQueue::default();
+        assert!(queue.push(ClientRequest::Player(PlayerRequest::Pause)));
+        assert!(!queue.push(ClientRequest::GetUserPlaylists));
+        assert!(queue.push(ClientRequest::Player(PlayerRequest::Volume(50))));
+        assert!(!queue.push(ClientRequest::GetCurrentPlayback));
+
+        let requests = queue.take();
+        assert!(matches!(
+            requests[..],
+            [
+                ClientRequest::Player(PlayerRequest::Pause),
+                ClientRequest::Player(PlayerRequest::Volume(50))
+            ]
+        ));
+        assert!(queue.take().is_empty());
+    }
+
+    #[test]
+    fn only_identical_fetch_requests_share_a_dedup_key() {
+        let search = |query: &str| ClientRequest::Search(query.to_string());
+        assert!(search("foo").dedup_key().is_some());
+        assert_eq!(search("foo").dedup_key(), search("foo").dedup_key());
+        assert_ne!(search("foo").dedup_key(), search("bar").dedup_key());
+        assert_ne!(
+            ClientRequest::GetUserPlaylists.dedup_key(),
+            ClientRequest::GetUserSavedAlbums.dedup_key()
+        );
+
+        // requests modifying data are never deduplicated
+        assert_eq!(
+            ClientRequest::Player(PlayerRequest::NextTrack).dedup_key(),
+            None
+        );
+        let track_id = TrackId::from_id("0000000000000000000001")
+            .unwrap()
+            .into_static();
+        assert_eq!(ClientRequest::AddTrackToQueue(track_id).dedup_key(), None);
+    }
+}
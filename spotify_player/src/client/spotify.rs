@@ -7,9 +7,18 @@ use rspotify::{
     sync::Mutex,
     ClientResult, Config, Credentials, OAuth, Token,
 };
-use std::{fmt, sync::Arc};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{config, encryption, token};
 
-use crate::token;
+/// the name of the file caching the latest access token inside the application's cache folder
+pub const TOKEN_CACHE_FILE: &str = "token_cache.json";
+/// the name of the file caching the latest access token when cache encryption is enabled
+pub const ENCRYPTED_TOKEN_CACHE_FILE: &str = "token_cache.enc";
 
 #[derive(Clone, Default)]
 /// A Spotify client to interact with Spotify API server
@@ -20,8 +29,8 @@ pub struct Spotify {
     token: Arc<Mutex<Option<Token>>>,
     client_id: String,
     http: HttpClient,
-    // session should always be non-empty, but `Option` is used to implement `Default`,
-    // which is required to implement `rspotify::BaseClient` trait
+    // session is empty when the application starts in offline mode, `Option` is also
+    // used to implement `Default`, which is required to implement `rspotify::BaseClient` trait
     pub(crate) session: Arc<tokio::sync::Mutex<Option<Session>>>,
 }
 
@@ -39,27 +48,40 @@ impl fmt::Debug for Spotify {
 
 impl Spotify {
     /// creates a new Spotify client
-    pub fn new(session: Session, client_id: String) -> Spotify {
+    pub fn new(session: Option<Session>, client_id: String) -> Spotify {
         Self {
             creds: Credentials::default(),
             oauth: OAuth::default(),
             config: Config {
                 token_refreshing: true,
+                token_cached: true,
+                cache_path: token_cache_path(),
                 ..Default::default()
             },
             token: Arc::new(Mutex::new(None)),
             http: HttpClient::default(),
-            session: Arc::new(tokio::sync::Mutex::new(Some(session))),
+            session: Arc::new(tokio::sync::Mutex::new(session)),
             client_id,
         }
     }
 
     pub async fn session(&self) -> Session {
-        self.session
+        self.try_session().await.expect("non-empty Spotify session")
+    }
+
+    /// gets the current session, returns `None` if the client hasn't connected to Spotify yet
+    pub async fn try_session(&self) -> Option<Session> {
+        self.session.lock().await.clone()
+    }
+
+    /// gets the expiry time of the current access token
+    pub async fn token_expires_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.token
             .lock()
             .await
-            .clone()
-            .expect("non-empty Spotify session")
+            .unwrap()
+            .as_ref()
+            .and_then(|t| t.expires_at)
     }
 
     /// gets a Spotify access token.
@@ -105,9 +127,22 @@ impl BaseClient for Spotify {
     }
 
     async fn refetch_token(&self) -> ClientResult<Option<Token>> {
-        let session = self.session().await;
         let old_token = self.token.lock().await.unwrap().clone();
 
+        // reuse the token cached by a previous run of the application if it's still valid
+        if old_token.is_none() {
+            if let Ok(token) = read_token_cache_file(&self.config.cache_path) {
+                if !token.is_expired() {
+                    tracing::info!("Loaded the authentication token from the token cache");
+                    return Ok(Some(token));
+                }
+            }
+        }
+
+        let Some(session) = self.try_session().await else {
+            tracing::error!("Failed to get a new token: not connected to Spotify");
+            return Ok(old_token);
+        };
         if session.is_invalid() {
             tracing::error!("Failed to get a new token: invalid session");
             return Ok(old_token);
@@ -121,6 +156,54 @@ impl BaseClient for Spotify {
             }
         }
     }
+
+    async fn write_token_cache(&self) -> ClientResult<()> {
+        if let Some(token) = self.token.lock().await.unwrap().as_ref() {
+            if let Err(err) = write_token_cache_file(token, &self.config.cache_path) {
+                tracing::warn!("Failed to write the token cache: {err:#}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// gets the path of the token cache file, which is encrypted if `enable_cache_encryption` is set
+fn token_cache_path() -> PathBuf {
+    let cache_folder = &config::get_config().cache_folder;
+    if encryption::is_enabled() {
+        // the plaintext token cache written before enabling the encryption is no longer used
+        let plaintext_path = cache_folder.join(TOKEN_CACHE_FILE);
+        if plaintext_path.exists() {
+            if let Err(err) = std::fs::remove_file(plaintext_path) {
+                tracing::warn!("Failed to remove the plaintext token cache: {err:#}");
+            }
+        }
+        cache_folder.join(ENCRYPTED_TOKEN_CACHE_FILE)
+    } else {
+        cache_folder.join(TOKEN_CACHE_FILE)
+    }
+}
+
+/// reads a token from a cache file, which is decrypted if `enable_cache_encryption` is set
+fn read_token_cache_file(path: &Path) -> Result<Token> {
+    if encryption::is_enabled() {
+        Ok(serde_json::from_slice(&encryption::read_encrypted_file(
+            path,
+        )?)?)
+    } else {
+        Ok(Token::from_cache(path)?)
+    }
+}
+
+/// writes a token into a cache file, which is only accessible by the current user on Unix systems
+/// and is encrypted if `enable_cache_encryption` is set
+fn write_token_cache_file(token: &Token, path: &Path) -> Result<()> {
+    if encryption::is_enabled() {
+        return encryption::write_encrypted_file(path, &serde_json::to_vec(token)?);
+    }
+
+    serde_json::to_writer(encryption::create_private_file(path)?, token)?;
+    Ok(())
 }
 
 /// Implement `OAuthClient` trait for `Spotify` struct
@@ -36,6 +36,10 @@ pub enum Command {
 
     #[cfg(feature = "streaming")]
     RestartIntegratedClient,
+    #[cfg(feature = "streaming")]
+    ClearAudioCache,
+    #[cfg(feature = "streaming")]
+    OpenPlaybackSettings,
 
     FocusNextWindow,
     FocusPreviousWindow,
@@ -44,10 +48,23 @@ pub enum Command {
     SwitchDevice,
     Search,
     Queue,
+    ToggleQueuePane,
+    ToggleLogPane,
+    ToggleVisualizerPane,
+    CycleLogPaneLevel,
+    OpenDiagnostics,
+    ExportScreenshot,
+    ReloadConfig,
+    RecordMacro,
+    ReplayMacro,
+    SwitchProfile,
 
     ShowActionsOnSelectedItem,
     ShowActionsOnCurrentTrack,
     AddSelectedItemToQueue,
+    CopySelectedItemLink,
+    CopySelectedItemUri,
+    OpenSelectedItemExternally,
 
     BrowseUserPlaylists,
     BrowseUserFollowedArtists,
@@ -71,6 +88,14 @@ pub enum Command {
     SortTrackByDuration,
     SortTrackByAddedDate,
     ReverseTrackOrder,
+    CopyTrackTableAsCsv,
+    CopyTrackTableAsMarkdown,
+    ExportTrackTableAsCsv,
+    ExportTrackTableAsMarkdown,
+    ToggleTrackTableColumns,
+    ToggleGroupByAlbum,
+    SelectNextGroup,
+    SelectPreviousGroup,
 
     MovePlaylistItemUp,
     MovePlaylistItemDown,
@@ -91,6 +116,8 @@ pub enum TrackAction {
     AddToLikedTracks,
     DeleteFromLikedTracks,
     CopyTrackLink,
+    ShareTrack,
+    ShowTrackDetails,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -101,6 +128,7 @@ pub enum AlbumAction {
     AddToLibrary,
     DeleteFromLibrary,
     CopyAlbumLink,
+    ShareAlbum,
     AddToQueue,
 }
 
@@ -110,6 +138,7 @@ pub enum ArtistAction {
     Follow,
     Unfollow,
     CopyArtistLink,
+    ShareArtist,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -118,6 +147,7 @@ pub enum PlaylistAction {
     AddToLibrary,
     DeleteFromLibrary,
     CopyPlaylistLink,
+    SharePlaylist,
 }
 
 /// constructs a list of actions on a track
@@ -129,8 +159,10 @@ pub fn construct_track_actions(track: &Track, data: &DataReadGuard) -> Vec<Track
         TrackAction::ShowActionsOnAlbum,
         TrackAction::ShowActionsOnArtist,
         TrackAction::CopyTrackLink,
+        TrackAction::ShareTrack,
         TrackAction::AddToPlaylist,
         TrackAction::AddToQueue,
+        TrackAction::ShowTrackDetails,
     ];
 
     // check if the track is a liked track
@@ -150,6 +182,7 @@ pub fn construct_album_actions(album: &Album, data: &DataReadGuard) -> Vec<Album
         AlbumAction::GoToAlbumRadio,
         AlbumAction::ShowActionsOnArtist,
         AlbumAction::CopyAlbumLink,
+        AlbumAction::ShareAlbum,
         AlbumAction::AddToQueue,
     ];
     if data.user_data.saved_albums.iter().any(|a| a.id == album.id) {
@@ -162,7 +195,11 @@ pub fn construct_album_actions(album: &Album, data: &DataReadGuard) -> Vec<Album
 
 /// constructs a list of actions on an artist
 pub fn construct_artist_actions(artist: &Artist, data: &DataReadGuard) -> Vec<ArtistAction> {
-    let mut actions = vec![ArtistAction::GoToArtistRadio, ArtistAction::CopyArtistLink];
+    let mut actions = vec![
+        ArtistAction::GoToArtistRadio,
+        ArtistAction::CopyArtistLink,
+        ArtistAction::ShareArtist,
+    ];
     if data
         .user_data
         .followed_artists
@@ -184,6 +221,7 @@ pub fn construct_playlist_actions(
     let mut actions = vec![
         PlaylistAction::GoToPlaylistRadio,
         PlaylistAction::CopyPlaylistLink,
+        PlaylistAction::SharePlaylist,
     ];
     if data.user_data.playlists.iter().any(|a| a.id == playlist.id) {
         actions.push(PlaylistAction::DeleteFromLibrary);
@@ -204,15 +242,21 @@ impl Command {
             Self::Repeat => "cycle the repeat mode",
             Self::ToggleFakeTrackRepeatMode => "toggle fake track repeat mode",
             Self::Shuffle => "toggle the shuffle mode",
-            Self::VolumeUp => "increase playback volume by 5%",
-            Self::VolumeDown => "decrease playback volume by 5%",
+            Self::VolumeUp => "increase playback volume by `volume_step_in_percent`",
+            Self::VolumeDown => "decrease playback volume by `volume_step_in_percent`",
             Self::Mute => "toggle playback volume between 0% and previous level",
-            Self::SeekForward => "seek forward by 5s",
-            Self::SeekBackward => "seek backward by 5s",
+            Self::SeekForward => "seek forward by `seek_step_in_secs`",
+            Self::SeekBackward => "seek backward by `seek_step_in_secs`",
             Self::Quit => "quit the application",
             Self::ClosePopup => "close a popup",
             #[cfg(feature = "streaming")]
             Self::RestartIntegratedClient => "restart the integrated librespot client",
+            #[cfg(feature = "streaming")]
+            Self::ClearAudioCache => "clear the integrated librespot client's audio cache",
+            #[cfg(feature = "streaming")]
+            Self::OpenPlaybackSettings => {
+                "open a popup for toggling the integrated librespot client's playback settings"
+            }
             Self::SelectNextOrScrollDown => "select the next item in a list/table or scroll down",
             Self::SelectPreviousOrScrollUp => {
                 "select the previous item in a list/table or scroll up"
@@ -234,6 +278,11 @@ impl Command {
             Self::ShowActionsOnSelectedItem => "open a popup showing actions on a selected item",
             Self::ShowActionsOnCurrentTrack => "open a popup showing actions on the current track",
             Self::AddSelectedItemToQueue => "add the selected item to queue",
+            Self::CopySelectedItemLink => "copy the Spotify link of the selected item",
+            Self::CopySelectedItemUri => "copy the Spotify URI of the selected item",
+            Self::OpenSelectedItemExternally => {
+                "open the selected item in the Spotify client or the web browser"
+            }
             Self::FocusNextWindow => "focus the next focusable window (if any)",
             Self::FocusPreviousWindow => "focus the previous focusable window (if any)",
             Self::SwitchTheme => "open a popup for switching theme",
@@ -252,6 +301,16 @@ impl Command {
             Self::SearchPage => "go to the search page",
             Self::BrowsePage => "go to the browse page",
             Self::Queue => "go to the queue page",
+            Self::ToggleQueuePane => "toggle the queue pane next to the main layout",
+            Self::ToggleLogPane => "toggle the pane showing the application's logs",
+            Self::ToggleVisualizerPane => "toggle the audio visualizer pane below the main layout",
+            Self::CycleLogPaneLevel => "change the minimum level of logs shown in the log pane",
+            Self::OpenDiagnostics => "open a popup showing the application's diagnostics",
+            Self::ExportScreenshot => "export the current frame to a plain-text and an ANSI file",
+            Self::ReloadConfig => "reload the application's configuration files",
+            Self::RecordMacro => "start/stop recording a keyboard macro",
+            Self::ReplayMacro => "replay a recorded keyboard macro",
+            Self::SwitchProfile => "switch to another profile",
             Self::OpenCommandHelp => "go to the command help page",
             Self::PreviousPage => "go to the previous page",
             Self::OpenSpotifyLinkFromClipboard => "open a Spotify link from clipboard",
@@ -261,6 +320,18 @@ impl Command {
             Self::SortTrackByDuration => "sort the track table (if any) by track's duration",
             Self::SortTrackByAddedDate => "sort the track table (if any) by track's added date",
             Self::ReverseTrackOrder => "reverse the order of the track table (if any)",
+            Self::CopyTrackTableAsCsv => "copy the track table (if any) as CSV",
+            Self::CopyTrackTableAsMarkdown => "copy the track table (if any) as a Markdown table",
+            Self::ExportTrackTableAsCsv => "export the track table (if any) to a CSV file",
+            Self::ExportTrackTableAsMarkdown => {
+                "export the track table (if any) to a Markdown file"
+            }
+            Self::ToggleTrackTableColumns => "show or hide the track table's columns",
+            Self::ToggleGroupByAlbum => {
+                "toggle grouping tracks by album in the track table (if any)"
+            }
+            Self::SelectNextGroup => "select the next group in the track table (if any)",
+            Self::SelectPreviousGroup => "select the previous group in the track table (if any)",
             Self::MovePlaylistItemUp => "move playlist item up one position",
             Self::MovePlaylistItemDown => "move playlist item down one position",
             Self::CreatePlaylist => "create a new playlist",
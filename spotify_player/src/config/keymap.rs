@@ -2,14 +2,34 @@ use crate::{
     command::Command,
     key::{Key, KeySequence},
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 /// Application's keymap configurations
 pub struct KeymapConfig {
+    #[serde(default)]
+    pub preset: KeymapPreset,
     #[serde(default)]
     pub keymaps: Vec<Keymap>,
+    #[cfg(feature = "scripting")]
+    #[serde(default)]
+    pub scripts: Vec<ScriptKeymap>,
+    #[cfg(feature = "plugins")]
+    #[serde(default)]
+    pub plugin_commands: Vec<PluginKeymap>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+/// A built-in set of keymaps applied on top of the default keymaps
+pub enum KeymapPreset {
+    /// the default (Vim-style) keymaps
+    #[default]
+    Vim,
+    /// Emacs-style navigation keymaps
+    Emacs,
+    /// keymaps using arrow and function keys
+    Arrows,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -19,9 +39,29 @@ pub struct Keymap {
     pub command: Command,
 }
 
+#[cfg(feature = "scripting")]
+#[derive(Clone, Debug, Deserialize)]
+/// A keymap that maps a `KeySequence` to a user-defined script
+pub struct ScriptKeymap {
+    pub key_sequence: KeySequence,
+    /// the script's name, whose file is `scripts/<name>.rhai` inside the config folder
+    pub name: String,
+}
+
+#[cfg(feature = "plugins")]
+#[derive(Clone, Debug, Deserialize)]
+/// A keymap that maps a `KeySequence` to a plugin's command
+pub struct PluginKeymap {
+    pub key_sequence: KeySequence,
+    /// the plugin's name, whose file is `plugins/<name>.wasm` inside the config folder
+    pub plugin: String,
+    pub command: String,
+}
+
 impl Default for KeymapConfig {
     fn default() -> Self {
         KeymapConfig {
+            preset: KeymapPreset::Vim,
             keymaps: vec![
                 Keymap {
                     key_sequence: "n".into(),
@@ -87,6 +127,46 @@ impl Default for KeymapConfig {
                     key_sequence: "z".into(),
                     command: Command::Queue,
                 },
+                Keymap {
+                    key_sequence: "W".into(),
+                    command: Command::ToggleQueuePane,
+                },
+                Keymap {
+                    key_sequence: "L".into(),
+                    command: Command::ToggleLogPane,
+                },
+                Keymap {
+                    key_sequence: "V".into(),
+                    command: Command::ToggleVisualizerPane,
+                },
+                Keymap {
+                    key_sequence: "C-l".into(),
+                    command: Command::CycleLogPaneLevel,
+                },
+                Keymap {
+                    key_sequence: "g D".into(),
+                    command: Command::OpenDiagnostics,
+                },
+                Keymap {
+                    key_sequence: "C-e".into(),
+                    command: Command::ExportScreenshot,
+                },
+                Keymap {
+                    key_sequence: "g R".into(),
+                    command: Command::ReloadConfig,
+                },
+                Keymap {
+                    key_sequence: "Q".into(),
+                    command: Command::RecordMacro,
+                },
+                Keymap {
+                    key_sequence: "@".into(),
+                    command: Command::ReplayMacro,
+                },
+                Keymap {
+                    key_sequence: "g P".into(),
+                    command: Command::SwitchProfile,
+                },
                 Keymap {
                     key_sequence: "C-z".into(),
                     command: Command::AddSelectedItemToQueue,
@@ -95,6 +175,18 @@ impl Default for KeymapConfig {
                     key_sequence: "Z".into(),
                     command: Command::AddSelectedItemToQueue,
                 },
+                Keymap {
+                    key_sequence: "y y".into(),
+                    command: Command::CopySelectedItemLink,
+                },
+                Keymap {
+                    key_sequence: "y u".into(),
+                    command: Command::CopySelectedItemUri,
+                },
+                Keymap {
+                    key_sequence: "g o".into(),
+                    command: Command::OpenSelectedItemExternally,
+                },
                 Keymap {
                     key_sequence: "C-space".into(),
                     command: Command::ShowActionsOnSelectedItem,
@@ -112,6 +204,16 @@ impl Default for KeymapConfig {
                     key_sequence: "R".into(),
                     command: Command::RestartIntegratedClient,
                 },
+                #[cfg(feature = "streaming")]
+                Keymap {
+                    key_sequence: "g C".into(),
+                    command: Command::ClearAudioCache,
+                },
+                #[cfg(feature = "streaming")]
+                Keymap {
+                    key_sequence: "g S".into(),
+                    command: Command::OpenPlaybackSettings,
+                },
                 Keymap {
                     key_sequence: "tab".into(),
                     command: Command::FocusNextWindow,
@@ -290,6 +392,38 @@ impl Default for KeymapConfig {
                     key_sequence: "s r".into(),
                     command: Command::ReverseTrackOrder,
                 },
+                Keymap {
+                    key_sequence: "y c".into(),
+                    command: Command::CopyTrackTableAsCsv,
+                },
+                Keymap {
+                    key_sequence: "y m".into(),
+                    command: Command::CopyTrackTableAsMarkdown,
+                },
+                Keymap {
+                    key_sequence: "y C".into(),
+                    command: Command::ExportTrackTableAsCsv,
+                },
+                Keymap {
+                    key_sequence: "y M".into(),
+                    command: Command::ExportTrackTableAsMarkdown,
+                },
+                Keymap {
+                    key_sequence: "s c".into(),
+                    command: Command::ToggleTrackTableColumns,
+                },
+                Keymap {
+                    key_sequence: "s g".into(),
+                    command: Command::ToggleGroupByAlbum,
+                },
+                Keymap {
+                    key_sequence: "]".into(),
+                    command: Command::SelectNextGroup,
+                },
+                Keymap {
+                    key_sequence: "[".into(),
+                    command: Command::SelectPreviousGroup,
+                },
                 Keymap {
                     key_sequence: "C-k".into(),
                     command: Command::MovePlaylistItemUp,
@@ -303,17 +437,74 @@ impl Default for KeymapConfig {
                     command: Command::CreatePlaylist,
                 },
             ],
+            #[cfg(feature = "scripting")]
+            scripts: vec![],
+            #[cfg(feature = "plugins")]
+            plugin_commands: vec![],
         }
     }
 }
 
+impl KeymapPreset {
+    /// gets the keymaps applied on top of the default keymaps by the preset
+    fn keymaps(self) -> Vec<Keymap> {
+        let keymaps: &[(&str, Command)] = match self {
+            Self::Vim => &[],
+            Self::Emacs => &[
+                ("C-g", Command::ClosePopup),
+                ("C-v", Command::PageSelectNextOrScrollDown),
+                ("M-v", Command::PageSelectPreviousOrScrollUp),
+                ("M-<", Command::SelectFirstOrScrollToTop),
+                ("M->", Command::SelectLastOrScrollToBottom),
+                ("C-s", Command::Search),
+                ("M-s", Command::Shuffle),
+                ("C-x C-c", Command::Quit),
+                ("C-x b", Command::SwitchDevice),
+            ],
+            Self::Arrows => &[
+                ("left", Command::SeekBackward),
+                ("right", Command::SeekForward),
+                ("C-left", Command::PreviousTrack),
+                ("C-right", Command::NextTrack),
+                ("C-up", Command::VolumeUp),
+                ("C-down", Command::VolumeDown),
+                ("f1", Command::OpenCommandHelp),
+                ("f2", Command::LibraryPage),
+                ("f3", Command::SearchPage),
+                ("f4", Command::CurrentlyPlayingContextPage),
+                ("f5", Command::RefreshPlayback),
+            ],
+        };
+        keymaps
+            .iter()
+            .map(|&(key_sequence, command)| Keymap {
+                key_sequence: key_sequence.into(),
+                command,
+            })
+            .collect()
+    }
+}
+
 impl KeymapConfig {
-    pub fn new(path: &std::path::Path) -> Result<Self> {
+    pub fn new(folders: &[std::path::PathBuf]) -> Result<Self> {
         let mut config = Self::default();
-        config.parse_config_file(path)?;
+        for path in folders {
+            config.parse_config_file(path)?;
+        }
+        // the default and preset keymaps are checked even if no keymap config file is found
+        config
+            .check_keymaps()
+            .context("invalid default or preset keymaps")?;
 
         Ok(config)
     }
+
+    /// checks the current keymaps and the keymaps of the current preset for
+    /// duplicated or conflicted key sequences
+    fn check_keymaps(&self) -> Result<()> {
+        check_duplicated_keymaps(&self.preset.keymaps())?;
+        self.check_conflicted_keymaps()
+    }
     /// parses a list of keymaps from the keymap config file in `path` folder
     /// and updates the current keymaps accordingly.
     fn parse_config_file(&mut self, path: &std::path::Path) -> Result<()> {
@@ -325,25 +516,83 @@ impl KeymapConfig {
                 );
             }
             Ok(content) => {
-                let mut keymaps = toml::from_str::<Self>(&content)?.keymaps;
-                std::mem::swap(&mut self.keymaps, &mut keymaps);
-                // a dumb approach (with quadratic complexity) to merge two different keymap arrays
-                // while keeping the invariant:
-                // - each `KeySequence` is mapped to only one `Command`.
-                keymaps.into_iter().for_each(|keymap| {
-                    if !self
-                        .keymaps
-                        .iter()
-                        .any(|k| k.key_sequence == keymap.key_sequence)
-                    {
-                        self.keymaps.push(keymap);
-                    }
-                });
+                let config = toml::from_str::<Self>(&content).with_context(|| {
+                    format!("failed to parse the keymap config file {file_path:?}")
+                })?;
+                check_duplicated_keymaps(&config.keymaps).with_context(|| {
+                    format!("invalid keymaps in the keymap config file {file_path:?}")
+                })?;
+                self.preset = config.preset;
+                self.merge_keymaps(config.preset.keymaps());
+                self.merge_keymaps(config.keymaps);
+                #[cfg(feature = "scripting")]
+                self.scripts.extend(config.scripts);
+                #[cfg(feature = "plugins")]
+                self.plugin_commands.extend(config.plugin_commands);
+                self.check_conflicted_keymaps().with_context(|| {
+                    format!(
+                        "conflicted keymaps after applying the keymap config file {file_path:?}"
+                    )
+                })?;
             }
         }
         Ok(())
     }
 
+    /// merges a list of keymaps into the current keymaps, in which
+    /// the new keymaps override the current keymaps with the same key sequences
+    fn merge_keymaps(&mut self, mut keymaps: Vec<Keymap>) {
+        std::mem::swap(&mut self.keymaps, &mut keymaps);
+        // a dumb approach (with quadratic complexity) to merge two different keymap arrays
+        // while keeping the invariant:
+        // - each `KeySequence` is mapped to only one `Command`.
+        keymaps.into_iter().for_each(|keymap| {
+            if !self
+                .keymaps
+                .iter()
+                .any(|k| k.key_sequence == keymap.key_sequence)
+            {
+                self.keymaps.push(keymap);
+            }
+        });
+    }
+
+    /// checks if there is a key sequence which is a prefix of another key sequence.
+    /// The longer key sequence in such case can never be triggered because the command
+    /// of the shorter one is executed first.
+    fn check_conflicted_keymaps(&self) -> Result<()> {
+        let keymaps = self
+            .keymaps
+            .iter()
+            .filter(|k| k.command != Command::None)
+            .collect::<Vec<_>>();
+        let conflicts = keymaps
+            .iter()
+            .flat_map(|&x| {
+                keymaps
+                    .iter()
+                    .filter(move |&&y| {
+                        x.key_sequence.keys.len() < y.key_sequence.keys.len()
+                            && x.key_sequence.is_prefix(&y.key_sequence)
+                    })
+                    .map(move |&y| {
+                        format!(
+                            "\"{}\" ({:?}) shadows \"{}\" ({:?})",
+                            x.key_sequence, x.command, y.key_sequence, y.command
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        if !conflicts.is_empty() {
+            anyhow::bail!(
+                "{}. Map one of the conflicted key sequences to another key sequence or to the `None` command.",
+                conflicts.join(", ")
+            );
+        }
+        Ok(())
+    }
+
     /// finds all keymaps whose mapped key sequence has a given `prefix` key sequence as its prefix
     pub fn find_matched_prefix_keymaps(&self, prefix: &KeySequence) -> Vec<&Keymap> {
         self.keymaps
@@ -352,6 +601,44 @@ impl KeymapConfig {
             .collect()
     }
 
+    #[cfg(feature = "scripting")]
+    /// checks if a key sequence is a prefix of any script keymap's key sequence
+    pub fn has_matched_prefix_scripts(&self, prefix: &KeySequence) -> bool {
+        self.scripts
+            .iter()
+            .any(|keymap| prefix.is_prefix(&keymap.key_sequence))
+    }
+
+    #[cfg(feature = "scripting")]
+    /// finds a script's name from a mapped key sequence
+    pub fn find_script_from_key_sequence(&self, key_sequence: &KeySequence) -> Option<&str> {
+        self.scripts
+            .iter()
+            .rev()
+            .find(|&keymap| keymap.key_sequence == *key_sequence)
+            .map(|keymap| keymap.name.as_str())
+    }
+
+    #[cfg(feature = "plugins")]
+    /// checks if a key sequence is a prefix of any plugin command keymap's key sequence
+    pub fn has_matched_prefix_plugin_commands(&self, prefix: &KeySequence) -> bool {
+        self.plugin_commands
+            .iter()
+            .any(|keymap| prefix.is_prefix(&keymap.key_sequence))
+    }
+
+    #[cfg(feature = "plugins")]
+    /// finds a plugin's command from a mapped key sequence
+    pub fn find_plugin_command_from_key_sequence(
+        &self,
+        key_sequence: &KeySequence,
+    ) -> Option<&PluginKeymap> {
+        self.plugin_commands
+            .iter()
+            .rev()
+            .find(|&keymap| keymap.key_sequence == *key_sequence)
+    }
+
     /// finds a command from a mapped key sequence
     pub fn find_command_from_key_sequence(&self, key_sequence: &KeySequence) -> Option<Command> {
         self.keymaps
@@ -361,12 +648,35 @@ impl KeymapConfig {
     }
 }
 
+/// checks if a key sequence is mapped more than once in a list of keymaps
+fn check_duplicated_keymaps(keymaps: &[Keymap]) -> Result<()> {
+    let mut duplicates = vec![];
+    for (i, x) in keymaps.iter().enumerate() {
+        if let Some(y) = keymaps[i + 1..]
+            .iter()
+            .find(|y| y.key_sequence == x.key_sequence)
+        {
+            duplicates.push(format!(
+                "\"{}\" is mapped to both {:?} and {:?}",
+                x.key_sequence, x.command, y.command
+            ));
+        }
+    }
+
+    if !duplicates.is_empty() {
+        anyhow::bail!("{}", duplicates.join(", "));
+    }
+    Ok(())
+}
+
 impl Keymap {
     pub fn include_in_help_screen(&self) -> bool {
         !matches!(&self.command, Command::None)
     }
 }
 
+impl crate::state::Searchable for Keymap {}
+
 impl std::fmt::Display for Keymap {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -398,3 +708,58 @@ impl From<&str> for KeySequence {
         Self::from_str(s).unwrap_or_else(|| panic!("invalid key sequence {s}"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymaps(keymaps: &[(&str, Command)]) -> Vec<Keymap> {
+        keymaps
+            .iter()
+            .map(|&(key_sequence, command)| Keymap {
+                key_sequence: key_sequence.into(),
+                command,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn default_and_preset_keymaps_are_valid() {
+        let default = KeymapConfig::default();
+        assert!(check_duplicated_keymaps(&default.keymaps).is_ok());
+        for preset in [KeymapPreset::Vim, KeymapPreset::Emacs, KeymapPreset::Arrows] {
+            let mut config = KeymapConfig {
+                preset,
+                ..Default::default()
+            };
+            config.merge_keymaps(preset.keymaps());
+            assert!(config.check_keymaps().is_ok(), "{preset:?}");
+        }
+    }
+
+    #[test]
+    fn duplicated_keymaps_are_rejected() {
+        let keymaps = keymaps(&[
+            ("n", Command::NextTrack),
+            ("p", Command::PreviousTrack),
+            ("n", Command::Shuffle),
+        ]);
+        let err = check_duplicated_keymaps(&keymaps).unwrap_err().to_string();
+        assert!(
+            err.contains("\"n\" is mapped to both NextTrack and Shuffle"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn prefix_conflicts_between_key_sequences_are_rejected() {
+        let mut config = KeymapConfig::default();
+        config.merge_keymaps(keymaps(&[("g", Command::NextTrack)]));
+        let err = config.check_conflicted_keymaps().unwrap_err().to_string();
+        assert!(err.contains("\"g\" (NextTrack) shadows \"g g\""), "{err}");
+
+        // a key sequence mapped to the `None` command doesn't shadow other key sequences
+        config.merge_keymaps(keymaps(&[("g", Command::None)]));
+        assert!(config.check_conflicted_keymaps().is_ok());
+    }
+}
@@ -1,46 +1,132 @@
 mod keymap;
 mod theme;
+mod wizard;
 
+const APP_FOLDER_NAME: &str = "spotify-player";
+/// the environment variable overriding the `client_id` config option
+const CLIENT_ID_ENV: &str = "SPOTIFY_CLIENT_ID";
 const DEFAULT_CONFIG_FOLDER: &str = ".config/spotify-player";
 const DEFAULT_CACHE_FOLDER: &str = ".cache/spotify-player";
 const APP_CONFIG_FILE: &str = "app.toml";
 const THEME_CONFIG_FILE: &str = "theme.toml";
 const KEYMAP_CONFIG_FILE: &str = "keymap.toml";
+const PROFILES_FOLDER: &str = "profiles";
+/// the `librespot`-owned subfolder of the audio cache folder storing the cached audio files
+const AUDIO_CACHE_FILES_FOLDER: &str = "files";
+/// the range (in dB) of the normalization pre-gain, same as `librespot`'s
+pub const NORMALIZATION_PREGAIN_RANGE: std::ops::RangeInclusive<f64> = -10.0..=10.0;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use config_parser2::*;
 use librespot_core::config::SessionConfig;
+use parking_lot::RwLock;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     path::{Path, PathBuf},
-    sync::OnceLock,
+    sync::Arc,
 };
 
 use keymap::*;
 use theme::*;
 
 pub use theme::Theme;
+pub use wizard::run_first_launch_wizard;
 
-static CONFIGS: OnceLock<Configs> = OnceLock::new();
+/// the application's configurations, which are replaced with new ones on a reload.
+///
+/// Previous configurations are dropped once the last reference to them is released.
+static CONFIGS: RwLock<Option<Arc<Configs>>> = parking_lot::const_rwlock(None);
 
 #[derive(Debug)]
 pub struct Configs {
     pub app_config: AppConfig,
     pub keymap_config: KeymapConfig,
     pub theme_config: ThemeConfig,
+    pub config_folder: std::path::PathBuf,
     pub cache_folder: std::path::PathBuf,
+    /// the name of the profile whose configurations override the default configurations (if any)
+    pub profile: Option<String>,
 }
 
 impl Configs {
-    pub fn new(config_folder: &std::path::Path, cache_folder: &std::path::Path) -> Result<Self> {
+    pub fn new(
+        config_folder: &std::path::Path,
+        cache_folder: &std::path::Path,
+        profile: Option<&str>,
+    ) -> Result<Self> {
+        let folders = config_folders(config_folder, profile);
         Ok(Self {
-            app_config: AppConfig::new(config_folder)?,
-            keymap_config: KeymapConfig::new(config_folder)?,
-            theme_config: ThemeConfig::new(config_folder)?,
+            app_config: AppConfig::new(&folders)?,
+            keymap_config: KeymapConfig::new(&folders)?,
+            theme_config: ThemeConfig::new(&folders)?,
+            config_folder: config_folder.to_path_buf(),
             cache_folder: cache_folder.to_path_buf(),
+            profile: profile.map(String::from),
         })
     }
+
+    /// gets the folder storing the integrated device's cached audio files, which is
+    /// a `librespot`-owned subfolder of the `device.audio_cache_folder` folder
+    pub fn audio_cache_folder(&self) -> PathBuf {
+        self.app_config
+            .device
+            .audio_cache_folder
+            .clone()
+            .unwrap_or_else(|| self.cache_folder.join("audio"))
+            .join(AUDIO_CACHE_FILES_FOLDER)
+    }
+}
+
+/// gets the folders containing the application's config files, in which
+/// the config files in the profile's folder override the ones in the application's config folder
+fn config_folders(config_folder: &Path, profile: Option<&str>) -> Vec<PathBuf> {
+    let mut folders = vec![config_folder.to_path_buf()];
+    if let Some(profile) = profile {
+        folders.push(get_profile_config_folder(config_folder, profile));
+    }
+    folders
+}
+
+/// checks that a profile name is valid, i.e. only consists of ASCII letters, digits, `-` and `_`,
+/// so that the name can't escape the profiles' folders when joined into a path
+pub fn validate_profile_name(name: &str) -> Result<String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!("invalid profile name {name:?}, which must only consist of ASCII letters, digits, `-` and `_`");
+    }
+    Ok(name.to_string())
+}
+
+/// gets the config folder of a profile, whose config files override the default config files
+pub fn get_profile_config_folder(config_folder: &Path, profile: &str) -> PathBuf {
+    config_folder.join(PROFILES_FOLDER).join(profile)
+}
+
+/// gets the cache folder of a profile, which partitions the profile's cached
+/// data (credentials, tokens, etc) from other profiles
+pub fn get_profile_cache_folder(cache_folder: &Path, profile: &str) -> PathBuf {
+    cache_folder.join(PROFILES_FOLDER).join(profile)
+}
+
+/// gets the names of the profiles defined in the application's config folder
+pub fn get_profiles(config_folder: &Path) -> Vec<String> {
+    let mut profiles = std::fs::read_dir(config_folder.join(PROFILES_FOLDER))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .filter(|name| validate_profile_name(name).is_ok())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    profiles.sort();
+    profiles
 }
 
 #[derive(Debug, Deserialize, Serialize, ConfigParse)]
@@ -51,7 +137,27 @@ pub struct AppConfig {
 
     pub client_port: u16,
 
+    #[cfg(feature = "http-api")]
+    pub http_api_address: Option<String>,
+    #[cfg(feature = "http-api")]
+    pub http_api_token: Option<Secret>,
+    #[cfg(feature = "metrics")]
+    pub metrics_address: Option<String>,
+    #[cfg(feature = "opentelemetry")]
+    pub otlp_endpoint: Option<String>,
+    #[cfg(feature = "mpd")]
+    pub mpd_address: Option<String>,
+
+    pub client_request_max_attempts: u32,
+
+    pub log_format: LogFormat,
+    pub log_levels: Option<BTreeMap<String, String>>,
+
     pub player_event_hook_command: Option<Command>,
+    pub event_hooks: EventHooks,
+
+    #[cfg(feature = "mqtt")]
+    pub mqtt: Option<MqttConfig>,
 
     pub playback_format: String,
     #[cfg(feature = "notify")]
@@ -67,14 +173,26 @@ pub struct AppConfig {
 
     // duration configs
     pub app_refresh_duration_in_ms: u64,
+    pub max_fps: u32,
     pub playback_refresh_duration_in_ms: u64,
+    pub key_sequence_timeout_in_ms: u64,
 
     pub page_size_in_rows: usize,
 
+    pub seek_step_in_secs: u32,
+    pub volume_step_in_percent: u8,
+
+    pub start_page: StartPage,
+
+    // marquee configs
+    pub enable_marquee: bool,
+    pub marquee_speed_in_ms: u64,
+
     // icon configs
     pub play_icon: String,
     pub pause_icon: String,
     pub liked_icon: String,
+    pub icon_set: IconSet,
 
     // layout configs
     pub border_type: BorderType,
@@ -91,6 +209,8 @@ pub struct AppConfig {
 
     pub playback_window_width: usize,
 
+    pub enable_queue_pane: bool,
+
     #[cfg(feature = "media-control")]
     pub enable_media_control: bool,
 
@@ -100,8 +220,18 @@ pub struct AppConfig {
     #[cfg(feature = "notify")]
     pub enable_notify: bool,
 
+    #[cfg(feature = "keyring")]
+    pub enable_keyring: bool,
+
+    pub enable_cache_encryption: bool,
+
     pub enable_cover_image_cache: bool,
 
+    pub enable_terminal_title: bool,
+
+    #[cfg(unix)]
+    pub enable_rpc_socket: bool,
+
     pub default_device: String,
 
     pub device: DeviceConfig,
@@ -110,6 +240,27 @@ pub struct AppConfig {
     pub notify_streaming_only: bool,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+/// The page opened on startup
+pub enum StartPage {
+    Library,
+    LikedTracks,
+    /// the last playlist, album or artist page browsed in the previous session
+    LastContext,
+    Search,
+}
+config_parser_impl!(StartPage);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+/// The format of the application's log lines
+pub enum LogFormat {
+    /// human-readable lines
+    Text,
+    /// newline-delimited JSON objects, which can be ingested by log collectors
+    Json,
+}
+config_parser_impl!(LogFormat);
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub enum Position {
     Top,
@@ -127,6 +278,18 @@ pub enum BorderType {
 }
 config_parser_impl!(BorderType);
 
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum IconSet {
+    /// Unicode icons, the play/pause/liked icons are configured by
+    /// `play_icon`, `pause_icon` and `liked_icon` options
+    Unicode,
+    /// Icons using [Nerd Fonts](https://www.nerdfonts.com/) glyphs
+    NerdFont,
+    /// Pure ASCII icons
+    Ascii,
+}
+config_parser_impl!(IconSet);
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub enum ProgressBarType {
     Line,
@@ -141,6 +304,15 @@ pub struct Command {
     pub args: Vec<String>,
 }
 
+#[derive(Debug, Default, Deserialize, Serialize, ConfigParse, Clone)]
+/// Hook commands executed upon player events, regardless of the playing device
+pub struct EventHooks {
+    pub track_changed: Option<Command>,
+    pub playback_started: Option<Command>,
+    pub playback_stopped: Option<Command>,
+    pub track_liked: Option<Command>,
+}
+
 #[derive(Debug, Deserialize, Serialize, ConfigParse, Clone)]
 /// Application device configurations
 pub struct DeviceConfig {
@@ -149,7 +321,20 @@ pub struct DeviceConfig {
     pub volume: u8,
     pub bitrate: u16,
     pub audio_cache: bool,
+    /// the folder storing the cached audio files, `$APP_CACHE_FOLDER/audio` by default
+    pub audio_cache_folder: Option<PathBuf>,
+    /// the maximum size (in MB) of the cached audio files
+    pub audio_cache_size_limit_in_mb: Option<u64>,
     pub normalization: bool,
+    /// the pre-gain (in dB) applied by the audio normalization
+    pub normalization_pregain: f64,
+    pub gapless: bool,
+    /// the duration (in ms) of the volume fade-out on pause and fade-in on resume, `0` to disable fades
+    pub fade_duration_in_ms: u64,
+    /// the `librespot` audio backend used to play audio
+    pub audio_backend: Option<String>,
+    /// the output device passed to the audio backend
+    pub audio_device: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, ConfigParse, Clone)]
@@ -159,8 +344,45 @@ pub struct NotifyFormat {
     pub body: String,
 }
 
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(transparent)]
+#[cfg(any(feature = "http-api", feature = "mqtt"))]
+/// A secret config value (e.g. a token), which is redacted when the configurations are logged
+pub struct Secret(String);
+
+#[cfg(any(feature = "http-api", feature = "mqtt"))]
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(any(feature = "http-api", feature = "mqtt"))]
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("\"<redacted>\"")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+#[cfg(feature = "mqtt")]
+/// Configurations of the MQTT broker to publish the playback's state to
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<Secret>,
+    /// the prefix of the published messages' topics
+    pub topic: String,
+    pub home_assistant_discovery: bool,
+    pub home_assistant_prefix: String,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(from = "StreamingTypeOrBool")]
+#[cfg_attr(not(feature = "streaming"), allow(dead_code))]
 pub enum StreamingType {
     Always,
     DaemonOnly,
@@ -170,6 +392,7 @@ config_parser_impl!(StreamingType);
 
 // For backward compatibility, to accept booleans for enable_streaming
 #[derive(Deserialize)]
+#[cfg_attr(not(feature = "streaming"), allow(dead_code))]
 enum RawStreamingType {
     Always,
     DaemonOnly,
@@ -178,6 +401,7 @@ enum RawStreamingType {
 
 #[derive(Deserialize)]
 #[serde(untagged)]
+#[cfg_attr(not(feature = "streaming"), allow(dead_code))]
 enum StreamingTypeOrBool {
     Bool(bool),
     Type(RawStreamingType),
@@ -217,6 +441,22 @@ impl Default for AppConfig {
 
             client_port: 8080,
 
+            #[cfg(feature = "http-api")]
+            http_api_address: None,
+            #[cfg(feature = "http-api")]
+            http_api_token: None,
+            #[cfg(feature = "metrics")]
+            metrics_address: None,
+            #[cfg(feature = "opentelemetry")]
+            otlp_endpoint: None,
+            #[cfg(feature = "mpd")]
+            mpd_address: None,
+
+            client_request_max_attempts: 3,
+
+            log_format: LogFormat::Text,
+            log_levels: None,
+
             tracks_playback_limit: 50,
 
             playback_format: String::from("{track} • {artists}\n{album}\n{metadata}"),
@@ -229,17 +469,30 @@ impl Default for AppConfig {
             notify_timeout_in_secs: 0,
 
             player_event_hook_command: None,
+            event_hooks: EventHooks::default(),
+
+            #[cfg(feature = "mqtt")]
+            mqtt: None,
 
             proxy: None,
             ap_port: None,
             app_refresh_duration_in_ms: 32,
+            max_fps: 30,
             playback_refresh_duration_in_ms: 0,
+            key_sequence_timeout_in_ms: 1000,
 
             page_size_in_rows: 20,
+            seek_step_in_secs: 5,
+            volume_step_in_percent: 5,
+            start_page: StartPage::Library,
+
+            enable_marquee: true,
+            marquee_speed_in_ms: 250,
 
             pause_icon: "▌▌".to_string(),
             play_icon: "▶".to_string(),
             liked_icon: "♥".to_string(),
+            icon_set: IconSet::Unicode,
 
             border_type: BorderType::Plain,
             progress_bar_type: ProgressBarType::Rectangle,
@@ -255,6 +508,8 @@ impl Default for AppConfig {
 
             playback_window_width: 6,
 
+            enable_queue_pane: false,
+
             // Because of the "creating new window and stealing focus" behaviour
             // when running the media control event loop on startup,
             // media control support is disabled by default for Windows and MacOS.
@@ -272,8 +527,18 @@ impl Default for AppConfig {
             #[cfg(feature = "notify")]
             enable_notify: true,
 
+            #[cfg(feature = "keyring")]
+            enable_keyring: false,
+
+            enable_cache_encryption: false,
+
             enable_cover_image_cache: true,
 
+            enable_terminal_title: false,
+
+            #[cfg(unix)]
+            enable_rpc_socket: false,
+
             default_device: "spotify-player".to_string(),
 
             device: DeviceConfig::default(),
@@ -292,29 +557,174 @@ impl Default for DeviceConfig {
             volume: 70,
             bitrate: 320,
             audio_cache: false,
+            audio_cache_folder: None,
+            audio_cache_size_limit_in_mb: None,
             normalization: false,
+            normalization_pregain: 0.0,
+            gapless: true,
+            fade_duration_in_ms: 0,
+            audio_backend: None,
+            audio_device: None,
+        }
+    }
+}
+
+#[cfg(feature = "mqtt")]
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 1883,
+            client_id: "spotify-player".to_string(),
+            username: None,
+            password: None,
+            topic: "spotify-player".to_string(),
+            home_assistant_discovery: false,
+            home_assistant_prefix: "homeassistant".to_string(),
         }
     }
 }
 
 impl AppConfig {
-    pub fn new(path: &Path) -> Result<Self> {
+    /// creates the application configurations from the config files in `folders`,
+    /// a config file overrides the configurations from the config files in the previous folders
+    pub fn new(folders: &[PathBuf]) -> Result<Self> {
         let mut config = Self::default();
-        if !config.parse_config_file(path)? {
-            config.write_config_file(path)?
+        for (i, path) in folders.iter().enumerate() {
+            // only write the default config file into the application's config folder
+            if !config.parse_config_file(path)? && i == 0 {
+                config.write_config_file(path)?
+            }
         }
+        config.apply_env_overrides();
+        config.validate().with_context(|| {
+            format!(
+                "invalid configurations in the application config files {:?}",
+                folders
+                    .iter()
+                    .map(|path| path.join(APP_CONFIG_FILE))
+                    .collect::<Vec<_>>()
+            )
+        })?;
 
         Ok(config)
     }
 
+    /// overrides the configurations specified by environment variables,
+    /// which take precedence over the config files
+    fn apply_env_overrides(&mut self) {
+        if let Ok(client_id) = std::env::var(CLIENT_ID_ENV) {
+            if !client_id.is_empty() {
+                self.client_id = client_id;
+            }
+        }
+    }
+
+    /// validates the configuration values that cannot be checked when parsing the config file.
+    /// An unsupported value that was accepted by previous versions is replaced by its default.
+    fn validate(&mut self) -> Result<()> {
+        let mut errors = vec![];
+        if self.app_refresh_duration_in_ms == 0 {
+            errors.push("`app_refresh_duration_in_ms` must be greater than 0".to_string());
+        }
+        if self.max_fps == 0 {
+            errors.push("`max_fps` must be greater than 0".to_string());
+        }
+        if self.page_size_in_rows == 0 {
+            errors.push("`page_size_in_rows` must be greater than 0".to_string());
+        }
+        if self.seek_step_in_secs == 0 {
+            errors.push("`seek_step_in_secs` must be greater than 0".to_string());
+        }
+        if !(1..=100).contains(&self.volume_step_in_percent) {
+            errors.push(format!(
+                "`volume_step_in_percent` must be between 1 and 100, got {}",
+                self.volume_step_in_percent
+            ));
+        }
+        if self.tracks_playback_limit == 0 {
+            errors.push("`tracks_playback_limit` must be greater than 0".to_string());
+        }
+        if self.client_port == 0 {
+            errors.push("`client_port` must be a non-zero port number".to_string());
+        }
+        if self.client_request_max_attempts == 0 {
+            errors.push("`client_request_max_attempts` must be greater than 0".to_string());
+        }
+        if self.device.volume > 100 {
+            errors.push(format!(
+                "`device.volume` must be between 0 and 100, got {}",
+                self.device.volume
+            ));
+        }
+        if ![96, 160, 320].contains(&self.device.bitrate) {
+            let default = DeviceConfig::default().bitrate;
+            // logging is not initialized at this point
+            eprintln!(
+                "Unsupported `device.bitrate` {}, which must be one of 96, 160 or 320. Use the default bitrate {default} instead",
+                self.device.bitrate
+            );
+            self.device.bitrate = default;
+        }
+        if !NORMALIZATION_PREGAIN_RANGE.contains(&self.device.normalization_pregain) {
+            errors.push(format!(
+                "`device.normalization_pregain` must be between {} and {}, got {}",
+                NORMALIZATION_PREGAIN_RANGE.start(),
+                NORMALIZATION_PREGAIN_RANGE.end(),
+                self.device.normalization_pregain
+            ));
+        }
+        if self.device.audio_cache_size_limit_in_mb == Some(0) {
+            errors.push("`device.audio_cache_size_limit_in_mb` must be greater than 0".to_string());
+        }
+        #[cfg(feature = "streaming")]
+        if let Some(backend) = &self.device.audio_backend {
+            let backends = librespot_playback::audio_backend::BACKENDS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>();
+            if !backends.contains(&backend.as_str()) {
+                errors.push(format!(
+                    "`device.audio_backend` must be one of {}, got {backend:?}",
+                    backends.join(", ")
+                ));
+            }
+        }
+        for (module, level) in self.log_levels.iter().flatten() {
+            if level
+                .as_str()
+                .parse::<tracing::level_filters::LevelFilter>()
+                .is_err()
+            {
+                errors.push(format!(
+                    "`log_levels.{module}` must be one of off, error, warn, info, debug or trace, got {level:?}"
+                ));
+            }
+        }
+        if let Some(proxy) = &self.proxy {
+            if let Err(err) = Url::parse(proxy) {
+                errors.push(format!("`proxy` must be a valid URL, got {proxy:?}: {err}"));
+            }
+        }
+
+        if !errors.is_empty() {
+            anyhow::bail!("{}", errors.join(", "));
+        }
+        Ok(())
+    }
+
     // parses configurations from an application config file in `path` folder,
     // then updates the current configurations accordingly.
     // returns false if no config file found and true otherwise
     fn parse_config_file(&mut self, path: &Path) -> Result<bool> {
         let file_path = path.join(APP_CONFIG_FILE);
-        match std::fs::read_to_string(file_path) {
-            Ok(content) => self
-                .parse(toml::from_str::<toml::Value>(&content)?)
+        match std::fs::read_to_string(&file_path) {
+            Ok(content) => toml::from_str::<toml::Value>(&content)
+                .map_err(From::from)
+                .and_then(|value| self.parse(value))
+                .with_context(|| {
+                    format!("failed to parse the application config file {file_path:?}")
+                })
                 .map(|_| true),
             Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
             Err(error) => Err(error.into()),
@@ -329,6 +739,33 @@ impl AppConfig {
             })
     }
 
+    /// gets the icon to indicate playing state of a Spotify item
+    pub fn get_play_icon(&self) -> &str {
+        match self.icon_set {
+            IconSet::Unicode => &self.play_icon,
+            IconSet::NerdFont => "\u{f04b}", // nf-fa-play
+            IconSet::Ascii => ">",
+        }
+    }
+
+    /// gets the icon to indicate pause state of a Spotify item
+    pub fn get_pause_icon(&self) -> &str {
+        match self.icon_set {
+            IconSet::Unicode => &self.pause_icon,
+            IconSet::NerdFont => "\u{f04c}", // nf-fa-pause
+            IconSet::Ascii => "||",
+        }
+    }
+
+    /// gets the icon to indicate the liked state of a song
+    pub fn get_liked_icon(&self) -> &str {
+        match self.icon_set {
+            IconSet::Unicode => &self.liked_icon,
+            IconSet::NerdFont => "\u{f004}", // nf-fa-heart
+            IconSet::Ascii => "*",
+        }
+    }
+
     pub fn session_config(&self) -> SessionConfig {
         let proxy = self
             .proxy
@@ -348,28 +785,215 @@ impl AppConfig {
     }
 }
 
+impl IconSet {
+    /// gets the icon to indicate the shuffle state of a playback, if any
+    pub fn shuffle_icon(self, shuffle_state: bool) -> Option<&'static str> {
+        match self {
+            Self::NerdFont if shuffle_state => Some("\u{f049f}"), // nf-md-shuffle_variant
+            Self::NerdFont => Some("\u{f049e}"),                  // nf-md-shuffle_disabled
+            _ => None,
+        }
+    }
+
+    /// gets the icon to indicate the repeat state of a playback, if any
+    pub fn repeat_icon(self, repeat_state: rspotify::model::RepeatState) -> Option<&'static str> {
+        use rspotify::model::RepeatState;
+
+        match self {
+            Self::NerdFont => Some(match repeat_state {
+                RepeatState::Off => "\u{f0457}",     // nf-md-repeat_off
+                RepeatState::Context => "\u{f0456}", // nf-md-repeat
+                RepeatState::Track => "\u{f0458}",   // nf-md-repeat_once
+            }),
+            _ => None,
+        }
+    }
+
+    /// gets the icon to indicate the volume of a playback, if any
+    pub fn volume_icon(self, is_muted: bool) -> Option<&'static str> {
+        match self {
+            Self::NerdFont if is_muted => Some("\u{f075f}"), // nf-md-volume_mute
+            Self::NerdFont => Some("\u{f057e}"),             // nf-md-volume_high
+            _ => None,
+        }
+    }
+
+    /// gets the icon to indicate the type of a Spotify device, if any
+    pub fn device_icon(self, device_type: &rspotify::model::DeviceType) -> Option<&'static str> {
+        use rspotify::model::DeviceType;
+
+        match self {
+            Self::Unicode => None,
+            Self::NerdFont => Some(match device_type {
+                DeviceType::Computer => "\u{f0322}",   // nf-md-laptop
+                DeviceType::Smartphone => "\u{f011c}", // nf-md-cellphone
+                DeviceType::Tablet => "\u{f04f6}",     // nf-md-tablet
+                DeviceType::Smartwatch => "\u{f0589}", // nf-md-watch
+                DeviceType::Tv | DeviceType::Stb | DeviceType::CastVideo => "\u{f0502}", // nf-md-television
+                DeviceType::CastAudio => "\u{f0118}", // nf-md-cast
+                DeviceType::Automobile => "\u{f010b}", // nf-md-car
+                DeviceType::GameConsole => "\u{f0297}", // nf-md-gamepad_variant
+                _ => "\u{f04c3}",                     // nf-md-speaker
+            }),
+            Self::Ascii => Some(match device_type {
+                DeviceType::Computer => "[pc]",
+                DeviceType::Smartphone | DeviceType::Tablet | DeviceType::Smartwatch => "[mobile]",
+                DeviceType::Tv | DeviceType::Stb | DeviceType::CastVideo => "[tv]",
+                DeviceType::Automobile => "[car]",
+                DeviceType::GameConsole => "[console]",
+                _ => "[speaker]",
+            }),
+        }
+    }
+}
+
 /// gets the application's configuration folder path
 pub fn get_config_folder_path() -> Result<PathBuf> {
-    match dirs_next::home_dir() {
-        Some(home) => Ok(home.join(DEFAULT_CONFIG_FOLDER)),
-        None => Err(anyhow!("cannot find the $HOME folder")),
-    }
+    get_xdg_folder_path("XDG_CONFIG_HOME", DEFAULT_CONFIG_FOLDER, false)
 }
 
 /// gets the application's cache folder path
 pub fn get_cache_folder_path() -> Result<PathBuf> {
-    match dirs_next::home_dir() {
-        Some(home) => Ok(home.join(DEFAULT_CACHE_FOLDER)),
-        None => Err(anyhow!("cannot find the $HOME folder")),
+    get_xdg_folder_path("XDG_CACHE_HOME", DEFAULT_CACHE_FOLDER, false)
+}
+
+/// gets the application's configuration folder path, moving the legacy folder into it if needed.
+///
+/// This should only be called when the default folder is used, i.e. not overridden by the user.
+pub fn migrate_config_folder() -> Result<PathBuf> {
+    get_xdg_folder_path("XDG_CONFIG_HOME", DEFAULT_CONFIG_FOLDER, true)
+}
+
+/// gets the application's cache folder path, moving the legacy folder into it if needed.
+///
+/// This should only be called when the default folder is used, i.e. not overridden by the user.
+pub fn migrate_cache_folder() -> Result<PathBuf> {
+    get_xdg_folder_path("XDG_CACHE_HOME", DEFAULT_CACHE_FOLDER, true)
+}
+
+/// gets the application's folder inside the base directory specified by the XDG environment variable `env`.
+/// The legacy `$HOME/<default_folder>` path is used if the variable is unset or is not an absolute path.
+///
+/// If the application's folder doesn't exist in the XDG base directory, an existing legacy folder is
+/// moved there if `migrate` is true, or used as is otherwise.
+fn get_xdg_folder_path(env: &str, default_folder: &str, migrate: bool) -> Result<PathBuf> {
+    let legacy_path = match dirs_next::home_dir() {
+        Some(home) => home.join(default_folder),
+        None => return Err(anyhow!("cannot find the $HOME folder")),
+    };
+    let path = match std::env::var_os(env).map(PathBuf::from) {
+        Some(base) if base.is_absolute() => base.join(APP_FOLDER_NAME),
+        _ => return Ok(legacy_path),
+    };
+
+    if path != legacy_path && !path.exists() && legacy_path.exists() {
+        if !migrate {
+            return Ok(legacy_path);
+        }
+        let migrated = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::rename(&legacy_path, &path));
+        if let Err(err) = migrated {
+            // logging is not initialized at this point
+            eprintln!(
+                "Failed to move the legacy folder {legacy_path:?} to {path:?}: {err:#}. Use the legacy folder instead"
+            );
+            return Ok(legacy_path);
+        }
     }
+
+    Ok(path)
 }
 
 #[inline(always)]
-pub fn get_config() -> &'static Configs {
-    CONFIGS.get().expect("configs is already initialized")
+pub fn get_config() -> Arc<Configs> {
+    CONFIGS
+        .read()
+        .clone()
+        .expect("configs is already initialized")
 }
 pub fn set_config(configs: Configs) {
-    CONFIGS
-        .set(configs)
-        .expect("configs should be initialized only once")
+    let mut current = CONFIGS.write();
+    assert!(current.is_none(), "configs should be initialized only once");
+    *current = Some(Arc::new(configs));
+}
+
+/// reloads the application's configurations from the config files.
+///
+/// The current configurations are kept unchanged if the new configurations are invalid.
+pub fn reload_config() -> Result<Arc<Configs>> {
+    let current = get_config();
+    let configs = Configs::new(
+        &current.config_folder,
+        &current.cache_folder,
+        current.profile.as_deref(),
+    )?;
+    let configs = Arc::new(configs);
+    *CONFIGS.write() = Some(configs.clone());
+    Ok(configs)
+}
+
+/// gets the latest modification time of the application's config files
+pub fn get_config_files_modified_time(configs: &Configs) -> Option<std::time::SystemTime> {
+    config_folders(&configs.config_folder, configs.profile.as_deref())
+        .into_iter()
+        .flat_map(|folder| {
+            [APP_CONFIG_FILE, THEME_CONFIG_FILE, KEYMAP_CONFIG_FILE].map(|file| folder.join(file))
+        })
+        .filter_map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(update: impl FnOnce(&mut AppConfig)) -> Result<AppConfig> {
+        let mut config = AppConfig::default();
+        update(&mut config);
+        config.validate().map(|()| config)
+    }
+
+    fn assert_invalid(update: impl FnOnce(&mut AppConfig), field: &str) {
+        let err = validate(update).expect_err("invalid config").to_string();
+        assert!(err.contains(field), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert!(validate(|_| {}).is_ok());
+    }
+
+    #[test]
+    fn zero_values_are_rejected() {
+        assert_invalid(
+            |c| c.app_refresh_duration_in_ms = 0,
+            "app_refresh_duration_in_ms",
+        );
+        assert_invalid(|c| c.max_fps = 0, "max_fps");
+        assert_invalid(|c| c.page_size_in_rows = 0, "page_size_in_rows");
+        assert_invalid(|c| c.client_port = 0, "client_port");
+    }
+
+    #[test]
+    fn out_of_range_volume_is_rejected() {
+        assert_invalid(|c| c.device.volume = 101, "device.volume");
+        assert!(validate(|c| c.device.volume = 100).is_ok());
+    }
+
+    #[test]
+    fn unsupported_bitrate_falls_back_to_default() {
+        let config = validate(|c| c.device.bitrate = 128).unwrap();
+        assert_eq!(config.device.bitrate, DeviceConfig::default().bitrate);
+
+        let config = validate(|c| c.device.bitrate = 96).unwrap();
+        assert_eq!(config.device.bitrate, 96);
+    }
+
+    #[test]
+    fn invalid_proxy_url_is_rejected() {
+        assert_invalid(|c| c.proxy = Some("not a url".to_string()), "proxy");
+        assert!(validate(|c| c.proxy = Some("http://localhost:8080".to_string())).is_ok());
+    }
 }
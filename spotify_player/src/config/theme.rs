@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::Deserialize;
 use tui::style;
 
@@ -72,8 +72,10 @@ pub struct ComponentStyle {
     pub current_playing: Option<Style>,
     pub page_desc: Option<Style>,
     pub playlist_desc: Option<Style>,
+    pub unplayable_track: Option<Style>,
     pub table_header: Option<Style>,
     pub selection: Option<Style>,
+    pub error_message: Option<Style>,
 }
 
 #[derive(Default, Clone, Debug, Deserialize)]
@@ -128,9 +130,11 @@ impl ThemeConfig {
         self.themes.iter().find(|&t| t.name == name).cloned()
     }
 
-    pub fn new(path: &std::path::Path) -> Result<Self> {
+    pub fn new(folders: &[std::path::PathBuf]) -> Result<Self> {
         let mut config = Self::default();
-        config.parse_config_file(path)?;
+        for path in folders {
+            config.parse_config_file(path)?;
+        }
 
         Ok(config)
     }
@@ -146,7 +150,9 @@ impl ThemeConfig {
                 );
             }
             Ok(content) => {
-                let config = toml::from_str::<Self>(&content)?;
+                let config = toml::from_str::<Self>(&content).with_context(|| {
+                    format!("failed to parse the theme config file {file_path:?}")
+                })?;
 
                 // merge user-defined themes and the application default themes
                 // Skip any theme whose name conflicts with already existed theme in the current application's themes
@@ -278,12 +284,29 @@ impl Theme {
         }
     }
 
+    pub fn unplayable_track(&self) -> tui::style::Style {
+        match &self.component_style.unplayable_track {
+            None => Style::default()
+                .fg(StyleColor::BrightBlack)
+                .modifiers(vec![StyleModifier::Dim, StyleModifier::CrossedOut])
+                .style(&self.palette),
+            Some(s) => s.style(&self.palette),
+        }
+    }
+
     pub fn table_header(&self) -> tui::style::Style {
         match &self.component_style.table_header {
             None => Style::default().fg(StyleColor::Blue).style(&self.palette),
             Some(s) => s.style(&self.palette),
         }
     }
+
+    pub fn error_message(&self) -> tui::style::Style {
+        match &self.component_style.error_message {
+            None => Style::default().fg(StyleColor::Red).style(&self.palette),
+            Some(s) => s.style(&self.palette),
+        }
+    }
 }
 
 impl Style {
@@ -482,7 +505,11 @@ impl From<style::Color> for Color {
 impl Default for ThemeConfig {
     fn default() -> Self {
         Self {
-            themes: vec![Theme::default()],
+            themes: vec![
+                Theme::default(),
+                Theme::high_contrast(),
+                Theme::color_blind(),
+            ],
         }
     }
 }
@@ -497,6 +524,115 @@ impl Default for Theme {
     }
 }
 
+impl Theme {
+    /// A high-contrast theme using a black background and bright colors.
+    ///
+    /// The selection and the currently playing item are also distinguished by
+    /// text modifiers so that they don't rely on colors alone.
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".to_owned(),
+            palette: Palette {
+                background: Some("#000000".into()),
+                foreground: Some("#ffffff".into()),
+                black: "#000000".into(),
+                red: "#ff5f5f".into(),
+                green: "#5fff5f".into(),
+                yellow: "#ffff00".into(),
+                blue: "#5fafff".into(),
+                magenta: "#ff87ff".into(),
+                cyan: "#00ffff".into(),
+                white: "#ffffff".into(),
+                bright_black: "#c0c0c0".into(),
+                bright_red: "#ff8787".into(),
+                bright_green: "#87ff87".into(),
+                bright_yellow: "#ffff87".into(),
+                bright_blue: "#87d7ff".into(),
+                bright_magenta: "#ffafff".into(),
+                bright_cyan: "#87ffff".into(),
+                bright_white: "#ffffff".into(),
+            },
+            component_style: ComponentStyle {
+                block_title: Some(
+                    Style::default()
+                        .fg(StyleColor::BrightWhite)
+                        .modifiers(vec![StyleModifier::Bold]),
+                ),
+                border: Some(Style::default().fg(StyleColor::BrightWhite)),
+                playback_metadata: Some(Style::default().fg(StyleColor::BrightWhite)),
+                playback_progress_bar: Some(
+                    Style::default()
+                        .fg(StyleColor::Yellow)
+                        .bg(StyleColor::Black),
+                ),
+                current_playing: Some(
+                    Style::default()
+                        .fg(StyleColor::Yellow)
+                        .modifiers(vec![StyleModifier::Bold, StyleModifier::Underlined]),
+                ),
+                playlist_desc: Some(Style::default().fg(StyleColor::BrightWhite)),
+                table_header: Some(
+                    Style::default()
+                        .fg(StyleColor::Cyan)
+                        .modifiers(vec![StyleModifier::Bold]),
+                ),
+                selection: Some(
+                    Style::default()
+                        .fg(StyleColor::Black)
+                        .bg(StyleColor::Yellow)
+                        .modifiers(vec![StyleModifier::Bold]),
+                ),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// A color-blind friendly theme based on the [Okabe-Ito] palette.
+    ///
+    /// The palette avoids red/green distinctions, and the currently playing item is
+    /// also distinguished by text modifiers so that it doesn't rely on colors alone.
+    ///
+    /// [Okabe-Ito]: https://jfly.uni-koeln.de/color/
+    pub fn color_blind() -> Self {
+        Self {
+            name: "color-blind".to_owned(),
+            palette: Palette {
+                background: None,
+                foreground: None,
+                black: Color::black(),
+                red: "#d55e00".into(),
+                green: "#0072b2".into(),
+                yellow: "#f0e442".into(),
+                blue: "#56b4e9".into(),
+                magenta: "#cc79a7".into(),
+                cyan: "#009e73".into(),
+                white: Color::white(),
+                bright_black: Color::bright_black(),
+                bright_red: "#e69f00".into(),
+                bright_green: "#56b4e9".into(),
+                bright_yellow: "#f0e442".into(),
+                bright_blue: "#56b4e9".into(),
+                bright_magenta: "#cc79a7".into(),
+                bright_cyan: "#009e73".into(),
+                bright_white: Color::bright_white(),
+            },
+            component_style: ComponentStyle {
+                playback_progress_bar: Some(
+                    Style::default()
+                        .fg(StyleColor::BrightRed)
+                        .bg(StyleColor::BrightBlack),
+                ),
+                current_playing: Some(
+                    Style::default()
+                        .fg(StyleColor::BrightRed)
+                        .modifiers(vec![StyleModifier::Bold, StyleModifier::Underlined]),
+                ),
+                ..Default::default()
+            },
+        }
+    }
+}
+
 impl Default for Palette {
     fn default() -> Self {
         Self {
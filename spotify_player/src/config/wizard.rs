@@ -0,0 +1,81 @@
+use std::io::Write;
+
+use super::*;
+
+/// runs an interactive wizard to create the application config file on the first launch,
+/// which happens when no application config file is found in the config folder
+pub fn run_first_launch_wizard(config_folder: &Path) -> Result<()> {
+    if config_folder.join(APP_CONFIG_FILE).exists() {
+        return Ok(());
+    }
+
+    let mut config = AppConfig::default();
+    println!(
+        "Welcome to spotify_player! No application config file found in {config_folder:?}, \
+        let's create one (press Enter to use the default values)."
+    );
+
+    println!(
+        "\nA Spotify client ID is used to make requests to Spotify API. \
+        It's recommended to create your own client at https://developer.spotify.com/dashboard \
+        to avoid possible rate limits and to have a full Spotify Connect support."
+    );
+    config.client_id = prompt("Client ID", &config.client_id, |id| {
+        if id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+            Ok(id.to_string())
+        } else {
+            Err("a client ID must be a 32-character hexadecimal string".to_string())
+        }
+    })?;
+
+    println!(
+        "\nThe application listens on a local port to handle CLI commands \
+        (e.g. `spotify_player playback play`)."
+    );
+    config.client_port = prompt("Client port", &config.client_port.to_string(), |port| {
+        let port = port
+            .parse::<u16>()
+            .ok()
+            .filter(|port| *port > 0)
+            .ok_or_else(|| "a port must be a number between 1 and 65535".to_string())?;
+        std::net::UdpSocket::bind(("127.0.0.1", port))
+            .map_err(|err| format!("failed to listen on the port {port}: {err}"))?;
+        Ok(port)
+    })?;
+
+    config
+        .write_config_file(config_folder)
+        .context("failed to write the application config file")?;
+    println!(
+        "\nConfigurations are written to {:?}. Other options can be found in \
+        https://github.com/aome510/spotify-player/blob/master/docs/config.md.\n\
+        Next, please authenticate the application with your Spotify account.\n",
+        config_folder.join(APP_CONFIG_FILE)
+    );
+
+    Ok(())
+}
+
+/// prompts the user for a value until it passes the validation,
+/// the default value is used if the user enters nothing
+fn prompt<T>(name: &str, default: &str, validate: impl Fn(&str) -> Result<T, String>) -> Result<T> {
+    let mut stdout = std::io::stdout();
+    loop {
+        write!(stdout, "{name} (default: {default}): ")?;
+        stdout.flush()?;
+
+        let mut input = String::new();
+        if std::io::stdin().read_line(&mut input)? == 0 {
+            anyhow::bail!("failed to read {name}: unexpected end of input");
+        }
+        let input = match input.trim() {
+            "" => default,
+            input => input,
+        };
+
+        match validate(input) {
+            Ok(value) => return Ok(value),
+            Err(err) => eprintln!("Invalid {name}: {err}"),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use once_cell::sync::OnceCell;
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+
+use crate::config;
+
+/// the environment variable storing the passphrase used to encrypt the cache files
+pub const PASSPHRASE_ENV: &str = "SPOTIFY_PLAYER_CACHE_PASSPHRASE";
+/// the name of the file storing the salt of the key derivation inside the cache folder
+const SALT_FILE: &str = "encryption_salt";
+const SALT_LEN: usize = 16;
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+static CACHE_KEY: OnceCell<LessSafeKey> = OnceCell::new();
+
+/// checks if the cache files storing authentication data should be encrypted
+pub fn is_enabled() -> bool {
+    config::get_config().app_config.enable_cache_encryption
+}
+
+/// gets the key used to encrypt the cache files.
+///
+/// The key is derived from a user-provided passphrase, which is read from
+/// the `SPOTIFY_PLAYER_CACHE_PASSPHRASE` environment variable or prompted
+/// from the terminal, the first time the function is called.
+fn cache_key() -> Result<&'static LessSafeKey> {
+    CACHE_KEY.get_or_try_init(|| {
+        let passphrase = match std::env::var(PASSPHRASE_ENV) {
+            Ok(passphrase) => passphrase,
+            Err(_) => rpassword::prompt_password("Passphrase for the cache encryption: ")
+                .context("failed to read the cache encryption passphrase")?,
+        };
+        if passphrase.is_empty() {
+            anyhow::bail!("the cache encryption passphrase must not be empty");
+        }
+
+        let salt = get_or_create_salt(&config::get_config().cache_folder)?;
+        derive_key(&passphrase, &salt)
+    })
+}
+
+/// derives an encryption key from a passphrase and a salt
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<LessSafeKey> {
+    let mut key = [0; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        std::num::NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+
+    let key = UnboundKey::new(&CHACHA20_POLY1305, &key)
+        .map_err(|_| anyhow::anyhow!("failed to construct the cache encryption key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn get_or_create_salt(cache_folder: &Path) -> Result<Vec<u8>> {
+    let path = cache_folder.join(SALT_FILE);
+    if path.exists() {
+        return std::fs::read(&path).context("failed to read the cache encryption salt");
+    }
+
+    let mut salt = vec![0; SALT_LEN];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| anyhow::anyhow!("failed to generate the cache encryption salt"))?;
+    std::fs::write(&path, &salt).context("failed to write the cache encryption salt")?;
+    Ok(salt)
+}
+
+/// encrypts data, the returned data is prefixed with a random nonce
+pub fn encrypt(data: &[u8]) -> Result<Vec<u8>> {
+    encrypt_with_key(cache_key()?, data)
+}
+
+fn encrypt_with_key(key: &LessSafeKey, data: &[u8]) -> Result<Vec<u8>> {
+    let mut nonce = [0; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| anyhow::anyhow!("failed to generate a nonce"))?;
+
+    let mut in_out = data.to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut in_out,
+    )
+    .map_err(|_| anyhow::anyhow!("failed to encrypt data"))?;
+
+    let mut encrypted = nonce.to_vec();
+    encrypted.extend(in_out);
+    Ok(encrypted)
+}
+
+/// decrypts data encrypted by [`encrypt`]
+pub fn decrypt(data: &[u8]) -> Result<Vec<u8>> {
+    decrypt_with_key(cache_key()?, data)
+}
+
+fn decrypt_with_key(key: &LessSafeKey, data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < NONCE_LEN {
+        anyhow::bail!("invalid encrypted data");
+    }
+    let (nonce, data) = data.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| anyhow::anyhow!("invalid encrypted data"))?;
+
+    let mut in_out = data.to_vec();
+    let decrypted = key
+        .open_in_place(nonce, Aad::empty(), &mut in_out)
+        .map_err(|_| anyhow::anyhow!("failed to decrypt data, the passphrase may be incorrect"))?;
+    Ok(decrypted.to_vec())
+}
+
+/// creates (or truncates) a file which is only accessible by the current user on Unix systems.
+///
+/// The permissions are also set on an existing file, whose mode isn't changed by opening it.
+pub fn create_private_file(path: &Path) -> Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(file)
+}
+
+/// writes encrypted data into a file, which is only accessible by the current user on Unix systems
+pub fn write_encrypted_file(path: &Path, data: &[u8]) -> Result<()> {
+    std::io::Write::write_all(&mut create_private_file(path)?, &encrypt(data)?)?;
+    Ok(())
+}
+
+/// reads and decrypts a file written by [`write_encrypted_file`]
+pub fn read_encrypted_file(path: &Path) -> Result<Vec<u8>> {
+    decrypt(&std::fs::read(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SALT: &[u8] = b"spotify-player-salt";
+
+    #[test]
+    fn decrypting_encrypted_data_returns_the_original_data() {
+        let key = derive_key("passphrase", SALT).unwrap();
+        let data = b"{\"access_token\": \"token\"}";
+
+        let encrypted = encrypt_with_key(&key, data).unwrap();
+        assert_ne!(&encrypted[NONCE_LEN..], data);
+        assert_eq!(decrypt_with_key(&key, &encrypted).unwrap(), data);
+    }
+
+    #[test]
+    fn decrypting_with_a_wrong_passphrase_fails() {
+        let key = derive_key("passphrase", SALT).unwrap();
+        let encrypted = encrypt_with_key(&key, b"data").unwrap();
+
+        let wrong_key = derive_key("wrong passphrase", SALT).unwrap();
+        let err = decrypt_with_key(&wrong_key, &encrypted).unwrap_err();
+        assert!(err.to_string().contains("passphrase may be incorrect"));
+        assert!(decrypt_with_key(&key, &encrypted[..NONCE_LEN - 1]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_files_are_restricted_to_the_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!(
+            "spotify-player-private-file-{}",
+            std::process::id()
+        ));
+        // an existing file keeps its mode when opened, so its permissions must be updated
+        std::fs::write(&path, "data").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        create_private_file(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -16,8 +16,10 @@ struct CommandProvider {
     paste_command: Command,
 }
 
+/// A provider setting the clipboard's contents with the OSC 52 escape sequence,
+/// which is handled by the terminal emulator, so it works over SSH sessions
 #[cfg(not(target_os = "windows"))]
-struct NopProvider {}
+struct Osc52Provider {}
 
 #[cfg(target_os = "windows")]
 struct WindowsProvider {}
@@ -52,12 +54,21 @@ impl ClipboardProvider for CommandProvider {
 }
 
 #[cfg(not(target_os = "windows"))]
-impl ClipboardProvider for NopProvider {
+impl ClipboardProvider for Osc52Provider {
     fn get_contents(&self) -> Result<String> {
-        anyhow::bail!("no clipboard provider found!")
+        // reading the clipboard with OSC 52 is disabled by most terminal emulators
+        anyhow::bail!(
+            "getting the clipboard's contents is not supported by the OSC 52 clipboard provider!"
+        )
     }
-    fn set_contents(&self, _contents: String) -> Result<()> {
-        anyhow::bail!("no clipboard provider found!")
+    fn set_contents(&self, contents: String) -> Result<()> {
+        use base64::Engine as _;
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode(contents);
+        let mut stdout = std::io::stdout().lock();
+        write!(stdout, "\x1b]52;c;{encoded}\x07")?;
+        stdout.flush()?;
+        Ok(())
     }
 }
 
@@ -79,6 +90,14 @@ impl ClipboardProvider for WindowsProvider {
 // The function's implementation is inspired by helix
 // (https://github.com/blaggacao/helix/blob/master/helix-view/src/clipboard.rs)
 pub fn get_clipboard_provider() -> Box<dyn ClipboardProvider> {
+    // the local clipboard commands of a remote machine (without a forwarded display)
+    // don't reach the user's clipboard, so OSC 52 is preferred in SSH sessions
+    #[cfg(not(target_os = "windows"))]
+    if env_var_is_set("SSH_TTY") && !env_var_is_set("DISPLAY") && !env_var_is_set("WAYLAND_DISPLAY")
+    {
+        return Box::new(Osc52Provider {});
+    }
+
     if binary_exists("pbcopy") && binary_exists("pbpaste") {
         Box::new(CommandProvider {
             paste_command: Command::new::<_, &str>("pbpaste", &[]),
@@ -108,8 +127,10 @@ pub fn get_clipboard_provider() -> Box<dyn ClipboardProvider> {
 
         #[cfg(not(target_os = "windows"))]
         {
-            tracing::warn!("No clipboard provider found! Fallback to a NOP clipboard provider.");
-            return Box::new(NopProvider {});
+            tracing::warn!(
+                "No clipboard provider found! Fallback to the OSC 52 clipboard provider."
+            );
+            Box::new(Osc52Provider {})
         }
     }
 }
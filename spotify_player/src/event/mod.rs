@@ -22,27 +22,80 @@ mod window;
 /// Start a terminal event handler (key pressed, mouse clicked, etc)
 pub fn start_event_handler(state: SharedState, client_pub: flume::Sender<ClientRequest>) {
     while let Ok(event) = crossterm::event::read() {
-        let _enter = tracing::info_span!("terminal_event", event = ?event).entered();
-        if let Err(err) = match event {
-            crossterm::event::Event::Mouse(event) => handle_mouse_event(event, &client_pub, &state),
-            crossterm::event::Event::Key(event) => {
-                if event.kind == crossterm::event::KeyEventKind::Press {
-                    // only handle key press event to avoid handling a key event multiple times
-                    // context:
-                    // - https://github.com/crossterm-rs/crossterm/issues/752
-                    // - https://github.com/aome510/spotify-player/issues/136
-                    handle_key_event(event, &client_pub, &state)
-                } else {
-                    Ok(())
-                }
-            }
-            _ => Ok(()),
-        } {
+        if let Err(err) = handle_event(event, &client_pub, &state) {
             tracing::error!("Failed to handle event: {err:#}");
         }
+        state.diagnostics.lock().record_event();
+        state.mark_changed();
+    }
+}
+
+/// Handle a terminal event, which is the entry point of the spans of user's actions
+#[tracing::instrument(name = "terminal_event", skip(client_pub, state))]
+fn handle_event(
+    event: crossterm::event::Event,
+    client_pub: &flume::Sender<ClientRequest>,
+    state: &SharedState,
+) -> Result<()> {
+    match event {
+        crossterm::event::Event::Mouse(event) => handle_mouse_event(event, client_pub, state),
+        crossterm::event::Event::Key(event) => {
+            if event.kind == crossterm::event::KeyEventKind::Press {
+                // only handle key press event to avoid handling a key event multiple times
+                // context:
+                // - https://github.com/crossterm-rs/crossterm/issues/752
+                // - https://github.com/aome510/spotify-player/issues/136
+                handle_key_event(event, client_pub, state)
+            } else {
+                Ok(())
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Start a watcher that reloads the application's configurations whenever a config file is modified
+pub fn start_config_watcher(state: SharedState) {
+    let mut last_modified = config::get_config_files_modified_time(&config::get_config());
+    loop {
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        let modified = config::get_config_files_modified_time(&config::get_config());
+        if modified == last_modified {
+            continue;
+        }
+        last_modified = modified;
+
+        tracing::info!("Detected changes in the config files, reloading the configurations...");
+        if let Err(err) = reload_config(&mut state.ui.lock()) {
+            tracing::error!("Failed to reload the configurations: {err:#}");
+        }
+        state.mark_changed();
     }
 }
 
+/// Get the duration to seek forward/backward by, which is configured by the `seek_step_in_secs` config option
+fn seek_step() -> chrono::Duration {
+    let secs = config::get_config().app_config.seek_step_in_secs;
+    chrono::Duration::try_seconds(i64::from(secs)).unwrap()
+}
+
+/// Reload the application's configurations and apply the new theme to the UI
+fn reload_config(ui: &mut UIStateGuard) -> Result<()> {
+    let configs = config::reload_config()?;
+    crate::logging::reload_log_filter(&configs)?;
+    #[cfg(feature = "plugins")]
+    crate::plugin::clear_modules();
+    match configs.theme_config.find_theme(&configs.app_config.theme) {
+        Some(theme) => ui.theme = theme,
+        None => tracing::warn!(
+            "Cannot find the theme {:?} after reloading the configurations",
+            configs.app_config.theme
+        ),
+    }
+    tracing::info!("Reloaded the configurations");
+    Ok(())
+}
+
 // Handle a terminal mouse event
 fn handle_mouse_event(
     event: crossterm::event::MouseEvent,
@@ -53,6 +106,28 @@ fn handle_mouse_event(
     if let crossterm::event::MouseEventKind::Down(crossterm::event::MouseButton::Left) = event.kind
     {
         tracing::debug!("Handling mouse event: {event:?}");
+
+        // jump back to a page in the navigation path if its breadcrumb's segment is clicked
+        {
+            let mut ui = state.ui.lock();
+            let clicked_page = ui.breadcrumb_rects.iter().find_map(|(rect, id)| {
+                (event.row == rect.y
+                    && event.column >= rect.x
+                    && event.column < rect.x + rect.width)
+                    .then_some(*id)
+            });
+            if let Some(id) = clicked_page {
+                let is_filtering = ui.is_search_popup_open();
+                ui.history.truncate(id + 1);
+                ui.popup = None;
+                drop(ui);
+                if is_filtering {
+                    state.flush_context_presets();
+                }
+                return Ok(());
+            }
+        }
+
         let rect = state.ui.lock().playback_progress_bar_rect;
         if event.row == rect.y {
             // calculate the seek position (in ms) based on the mouse click position,
@@ -82,50 +157,228 @@ fn handle_key_event(
 ) -> Result<()> {
     let key: Key = event.into();
     let mut ui = state.ui.lock();
+    let was_filtering = ui.is_search_popup_open();
+
+    tracing::debug!("Handling key event: {event:?}");
+    let result = handle_key(key, client_pub, state, &mut ui);
 
+    // the filter typed into the search popup is only persisted once the popup is closed
+    // instead of on every keystroke
+    let is_filtering = ui.is_search_popup_open();
+    drop(ui);
+    if was_filtering && !is_filtering {
+        state.flush_context_presets();
+    }
+    result
+}
+
+/// Handle a key pressed by user or replayed from a keyboard macro
+fn handle_key(
+    key: Key,
+    client_pub: &flume::Sender<ClientRequest>,
+    state: &SharedState,
+    ui: &mut UIStateGuard,
+) -> Result<()> {
+    let is_recording_macro = ui.recording_macro.is_some() && !ui.is_replaying_macro;
+
+    // the key following a `RecordMacro` or `ReplayMacro` command specifies the macro's register
+    if let Some(command) = ui.pending_macro_command.take() {
+        if let Key::None(crossterm::event::KeyCode::Char(register)) = key {
+            handle_macro_command(command, register, client_pub, state, ui)?;
+        }
+    } else {
+        handle_key_sequence(key, client_pub, state, ui)?;
+    }
+
+    if is_recording_macro {
+        if let Some((_, keys)) = &mut ui.recording_macro {
+            keys.push(key);
+        }
+    }
+    Ok(())
+}
+
+/// Start recording or replay a keyboard macro in a given register
+fn handle_macro_command(
+    command: Command,
+    register: char,
+    client_pub: &flume::Sender<ClientRequest>,
+    state: &SharedState,
+    ui: &mut UIStateGuard,
+) -> Result<()> {
+    match command {
+        Command::RecordMacro => {
+            ui.recording_macro = Some((register, vec![]));
+        }
+        Command::ReplayMacro => {
+            let Some(keys) = ui.macros.get(&register).cloned() else {
+                tracing::warn!("No keyboard macro recorded in register {register}");
+                return Ok(());
+            };
+            ui.is_replaying_macro = true;
+            let result = keys
+                .into_iter()
+                .try_for_each(|key| handle_key(key, client_pub, state, ui));
+            ui.is_replaying_macro = false;
+            result?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle a key as a part of the input key sequence
+fn handle_key_sequence(
+    key: Key,
+    client_pub: &flume::Sender<ClientRequest>,
+    state: &SharedState,
+    ui: &mut UIStateGuard,
+) -> Result<()> {
+    ui.expire_input_key_sequence();
     let mut key_sequence = ui.input_key_sequence.clone();
     key_sequence.keys.push(key);
 
     // check if the current key sequence matches any keymap's prefix
     // if not, reset the key sequence
     let keymap_config = &config::get_config().keymap_config;
-    if keymap_config
-        .find_matched_prefix_keymaps(&key_sequence)
-        .is_empty()
+    #[cfg(feature = "scripting")]
+    let has_matched_scripts = keymap_config.has_matched_prefix_scripts(&key_sequence);
+    #[cfg(not(feature = "scripting"))]
+    let has_matched_scripts = false;
+    #[cfg(feature = "plugins")]
+    let has_matched_plugin_commands =
+        keymap_config.has_matched_prefix_plugin_commands(&key_sequence);
+    #[cfg(not(feature = "plugins"))]
+    let has_matched_plugin_commands = false;
+    if !has_matched_scripts
+        && !has_matched_plugin_commands
+        && keymap_config
+            .find_matched_prefix_keymaps(&key_sequence)
+            .is_empty()
     {
         key_sequence = KeySequence { keys: vec![key] };
     }
 
-    tracing::debug!("Handling key event: {event:?}, current key sequence: {key_sequence:?}");
+    tracing::debug!("Handling key: {key:?}, current key sequence: {key_sequence:?}");
     let handled = {
         if ui.popup.is_none() {
-            page::handle_key_sequence_for_page(&key_sequence, client_pub, state, &mut ui)?
+            page::handle_key_sequence_for_page(&key_sequence, client_pub, state, ui)?
         } else {
-            popup::handle_key_sequence_for_popup(&key_sequence, client_pub, state, &mut ui)?
+            popup::handle_key_sequence_for_popup(&key_sequence, client_pub, state, ui)?
         }
     };
 
     // if the key sequence is not handled, let the global command handler handle it
     let handled = if !handled {
         match keymap_config.find_command_from_key_sequence(&key_sequence) {
-            Some(command) => handle_global_command(command, client_pub, state, &mut ui)?,
+            Some(command) => handle_global_command(command, client_pub, state, ui)?,
             None => false,
         }
     } else {
         true
     };
 
+    // if the key sequence is still not handled, run the script mapped to the key sequence (if any)
+    #[cfg(feature = "scripting")]
+    let handled = handled
+        || match keymap_config.find_script_from_key_sequence(&key_sequence) {
+            Some(name) => {
+                handle_script(name, client_pub, state, ui)?;
+                true
+            }
+            None => false,
+        };
+
+    // if the key sequence is still not handled, run the plugin command mapped to the key sequence (if any)
+    #[cfg(feature = "plugins")]
+    let handled = handled
+        || match keymap_config.find_plugin_command_from_key_sequence(&key_sequence) {
+            Some(keymap) => {
+                handle_plugin_command(&keymap.plugin, &keymap.command, client_pub, state, ui)?;
+                true
+            }
+            None => false,
+        };
+
     // if handled, clear the key sequence
     // otherwise, the current key sequence can be a prefix of a command's shortcut
     if handled {
         ui.input_key_sequence.keys = vec![];
     } else {
         ui.input_key_sequence = key_sequence;
+        ui.input_key_sequence_time = std::time::Instant::now();
+    }
+    Ok(())
+}
+
+#[cfg(feature = "scripting")]
+/// Run a user-defined script and dispatch the actions requested by the script
+fn handle_script(
+    name: &str,
+    client_pub: &flume::Sender<ClientRequest>,
+    state: &SharedState,
+    ui: &mut UIStateGuard,
+) -> Result<()> {
+    let actions = match crate::scripting::run_script(name, state) {
+        Ok(actions) => actions,
+        Err(err) => {
+            // report the script's error in the logs instead of stopping the event handler
+            tracing::error!("{err:#}");
+            return Ok(());
+        }
+    };
+    for action in actions {
+        match action {
+            crate::scripting::ScriptAction::Command(command) => {
+                if !handle_global_command(command, client_pub, state, ui)? {
+                    tracing::warn!(
+                        "Command {command:?} requested by script {name} is not a global command"
+                    );
+                }
+            }
+            crate::scripting::ScriptAction::Request(request) => client_pub.send(*request)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "plugins")]
+/// Run a plugin's command and dispatch the actions requested by the plugin
+fn handle_plugin_command(
+    plugin: &str,
+    command: &str,
+    client_pub: &flume::Sender<ClientRequest>,
+    state: &SharedState,
+    ui: &mut UIStateGuard,
+) -> Result<()> {
+    // the UI is unlocked while running the plugin, which may need to compile the plugin first
+    let requests = match parking_lot::MutexGuard::unlocked(ui, || {
+        crate::plugin::run_plugin_command(plugin, command, state)
+    }) {
+        Ok(requests) => requests,
+        Err(err) => {
+            // report the plugin's error in the logs instead of stopping the event handler
+            tracing::error!("Failed to run command {command} of plugin {plugin}: {err:#}");
+            return Ok(());
+        }
+    };
+    for request in requests {
+        match request {
+            crate::plugin::PluginRequest::Command(c) => {
+                if !handle_global_command(c, client_pub, state, ui)? {
+                    tracing::warn!(
+                        "Command {c:?} requested by plugin {plugin} is not a global command"
+                    );
+                }
+            }
+            crate::plugin::PluginRequest::Request(request) => client_pub.send(*request)?,
+        }
     }
     Ok(())
 }
 
 /// Handle a global command that is not specific to any page/popup
+#[tracing::instrument(skip(client_pub, state, ui))]
 fn handle_global_command(
     command: Command,
     client_pub: &flume::Sender<ClientRequest>,
@@ -158,38 +411,47 @@ fn handle_global_command(
             client_pub.send(ClientRequest::Player(PlayerRequest::Shuffle))?;
         }
         Command::VolumeUp => {
-            if let Some(ref playback) = state.player.read().buffered_playback {
-                if let Some(volume) = playback.volume {
-                    let volume = std::cmp::min(volume + 5, 100_u32);
-                    client_pub.send(ClientRequest::Player(PlayerRequest::Volume(volume as u8)))?;
-                }
+            let volume = state
+                .player
+                .read()
+                .buffered_playback
+                .as_ref()
+                .and_then(|p| p.volume);
+            if let Some(volume) = volume {
+                let step = config::get_config().app_config.volume_step_in_percent;
+                let volume = std::cmp::min(volume + u32::from(step), 100_u32);
+                client_pub.send(ClientRequest::Player(PlayerRequest::Volume(volume as u8)))?;
             }
         }
         Command::VolumeDown => {
-            if let Some(ref playback) = state.player.read().buffered_playback {
-                if let Some(volume) = playback.volume {
-                    let volume = volume.saturating_sub(5_u32);
-                    client_pub.send(ClientRequest::Player(PlayerRequest::Volume(volume as u8)))?;
-                }
+            let volume = state
+                .player
+                .read()
+                .buffered_playback
+                .as_ref()
+                .and_then(|p| p.volume);
+            if let Some(volume) = volume {
+                let step = config::get_config().app_config.volume_step_in_percent;
+                let volume = volume.saturating_sub(u32::from(step));
+                client_pub.send(ClientRequest::Player(PlayerRequest::Volume(volume as u8)))?;
             }
         }
         Command::Mute => {
             client_pub.send(ClientRequest::Player(PlayerRequest::ToggleMute))?;
         }
         Command::SeekForward => {
-            if let Some(progress) = state.player.read().playback_progress() {
+            let progress = state.player.read().playback_progress();
+            if let Some(progress) = progress {
                 client_pub.send(ClientRequest::Player(PlayerRequest::SeekTrack(
-                    progress + chrono::Duration::try_seconds(5).unwrap(),
+                    progress + seek_step(),
                 )))?;
             }
         }
         Command::SeekBackward => {
-            if let Some(progress) = state.player.read().playback_progress() {
+            let progress = state.player.read().playback_progress();
+            if let Some(progress) = progress {
                 client_pub.send(ClientRequest::Player(PlayerRequest::SeekTrack(
-                    std::cmp::max(
-                        chrono::Duration::zero(),
-                        progress - chrono::Duration::try_seconds(5).unwrap(),
-                    ),
+                    std::cmp::max(chrono::Duration::zero(), progress - seek_step()),
                 )))?;
             }
         }
@@ -200,8 +462,9 @@ fn handle_global_command(
             client_pub.send(ClientRequest::GetCurrentPlayback)?;
         }
         Command::ShowActionsOnCurrentTrack => {
-            if let Some(track) = state.player.read().current_playing_track() {
-                if let Some(track) = Track::try_from_full_track(track.clone()) {
+            let track = state.player.read().current_playing_track().cloned();
+            if let Some(track) = track {
+                if let Some(track) = Track::try_from_full_track(track) {
                     let data = state.data.read();
                     let actions = command::construct_track_actions(&track, &data);
                     ui.popup = Some(PopupState::ActionList(
@@ -372,6 +635,14 @@ fn handle_global_command(
         Command::RestartIntegratedClient => {
             client_pub.send(ClientRequest::RestartIntegratedClient)?;
         }
+        #[cfg(feature = "streaming")]
+        Command::ClearAudioCache => {
+            ui.popup = Some(PopupState::ConfirmClearAudioCache);
+        }
+        #[cfg(feature = "streaming")]
+        Command::OpenPlaybackSettings => {
+            ui.popup = Some(PopupState::PlaybackSettings(new_list_state()));
+        }
         Command::FocusNextWindow => {
             if !ui.has_focused_popup() {
                 ui.current_page_mut().next()
@@ -386,6 +657,77 @@ fn handle_global_command(
             ui.new_page(PageState::Queue { scroll_offset: 0 });
             client_pub.send(ClientRequest::GetCurrentUserQueue)?;
         }
+        Command::ToggleQueuePane => {
+            ui.is_queue_pane_visible = !ui.is_queue_pane_visible;
+            if ui.is_queue_pane_visible {
+                client_pub.send(ClientRequest::GetCurrentUserQueue)?;
+            }
+        }
+        Command::ToggleLogPane => {
+            ui.log_pane_level = match ui.log_pane_level {
+                None => Some(tracing::Level::INFO),
+                Some(_) => None,
+            };
+        }
+        Command::ToggleVisualizerPane => {
+            ui.is_visualizer_pane_visible = !ui.is_visualizer_pane_visible;
+            crate::visualizer::set_enabled(ui.is_visualizer_pane_visible);
+        }
+        Command::CycleLogPaneLevel => {
+            if let Some(level) = ui.log_pane_level {
+                ui.log_pane_level = Some(match level {
+                    tracing::Level::ERROR => tracing::Level::WARN,
+                    tracing::Level::WARN => tracing::Level::INFO,
+                    tracing::Level::INFO => tracing::Level::DEBUG,
+                    tracing::Level::DEBUG => tracing::Level::TRACE,
+                    tracing::Level::TRACE => tracing::Level::ERROR,
+                });
+            }
+        }
+        Command::ToggleTrackTableColumns => {
+            ui.popup = Some(PopupState::TrackTableColumnList(new_list_state()));
+        }
+        Command::ExportScreenshot => {
+            ui.is_screenshot_requested = true;
+        }
+        Command::OpenDiagnostics => {
+            ui.popup = Some(PopupState::Diagnostics);
+        }
+        Command::SwitchProfile => {
+            let profiles = std::iter::once(None)
+                .chain(
+                    config::get_profiles(&config::get_config().config_folder)
+                        .into_iter()
+                        .map(Some),
+                )
+                .collect();
+            ui.popup = Some(PopupState::ProfileList(profiles, new_list_state()));
+        }
+        Command::ReloadConfig => {
+            reload_config(ui)?;
+        }
+        Command::RecordMacro => {
+            // macros cannot be recorded while replaying another macro
+            if !ui.is_replaying_macro {
+                match ui.recording_macro.take() {
+                    Some((register, mut keys)) => {
+                        // remove the recorded prefix keys of the key sequence stopping the recording
+                        let n_keys = keys.len().saturating_sub(ui.input_key_sequence.keys.len());
+                        keys.truncate(n_keys);
+                        ui.macros.insert(register, keys);
+                    }
+                    None => {
+                        ui.pending_macro_command = Some(Command::RecordMacro);
+                    }
+                }
+            }
+        }
+        Command::ReplayMacro => {
+            // nested macro replays are not supported to avoid infinite recursions
+            if !ui.is_replaying_macro {
+                ui.pending_macro_command = Some(Command::ReplayMacro);
+            }
+        }
         Command::CreatePlaylist => {
             ui.popup = Some(PopupState::PlaylistCreate {
                 name: LineInput::default(),
@@ -394,6 +736,10 @@ fn handle_global_command(
             });
         }
         Command::ClosePopup => {
+            // closing the search popup removes the filter applied to the current context
+            if let Some(PopupState::Search { .. }) = ui.popup {
+                popup::update_context_filter_preset(state, ui, None);
+            }
             ui.popup = None;
         }
         _ => return Ok(false),
@@ -401,6 +747,12 @@ fn handle_global_command(
     Ok(true)
 }
 
+fn execute_copy_command(text: String) -> Result<()> {
+    CLIPBOARD_PROVIDER
+        .get_or_init(|| get_clipboard_provider())
+        .set_contents(text)
+}
+
 fn get_clipboard_content() -> Result<String> {
     CLIPBOARD_PROVIDER
         .get_or_init(|| get_clipboard_provider())
@@ -225,6 +225,98 @@ pub fn handle_key_sequence_for_popup(
                 },
             )
         }
+        PopupState::TrackTableColumnList(_) => {
+            let columns = TrackTableColumn::ALL
+                .into_iter()
+                .filter(|c| c.is_hideable())
+                .collect::<Vec<_>>();
+
+            handle_command_for_list_popup(
+                command,
+                ui,
+                columns.len(),
+                |_, _| {},
+                |ui: &mut UIStateGuard, id: usize| -> Result<()> {
+                    // show or hide the selected column, the popup is kept open
+                    // so that multiple columns can be toggled at once
+                    let column = columns[id];
+                    if !ui.hidden_track_table_columns.remove(&column) {
+                        ui.hidden_track_table_columns.insert(column);
+                    }
+                    Ok(())
+                },
+                |ui: &mut UIStateGuard| {
+                    ui.popup = None;
+                },
+            )
+        }
+        #[cfg(feature = "streaming")]
+        PopupState::PlaybackSettings(_) => handle_command_for_list_popup(
+            command,
+            ui,
+            2,
+            |_, _| {},
+            |_: &mut UIStateGuard, id: usize| -> Result<()> {
+                let settings = {
+                    let mut player = state.player.write();
+                    let mut settings = player.integrated_player_settings();
+                    match id {
+                        0 => settings.normalization = !settings.normalization,
+                        _ => settings.gapless = !settings.gapless,
+                    }
+                    player.integrated_player_settings = Some(settings);
+                    settings
+                };
+                tracing::info!("Changed the integrated player's settings: {settings:?}");
+                // the settings are applied to a new integrated player, so the client is restarted
+                client_pub.send(ClientRequest::RestartIntegratedClient)?;
+                Ok(())
+            },
+            |ui: &mut UIStateGuard| {
+                ui.popup = None;
+            },
+        ),
+        PopupState::ProfileList(profiles, _) => {
+            let profiles = profiles.clone();
+
+            handle_command_for_list_popup(
+                command,
+                ui,
+                profiles.len(),
+                |_, _| {},
+                |ui: &mut UIStateGuard, id: usize| -> Result<()> {
+                    // the application is restarted with the selected profile after it stops running
+                    ui.restart_args = Some(crate::utils::restart_args_with_profile(
+                        profiles[id].as_deref(),
+                    ));
+                    ui.is_running = false;
+                    Ok(())
+                },
+                |ui: &mut UIStateGuard| {
+                    ui.popup = None;
+                },
+            )
+        }
+        #[cfg(feature = "streaming")]
+        PopupState::ConfirmClearAudioCache => match command {
+            Command::ChooseSelected => {
+                client_pub.send(ClientRequest::ClearAudioCache)?;
+                ui.popup = None;
+                Ok(true)
+            }
+            Command::ClosePopup => {
+                ui.popup = None;
+                Ok(true)
+            }
+            _ => Ok(false),
+        },
+        PopupState::TrackDetails(_) | PopupState::Diagnostics | PopupState::Share { .. } => {
+            if command == Command::ClosePopup {
+                ui.popup = None;
+                return Ok(true);
+            }
+            Ok(false)
+        }
     }
 }
 
@@ -291,13 +383,17 @@ fn handle_key_sequence_for_search_popup(
             match c {
                 crossterm::event::KeyCode::Char(c) => {
                     query.push(c);
+                    let query = query.clone();
                     ui.current_page_mut().select(0);
+                    update_context_filter_preset(state, ui, Some(query));
                     return Ok(true);
                 }
                 crossterm::event::KeyCode::Backspace => {
                     if !query.is_empty() {
                         query.pop().unwrap();
+                        let query = (!query.is_empty()).then(|| query.clone());
                         ui.current_page_mut().select(0);
+                        update_context_filter_preset(state, ui, query);
                     }
                     return Ok(true);
                 }
@@ -310,6 +406,17 @@ fn handle_key_sequence_for_search_popup(
     page::handle_key_sequence_for_page(key_sequence, client_pub, state, ui)
 }
 
+/// Update the filter preset of the current page's context (if any)
+pub fn update_context_filter_preset(
+    state: &SharedState,
+    ui: &UIStateGuard,
+    filter: Option<String>,
+) {
+    if let PageState::Context { id: Some(id), .. } = ui.current_page() {
+        state.update_context_preset(id.uri(), |preset| preset.filter = filter);
+    }
+}
+
 /// Handle a command for a context list popup in which each item represents a context
 ///
 /// # Arguments
@@ -403,12 +510,6 @@ fn handle_command_for_list_popup(
     Ok(true)
 }
 
-fn execute_copy_command(text: String) -> Result<()> {
-    CLIPBOARD_PROVIDER
-        .get_or_init(|| get_clipboard_provider())
-        .set_contents(text)
-}
-
 fn handle_key_sequence_for_action_list_popup(
     n_actions: usize,
     key_sequence: &KeySequence,
@@ -488,11 +589,21 @@ fn handle_item_action(
                 client_pub.send(ClientRequest::AddTrackToQueue(track.id))?;
                 ui.popup = None;
             }
+            TrackAction::ShowTrackDetails => {
+                client_pub.send(ClientRequest::GetTrackDetails(track.id.clone()))?;
+                ui.popup = Some(PopupState::TrackDetails(track));
+            }
             TrackAction::CopyTrackLink => {
                 let track_url = format!("https://open.spotify.com/track/{}", track.id.id());
                 execute_copy_command(track_url)?;
                 ui.popup = None;
             }
+            TrackAction::ShareTrack => {
+                ui.popup = Some(PopupState::Share {
+                    url: format!("https://open.spotify.com/track/{}", track.id.id()),
+                    name: track.name,
+                });
+            }
             TrackAction::AddToPlaylist => {
                 client_pub.send(ClientRequest::GetUserPlaylists)?;
                 ui.popup = Some(PopupState::UserPlaylistList(
@@ -579,6 +690,12 @@ fn handle_item_action(
                 execute_copy_command(album_url)?;
                 ui.popup = None;
             }
+            AlbumAction::ShareAlbum => {
+                ui.popup = Some(PopupState::Share {
+                    url: format!("https://open.spotify.com/album/{}", album.id.id()),
+                    name: album.name,
+                });
+            }
             AlbumAction::AddToLibrary => {
                 client_pub.send(ClientRequest::AddToLibrary(Item::Album(album)))?;
                 ui.popup = None;
@@ -611,6 +728,12 @@ fn handle_item_action(
                 execute_copy_command(artist_url)?;
                 ui.popup = None;
             }
+            ArtistAction::ShareArtist => {
+                ui.popup = Some(PopupState::Share {
+                    url: format!("https://open.spotify.com/artist/{}", artist.id.id()),
+                    name: artist.name,
+                });
+            }
             ArtistAction::Unfollow => {
                 client_pub.send(ClientRequest::DeleteFromLibrary(ItemId::Artist(artist.id)))?;
                 ui.popup = None;
@@ -636,6 +759,12 @@ fn handle_item_action(
                 execute_copy_command(playlist_url)?;
                 ui.popup = None;
             }
+            PlaylistAction::SharePlaylist => {
+                ui.popup = Some(PopupState::Share {
+                    url: format!("https://open.spotify.com/playlist/{}", playlist.id.id()),
+                    name: playlist.name,
+                });
+            }
             PlaylistAction::DeleteFromLibrary => {
                 client_pub.send(ClientRequest::DeleteFromLibrary(ItemId::Playlist(
                     playlist.id,
@@ -43,6 +43,11 @@ pub fn handle_command_for_focused_context_window(
             if let Some(tracks) = data.context_tracks(context_id) {
                 tracks.sort_by(|x, y| order.compare(x, y));
             }
+            drop(data);
+            state.update_context_preset(context_id.uri(), |preset| {
+                preset.order = Some(order);
+                preset.reversed = false;
+            });
             return Ok(true);
         }
         // reverse ordering command
@@ -51,6 +56,10 @@ pub fn handle_command_for_focused_context_window(
             if let Some(tracks) = data.context_tracks(context_id) {
                 tracks.reverse();
             }
+            drop(data);
+            state.update_context_preset(context_id.uri(), |preset| {
+                preset.reversed = !preset.reversed;
+            });
             return Ok(true);
         }
     }
@@ -178,10 +187,78 @@ fn handle_command_for_track_table_window(
 ) -> Result<bool> {
     let id = ui.current_page_mut().selected().unwrap_or_default();
     let filtered_tracks = ui.search_filtered_items(tracks);
+
+    if command == Command::ToggleGroupByAlbum {
+        return Ok(match ui.current_page_mut().album_grouping_mut() {
+            Some(album_grouping) => {
+                *album_grouping = match album_grouping {
+                    None => Some(AlbumGroupingState::default()),
+                    Some(_) => None,
+                };
+                ui.current_page_mut().select(0);
+                true
+            }
+            None => false,
+        });
+    }
+
+    match command {
+        Command::CopyTrackTableAsCsv => {
+            execute_copy_command(crate::utils::tracks_to_csv(&filtered_tracks))?;
+            return Ok(true);
+        }
+        Command::CopyTrackTableAsMarkdown => {
+            execute_copy_command(crate::utils::tracks_to_markdown(&filtered_tracks))?;
+            return Ok(true);
+        }
+        Command::ExportTrackTableAsCsv => {
+            let path = crate::utils::export_track_table(
+                &crate::utils::tracks_to_csv(&filtered_tracks),
+                "csv",
+            )?;
+            tracing::info!("Exported the track table to {}", path.display());
+            return Ok(true);
+        }
+        Command::ExportTrackTableAsMarkdown => {
+            let path = crate::utils::export_track_table(
+                &crate::utils::tracks_to_markdown(&filtered_tracks),
+                "md",
+            )?;
+            tracing::info!("Exported the track table to {}", path.display());
+            return Ok(true);
+        }
+        _ => {}
+    }
+
+    // In a grouped track table, the selected row can be either a group header or a track.
+    // Handle commands specific to the grouped table, then map the selected row into its track's position.
+    let id = match ui.current_page().grouped_track_table_rows(&filtered_tracks) {
+        Some(rows) => match handle_command_for_grouped_rows(command, &rows, id, ui) {
+            Some(handled) => return Ok(handled),
+            None => match rows[id] {
+                TrackTableRow::Track(id) => id,
+                TrackTableRow::AlbumHeader { .. } | TrackTableRow::DiscHeader { .. } => {
+                    unreachable!()
+                }
+            },
+        },
+        None => id,
+    };
+
     if id >= filtered_tracks.len() {
         return Ok(false);
     }
 
+    // track actions are not supported for a playlist's podcast episode
+    if filtered_tracks[id].episode.is_some()
+        && matches!(
+            command,
+            Command::ShowActionsOnSelectedItem | Command::AddSelectedItemToQueue
+        )
+    {
+        return Ok(false);
+    }
+
     if let Some(ContextId::Playlist(ref playlist_id)) = context_id {
         let modifiable = data
             .user_data
@@ -209,18 +286,33 @@ fn handle_command_for_track_table_window(
 
     match command {
         Command::PlayRandom | Command::ChooseSelected => {
+            // unplayable tracks are skipped on playback. Without a context, the playback is
+            // started from a list of track URIs, which can't include episodes.
+            let can_play =
+                |t: &Track| t.is_playable && (context_id.is_some() || t.episode.is_none());
+            let playable_tracks = tracks.iter().filter(|t| can_play(t)).collect::<Vec<_>>();
             let uri = if command == Command::PlayRandom {
-                tracks[rand::thread_rng().gen_range(0..tracks.len())]
-                    .id
-                    .uri()
+                if playable_tracks.is_empty() {
+                    return Ok(true);
+                }
+                playable_tracks[rand::thread_rng().gen_range(0..playable_tracks.len())].uri()
             } else {
-                filtered_tracks[id].id.uri()
+                if !can_play(filtered_tracks[id]) {
+                    return Ok(true);
+                }
+                filtered_tracks[id].uri()
             };
 
             let base_playback = if let Some(context_id) = context_id {
                 Playback::Context(context_id, None)
             } else {
-                Playback::URIs(tracks.iter().map(|t| t.id.clone_static()).collect(), None)
+                Playback::URIs(
+                    playable_tracks
+                        .iter()
+                        .map(|t| t.id.clone_static())
+                        .collect(),
+                    None,
+                )
             };
 
             client_pub.send(ClientRequest::Player(PlayerRequest::StartPlayback(
@@ -241,11 +333,75 @@ fn handle_command_for_track_table_window(
                 filtered_tracks[id].id.clone(),
             ))?;
         }
+        Command::CopySelectedItemLink => execute_copy_command(filtered_tracks[id].url())?,
+        Command::CopySelectedItemUri => execute_copy_command(filtered_tracks[id].uri())?,
+        Command::OpenSelectedItemExternally => {
+            crate::utils::open_spotify_item(
+                &filtered_tracks[id].uri(),
+                &filtered_tracks[id].url(),
+            )?;
+        }
         _ => return Ok(false),
     }
     Ok(true)
 }
 
+/// Handle a command for a grouped (by album or by disc) track table.
+///
+/// Returns `None` if the command needs to be handled by the track table's handler
+/// for the selected track.
+fn handle_command_for_grouped_rows(
+    command: Command,
+    rows: &[TrackTableRow],
+    id: usize,
+    ui: &mut UIStateGuard,
+) -> Option<bool> {
+    if id >= rows.len() {
+        return Some(false);
+    }
+
+    if handle_navigation_command(command, ui.current_page_mut(), id, rows.len()) {
+        return Some(true);
+    }
+
+    let is_header = |row: &TrackTableRow| {
+        matches!(
+            row,
+            TrackTableRow::AlbumHeader { .. } | TrackTableRow::DiscHeader { .. }
+        )
+    };
+    match command {
+        Command::SelectNextGroup => {
+            if let Some(pos) = rows[id + 1..].iter().position(is_header) {
+                ui.current_page_mut().select(id + 1 + pos);
+            }
+            Some(true)
+        }
+        Command::SelectPreviousGroup => {
+            if let Some(pos) = rows[..id].iter().rposition(is_header) {
+                ui.current_page_mut().select(pos);
+            }
+            Some(true)
+        }
+        // re-ordering playlist items is not supported in a grouped track table
+        Command::MovePlaylistItemUp | Command::MovePlaylistItemDown => Some(false),
+        _ => match rows[id] {
+            TrackTableRow::AlbumHeader { ref uri, .. } => {
+                if command != Command::ChooseSelected {
+                    return Some(false);
+                }
+                // collapse or expand the selected album group
+                if let Some(Some(album_grouping)) = ui.current_page_mut().album_grouping_mut() {
+                    album_grouping.toggle_collapsed(uri);
+                }
+                Some(true)
+            }
+            TrackTableRow::DiscHeader { .. } => Some(false),
+            TrackTableRow::Track(_) => None,
+        },
+    }
+}
+
 pub fn handle_command_for_track_list_window(
     command: Command,
     client_pub: &flume::Sender<ClientRequest>,
@@ -283,6 +439,11 @@ pub fn handle_command_for_track_list_window(
         Command::AddSelectedItemToQueue => {
             client_pub.send(ClientRequest::AddTrackToQueue(tracks[id].id.clone()))?;
         }
+        Command::CopySelectedItemLink => execute_copy_command(tracks[id].url())?,
+        Command::CopySelectedItemUri => execute_copy_command(tracks[id].uri())?,
+        Command::OpenSelectedItemExternally => {
+            crate::utils::open_spotify_item(&tracks[id].uri(), &tracks[id].url())?;
+        }
         _ => return Ok(false),
     }
     Ok(true)
@@ -318,6 +479,11 @@ pub fn handle_command_for_artist_list_window(
                 new_list_state(),
             ));
         }
+        Command::CopySelectedItemLink => execute_copy_command(artists[id].id.url())?,
+        Command::CopySelectedItemUri => execute_copy_command(artists[id].id.uri())?,
+        Command::OpenSelectedItemExternally => {
+            crate::utils::open_spotify_item(&artists[id].id.uri(), &artists[id].id.url())?;
+        }
         _ => return Ok(false),
     }
     Ok(true)
@@ -357,6 +523,11 @@ pub fn handle_command_for_album_list_window(
         Command::AddSelectedItemToQueue => {
             client_pub.send(ClientRequest::AddAlbumToQueue(albums[id].id.clone()))?;
         }
+        Command::CopySelectedItemLink => execute_copy_command(albums[id].id.url())?,
+        Command::CopySelectedItemUri => execute_copy_command(albums[id].id.uri())?,
+        Command::OpenSelectedItemExternally => {
+            crate::utils::open_spotify_item(&albums[id].id.uri(), &albums[id].id.url())?;
+        }
         _ => return Ok(false),
     }
     Ok(true)
@@ -392,6 +563,11 @@ pub fn handle_command_for_playlist_list_window(
                 new_list_state(),
             ));
         }
+        Command::CopySelectedItemLink => execute_copy_command(playlists[id].id.url())?,
+        Command::CopySelectedItemUri => execute_copy_command(playlists[id].id.uri())?,
+        Command::OpenSelectedItemExternally => {
+            crate::utils::open_spotify_item(&playlists[id].id.uri(), &playlists[id].id.url())?;
+        }
         _ => return Ok(false),
     }
     Ok(true)
@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, Request as HttpRequest, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cli::{self, Command, GetRequest, IdOrName, Key, Request},
+    client::Client,
+    state::SharedState,
+    utils::map_join,
+};
+
+/// the interval to send the current playback to the overlay's event stream
+const OVERLAY_UPDATE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+#[derive(Clone)]
+struct ApiState {
+    client: Client,
+    state: SharedState,
+    token: Arc<str>,
+}
+
+#[derive(Deserialize)]
+struct VolumeParams {
+    percent: i8,
+    #[serde(default)]
+    offset: bool,
+}
+
+#[derive(Deserialize)]
+struct SeekParams {
+    offset_ms: i64,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+}
+
+#[derive(Deserialize)]
+struct QueueParams {
+    id: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Serialize)]
+/// The current playback's data displayed by the now-playing overlay
+struct NowPlaying {
+    is_playing: bool,
+    track: String,
+    artists: String,
+    album: String,
+    art_url: Option<String>,
+    progress_ms: i64,
+    duration_ms: i64,
+}
+
+/// Start the HTTP API server, which exposes the CLI's control surface as REST endpoints
+pub async fn start_server(client: Client, state: SharedState, address: String) {
+    let configs = crate::config::get_config();
+    let Some(token) = configs.app_config.http_api_token.as_ref() else {
+        tracing::error!("`http_api_token` is not set, refusing to start the HTTP API server");
+        return;
+    };
+    let token = Arc::from(token.expose());
+
+    let api_state = ApiState {
+        client,
+        state,
+        token,
+    };
+    let router = Router::new()
+        .route("/status", get(status))
+        .route("/queue", get(queue).post(add_to_queue))
+        .route("/search", get(search))
+        .route("/playback/volume", post(volume))
+        .route("/playback/seek", post(seek))
+        .route("/playback/:command", post(playback))
+        .layer(middleware::from_fn_with_state(api_state.clone(), authorize))
+        // the overlay's endpoints are read-only and don't require authorization,
+        // so that they can be embedded by browser sources (e.g. in OBS)
+        .route("/overlay", get(overlay))
+        .route("/overlay/now-playing", get(now_playing))
+        .route("/overlay/events", get(overlay_events))
+        .with_state(api_state);
+
+    tracing::info!("Starting the HTTP API server at {address}");
+    let listener = match tokio::net::TcpListener::bind(&address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            tracing::warn!("Failed to bind the HTTP API server to {address}: {err:#}");
+            return;
+        }
+    };
+    if let Err(err) = axum::serve(listener, router).await {
+        tracing::error!("Encountered an error when running the HTTP API server: {err:#}");
+    }
+}
+
+/// Reject requests without the configured bearer token
+async fn authorize(State(api): State<ApiState>, request: HttpRequest, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|t| constant_time_eq(t.as_bytes(), api.token.as_bytes()));
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, "Missing or invalid bearer token").into_response();
+    }
+    next.run(request).await
+}
+
+/// Compare two byte strings in a time independent of their contents,
+/// so that the bearer token can't be guessed from the response times
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Handle an API request with the client socket's request handler
+async fn handle(api: &ApiState, request: Request) -> Response {
+    tracing::info!("Handling an HTTP API request: {request:?}");
+    let state = Some(api.state.clone());
+    let result = cli::handle_socket_request(&api.client, &state, request).await;
+    api.state.mark_changed();
+
+    match result {
+        Ok(data) if data.is_empty() => StatusCode::NO_CONTENT.into_response(),
+        Ok(data) => ([(header::CONTENT_TYPE, "application/json")], data).into_response(),
+        Err(err) => {
+            tracing::error!("Failed to handle an HTTP API request: {err:#}");
+            (StatusCode::BAD_REQUEST, format!("Bad request: {err:#}")).into_response()
+        }
+    }
+}
+
+async fn status(State(api): State<ApiState>) -> Response {
+    handle(&api, Request::Get(GetRequest::Key(Key::Playback))).await
+}
+
+async fn queue(State(api): State<ApiState>) -> Response {
+    handle(&api, Request::Get(GetRequest::Key(Key::Queue))).await
+}
+
+async fn add_to_queue(State(api): State<ApiState>, Query(params): Query<QueueParams>) -> Response {
+    let id_or_name = match (params.id, params.name) {
+        (Some(id), _) => IdOrName::Id(id),
+        (None, Some(name)) => IdOrName::Name(name),
+        (None, None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                "Either `id` or `name` must be specified",
+            )
+                .into_response()
+        }
+    };
+    handle(&api, Request::Queue(id_or_name)).await
+}
+
+async fn search(State(api): State<ApiState>, Query(params): Query<SearchParams>) -> Response {
+    handle(&api, Request::Search { query: params.q }).await
+}
+
+async fn volume(State(api): State<ApiState>, Query(params): Query<VolumeParams>) -> Response {
+    if !(-100..=100).contains(&params.percent) {
+        return (
+            StatusCode::BAD_REQUEST,
+            "`percent` must be in range [-100, 100]",
+        )
+            .into_response();
+    }
+    let command = Command::Volume {
+        percent: params.percent,
+        is_offset: params.offset,
+    };
+    handle(&api, Request::Playback(command)).await
+}
+
+async fn seek(State(api): State<ApiState>, Query(params): Query<SeekParams>) -> Response {
+    handle(&api, Request::Playback(Command::Seek(params.offset_ms))).await
+}
+
+fn current_now_playing(state: &SharedState) -> Option<NowPlaying> {
+    let player = state.player.read();
+    let track = player.current_playing_track()?;
+    Some(NowPlaying {
+        is_playing: player
+            .buffered_playback
+            .as_ref()
+            .is_some_and(|p| p.is_playing),
+        track: track.name.clone(),
+        artists: map_join(&track.artists, |a| &a.name, ", "),
+        album: track.album.name.clone(),
+        art_url: track.album.images.first().map(|i| i.url.clone()),
+        progress_ms: player
+            .playback_progress()
+            .map(|p| p.num_milliseconds())
+            .unwrap_or_default(),
+        duration_ms: track.duration.num_milliseconds(),
+    })
+}
+
+async fn overlay() -> Html<&'static str> {
+    Html(include_str!("overlay.html"))
+}
+
+async fn now_playing(State(api): State<ApiState>) -> Json<Option<NowPlaying>> {
+    Json(current_now_playing(&api.state))
+}
+
+/// Stream the current playback's data (or `null` if no track is playing) to the overlay
+/// as server-sent events
+async fn overlay_events(
+    State(api): State<ApiState>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let interval = tokio::time::interval(OVERLAY_UPDATE_INTERVAL);
+    let stream = futures::stream::unfold((api.state, interval), |(state, mut interval)| async {
+        interval.tick().await;
+        let event = Event::default().json_data(current_now_playing(&state));
+        Some((event, (state, interval)))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn playback(State(api): State<ApiState>, Path(command): Path<String>) -> Response {
+    let command = match command.as_str() {
+        "play-pause" => Command::PlayPause,
+        "play" => Command::Play,
+        "pause" => Command::Pause,
+        "next" => Command::Next,
+        "previous" => Command::Previous,
+        "shuffle" => Command::Shuffle,
+        "repeat" => Command::Repeat,
+        _ => return (StatusCode::NOT_FOUND, "Unknown playback command").into_response(),
+    };
+    handle(&api, Request::Playback(command)).await
+}
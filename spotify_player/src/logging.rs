@@ -0,0 +1,226 @@
+use std::{
+    collections::VecDeque,
+    io::Write,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::Mutex;
+use tracing_subscriber::{reload, EnvFilter};
+
+use crate::config;
+
+/// the maximum number of log entries kept in memory
+const MAX_LOG_ENTRIES: usize = 500;
+
+static LOG_ENTRIES: Lazy<Mutex<VecDeque<LogEntry>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_LOG_ENTRIES)));
+/// a counter increased on every new log entry
+static LOG_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+/// A log entry recorded by the application
+pub struct LogEntry {
+    pub level: tracing::Level,
+    pub line: String,
+}
+
+/// A log writer that writes logs into a file while keeping
+/// the most recent log entries in memory for the in-app log viewer
+pub struct LogWriter {
+    file: std::fs::File,
+}
+
+impl LogWriter {
+    pub fn new(file: std::fs::File) -> Self {
+        Self { file }
+    }
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // the formatter writes each event in a single call, so
+        // the buffer can be treated as a list of complete log lines
+        let text = String::from_utf8_lossy(buf);
+        let mut entries = LOG_ENTRIES.lock();
+        for line in text.lines().filter(|l| !l.is_empty()) {
+            // lines without a level are continuation of the previous entry's message
+            match line_level(line) {
+                Some(level) => {
+                    if entries.len() == MAX_LOG_ENTRIES {
+                        entries.pop_front();
+                    }
+                    entries.push_back(LogEntry {
+                        level,
+                        line: line.to_string(),
+                    });
+                }
+                None => {
+                    if let Some(entry) = entries.back_mut() {
+                        entry.line.push(' ');
+                        entry.line.push_str(line.trim());
+                    }
+                }
+            }
+        }
+        LOG_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// gets the level of a formatted log line, which is either a JSON object with a `level` field
+/// or a text line looking like `<timestamp> <level> <target>: <message>`
+fn line_level(line: &str) -> Option<tracing::Level> {
+    if line.starts_with('{') {
+        let value = serde_json::from_str::<serde_json::Value>(line).ok()?;
+        value.get("level")?.as_str()?.parse().ok()
+    } else {
+        line.split_whitespace().nth(1)?.parse().ok()
+    }
+}
+
+/// gets the most recent log entries whose level is at least as severe as `level`
+pub fn recent_log_entries(level: tracing::Level, limit: usize) -> Vec<LogEntry> {
+    let entries = LOG_ENTRIES.lock();
+    let mut entries = entries
+        .iter()
+        .rev()
+        .filter(|e| e.level <= level)
+        .take(limit)
+        .cloned()
+        .collect::<Vec<_>>();
+    entries.reverse();
+    entries
+}
+
+/// gets the number of times new logs were recorded, which is used
+/// by the UI to determine whether the log pane needs to be redrawn
+pub fn log_counter() -> u64 {
+    LOG_COUNTER.load(Ordering::Relaxed)
+}
+
+/// the subscriber the log filter is applied to
+#[cfg(feature = "opentelemetry")]
+type FilteredSubscriber = tracing_subscriber::layer::Layered<
+    reload::Layer<OtlpLayer, tracing_subscriber::Registry>,
+    tracing_subscriber::Registry,
+>;
+#[cfg(not(feature = "opentelemetry"))]
+type FilteredSubscriber = tracing_subscriber::Registry;
+
+/// the `--log-level` argument (if any), which overrides the configured log levels
+static LOG_LEVEL_ARG: OnceCell<Option<String>> = OnceCell::new();
+static LOG_FILTER_HANDLE: OnceCell<reload::Handle<EnvFilter, FilteredSubscriber>> = OnceCell::new();
+
+/// creates the filter of the application's logs, which is updated by `reload_log_filter`
+/// when the configurations are reloaded
+pub fn log_filter(
+    configs: &config::Configs,
+    log_level: Option<&String>,
+) -> anyhow::Result<reload::Layer<EnvFilter, FilteredSubscriber>> {
+    LOG_LEVEL_ARG.set(log_level.cloned()).ok();
+    let (layer, handle) = reload::Layer::new(new_env_filter(configs)?);
+    LOG_FILTER_HANDLE.set(handle).ok();
+    Ok(layer)
+}
+
+/// updates the filter of the application's logs based on the current configurations
+pub fn reload_log_filter(configs: &config::Configs) -> anyhow::Result<()> {
+    if let Some(handle) = LOG_FILTER_HANDLE.get() {
+        handle.reload(new_env_filter(configs)?)?;
+    }
+    Ok(())
+}
+
+fn new_env_filter(configs: &config::Configs) -> anyhow::Result<EnvFilter> {
+    // the per-module levels in the config file are overridden by
+    // the `RUST_LOG` environment variable and the `--log-level` argument
+    let mut directives = configs
+        .app_config
+        .log_levels
+        .iter()
+        .flatten()
+        .map(|(module, level)| format!("{module}={level}"))
+        .collect::<Vec<_>>();
+    match std::env::var("RUST_LOG") {
+        Ok(env) => directives.push(env),
+        // default to log the current crate only
+        Err(_) if directives.is_empty() => directives.push("spotify_player=info".to_string()),
+        Err(_) => {}
+    }
+    if let Some(level) = LOG_LEVEL_ARG.get().and_then(Option::as_ref) {
+        directives.push(format!("spotify_player={level}"));
+    }
+    EnvFilter::try_new(directives.join(","))
+        .map_err(|err| anyhow::anyhow!("invalid log level directives: {err}"))
+}
+
+#[cfg(feature = "opentelemetry")]
+type OtlpLayer =
+    Option<Box<dyn tracing_subscriber::Layer<tracing_subscriber::Registry> + Send + Sync>>;
+
+#[cfg(feature = "opentelemetry")]
+static OTLP_LAYER_HANDLE: once_cell::sync::OnceCell<
+    tracing_subscriber::reload::Handle<OtlpLayer, tracing_subscriber::Registry>,
+> = once_cell::sync::OnceCell::new();
+#[cfg(feature = "opentelemetry")]
+static OTLP_TRACER_PROVIDER: once_cell::sync::OnceCell<opentelemetry_sdk::trace::TracerProvider> =
+    once_cell::sync::OnceCell::new();
+
+/// creates a placeholder layer for exporting spans to an OTLP collector, which is
+/// replaced by the exporter's layer in `init_otlp_exporter`.
+///
+/// The exporter is initialized separately because it requires the application's async runtime.
+#[cfg(feature = "opentelemetry")]
+pub fn otlp_layer() -> tracing_subscriber::reload::Layer<OtlpLayer, tracing_subscriber::Registry> {
+    let (layer, handle) = tracing_subscriber::reload::Layer::new(None);
+    OTLP_LAYER_HANDLE.set(handle).ok();
+    layer
+}
+
+/// initializes the exporter sending the application's spans to an OTLP collector via HTTP
+#[cfg(feature = "opentelemetry")]
+pub fn init_otlp_exporter(endpoint: &str) -> anyhow::Result<()> {
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig as _;
+
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(
+            opentelemetry_sdk::Resource::new([opentelemetry::KeyValue::new(
+                "service.name",
+                env!("CARGO_PKG_NAME"),
+            )]),
+        ))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+    let tracer = provider.tracer(env!("CARGO_PKG_NAME"));
+    OTLP_TRACER_PROVIDER.set(provider).ok();
+
+    let handle = OTLP_LAYER_HANDLE
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("the OTLP layer is not initialized"))?;
+    let layer: OtlpLayer = Some(Box::new(tracing_opentelemetry::layer().with_tracer(tracer)));
+    handle.reload(layer)?;
+    tracing::info!("Exporting spans to the OTLP collector at {endpoint}");
+    Ok(())
+}
+
+/// flushes the pending spans and shuts down the OTLP exporter
+#[cfg(feature = "opentelemetry")]
+pub fn shutdown_otlp_exporter() {
+    if let Some(provider) = OTLP_TRACER_PROVIDER.get() {
+        if let Err(err) = provider.shutdown() {
+            tracing::warn!("Failed to shut down the OTLP exporter: {err:#}");
+        }
+    }
+}
@@ -3,20 +3,38 @@ mod cli;
 mod client;
 mod command;
 mod config;
+mod encryption;
 mod event;
+#[cfg(feature = "http-api")]
+mod http_api;
 mod key;
+mod logging;
 #[cfg(feature = "media-control")]
 mod media_control;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "mpd")]
+mod mpd;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+#[cfg(feature = "plugins")]
+mod plugin;
+#[cfg(unix)]
+mod rpc;
+#[cfg(feature = "scripting")]
+mod scripting;
 mod state;
 #[cfg(feature = "streaming")]
 mod streaming;
 mod token;
 mod ui;
 mod utils;
+mod visualizer;
 
 use anyhow::{Context, Result};
 use rspotify::clients::BaseClient;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
+use tracing_subscriber::{layer::SubscriberExt as _, util::SubscriberInitExt as _, Layer as _};
 
 async fn init_spotify(
     client_pub: &flume::Sender<client::ClientRequest>,
@@ -47,23 +65,31 @@ async fn init_spotify(
     Ok(())
 }
 
-fn init_logging(cache_folder: &std::path::Path) -> Result<()> {
+fn init_logging(cache_folder: &std::path::Path, log_level: Option<&String>) -> Result<()> {
     let log_prefix = format!(
         "spotify-player-{}",
         chrono::Local::now().format("%y-%m-%d-%H-%M")
     );
 
     // initialize the application's logging
-    if std::env::var("RUST_LOG").is_err() {
-        std::env::set_var("RUST_LOG", "spotify_player=info"); // default to log the current crate only
-    }
+    let configs = config::get_config();
+    let filter = logging::log_filter(&configs, log_level)?;
+
     let log_file = std::fs::File::create(cache_folder.join(format!("{log_prefix}.log")))
         .context("failed to create log file")?;
-    tracing_subscriber::fmt::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+    let writer = std::sync::Mutex::new(logging::LogWriter::new(log_file));
+    let fmt_layer = tracing_subscriber::fmt::layer()
         .with_ansi(false)
-        .with_writer(std::sync::Mutex::new(log_file))
-        .init();
+        .with_writer(writer);
+    let fmt_layer = match configs.app_config.log_format {
+        config::LogFormat::Text => fmt_layer.boxed(),
+        config::LogFormat::Json => fmt_layer.json().flatten_event(true).boxed(),
+    };
+
+    let registry = tracing_subscriber::registry();
+    #[cfg(feature = "opentelemetry")]
+    let registry = registry.with(logging::otlp_layer());
+    registry.with(filter).with(fmt_layer).init();
 
     // initialize the application's panic backtrace
     let backtrace_file =
@@ -80,10 +106,57 @@ fn init_logging(cache_folder: &std::path::Path) -> Result<()> {
     Ok(())
 }
 
+/// waits for a signal terminating the application (`SIGINT` or `SIGTERM`)
+#[cfg(feature = "opentelemetry")]
+async fn wait_for_termination_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        let mut sigterm =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            _ = sigterm.recv() => {}
+            res = tokio::signal::ctrl_c() => res?,
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
+
 #[tokio::main]
-async fn start_app(state: &state::SharedState) -> Result<()> {
+async fn start_app(
+    state: &state::SharedState,
+    start_context: Option<(state::ContextId, bool)>,
+    offline: bool,
+    data_source: Option<client::DataSource>,
+) -> Result<()> {
     let configs = config::get_config();
 
+    // the exporter requires the async runtime, so it's initialized here instead of upon
+    // initializing the application's logging
+    #[cfg(feature = "opentelemetry")]
+    if let Some(ref endpoint) = configs.app_config.otlp_endpoint {
+        if let Err(err) = logging::init_otlp_exporter(endpoint) {
+            tracing::warn!("Failed to initialize the OTLP exporter: {err:#}");
+        }
+
+        // a daemon is stopped by a signal, upon which the pending spans are flushed.
+        // The UI flushes them itself when quitting.
+        if state.is_daemon {
+            tokio::task::spawn(async {
+                if let Err(err) = wait_for_termination_signal().await {
+                    tracing::error!("Failed to listen for termination signals: {err:#}");
+                    return;
+                }
+                tracing::info!("Received a termination signal, exiting...");
+                tokio::task::spawn_blocking(logging::shutdown_otlp_exporter)
+                    .await
+                    .unwrap_or_default();
+                std::process::exit(0);
+            });
+        }
+    }
+
     if !state.is_daemon {
         #[cfg(feature = "image")]
         {
@@ -124,18 +197,81 @@ async fn start_app(state: &state::SharedState) -> Result<()> {
         }
     }
 
-    // create a librespot session
-    let auth_config = auth::AuthConfig::new(configs)?;
-    let session = auth::new_session(&auth_config, !state.is_daemon).await?;
+    // create a librespot session, the application starts in offline mode
+    // if specified or if Spotify cannot be reached
+    let auth_config = auth::AuthConfig::new(&configs)?;
+    let is_offline_data_source = data_source.as_ref().is_some_and(|s| s.is_offline());
+    let session = if offline || is_offline_data_source {
+        None
+    } else {
+        match auth::new_session(&auth_config, !state.is_daemon).await {
+            Ok(session) => Some(session),
+            Err(err) if client::ClientError::classify(&err) == client::ClientError::Network => {
+                tracing::warn!("Failed to connect to Spotify, starting in offline mode: {err:#}");
+                None
+            }
+            Err(err) => return Err(err),
+        }
+    };
+    state.set_offline(session.is_none());
 
     // create a Spotify API client
-    let client = client::Client::new(session, auth_config, configs.app_config.client_id.clone());
-    client.refresh_token().await?;
+    let mut client =
+        client::Client::new(session, auth_config, configs.app_config.client_id.clone());
+    if let Some(data_source) = data_source {
+        client = client.with_data_source(data_source);
+    }
+
+    if is_offline_data_source {
+        // request user data from the alternative data source
+        client_pub.send(client::ClientRequest::GetUserPlaylists)?;
+        client_pub.send(client::ClientRequest::GetUserFollowedArtists)?;
+        client_pub.send(client::ClientRequest::GetUserSavedAlbums)?;
+        client_pub.send(client::ClientRequest::GetUserSavedTracks)?;
+    } else if state.is_offline() {
+        // initialize Spotify-related stuff once connected to Spotify
+        tokio::task::spawn({
+            let client = client.clone();
+            let client_pub = client_pub.clone();
+            let state = state.clone();
+            async move {
+                client.reconnect_when_online(&state).await;
+                if let Err(err) = init_spotify(&client_pub, &client, &state).await {
+                    tracing::error!("Failed to initialize the Spotify data: {err:#}");
+                }
+                client::start_token_refresher(client).await;
+            }
+        });
+        // browse the liked tracks stored in the cache folder
+        client_pub.send(client::ClientRequest::GetUserSavedTracks)?;
+    } else {
+        client.refresh_token().await?;
+
+        // initialize Spotify-related stuff
+        init_spotify(&client_pub, &client, state)
+            .await
+            .context("Failed to initialize the Spotify data")?;
+    }
 
-    // initialize Spotify-related stuff
-    init_spotify(&client_pub, &client, state)
-        .await
-        .context("Failed to initialize the Spotify data")?;
+    // start playing the context specified by CLI arguments
+    if let Some((id, true)) = start_context.filter(|_| !state.is_offline()) {
+        let playback = match id {
+            state::ContextId::Tracks(_) => {
+                let mut ids = client
+                    .current_user_saved_tracks()
+                    .await?
+                    .into_iter()
+                    .map(|t| t.id)
+                    .collect::<Vec<_>>();
+                ids.truncate(configs.app_config.tracks_playback_limit);
+                state::Playback::URIs(ids, None)
+            }
+            id => state::Playback::Context(id, None),
+        };
+        client_pub.send(client::ClientRequest::Player(
+            client::PlayerRequest::StartPlayback(playback, None),
+        ))?;
+    }
 
     // Spawn application's tasks
     let mut tasks = Vec::new();
@@ -144,8 +280,8 @@ async fn start_app(state: &state::SharedState) -> Result<()> {
     tasks.push(tokio::task::spawn({
         let client = client.clone();
         let state = state.clone();
+        let port = configs.app_config.client_port;
         async move {
-            let port = configs.app_config.client_port;
             tracing::info!("Starting a client socket at 127.0.0.1:{port}");
             match tokio::net::UdpSocket::bind(("127.0.0.1", port)).await {
                 Ok(socket) => cli::start_socket(client, socket, Some(state)).await,
@@ -158,6 +294,83 @@ async fn start_app(state: &state::SharedState) -> Result<()> {
         }
     }));
 
+    #[cfg(unix)]
+    if configs.app_config.enable_rpc_socket {
+        // JSON-RPC server task (for local integrations)
+        tasks.push(tokio::task::spawn(rpc::start_server(
+            client.clone(),
+            state.clone(),
+        )));
+    }
+
+    #[cfg(feature = "mpd")]
+    if let Some(address) = configs.app_config.mpd_address.clone() {
+        // MPD server task (for controlling the playback with MPD clients)
+        tasks.push(tokio::task::spawn(mpd::start_server(
+            client.clone(),
+            state.clone(),
+            address,
+        )));
+    }
+
+    #[cfg(feature = "mqtt")]
+    if let Some(mqtt_configs) = configs.app_config.mqtt.clone() {
+        // MQTT publisher task (for home-automation integrations)
+        tasks.push(tokio::task::spawn(mqtt::start_publisher(
+            state.clone(),
+            client_pub.clone(),
+            mqtt_configs,
+        )));
+    }
+
+    #[cfg(feature = "metrics")]
+    if let Some(address) = configs.app_config.metrics_address.clone() {
+        // metrics server task (for monitoring long-running instances)
+        tasks.push(tokio::task::spawn(metrics::start_server(
+            state.clone(),
+            address,
+        )));
+    }
+
+    #[cfg(feature = "http-api")]
+    if let Some(address) = configs.app_config.http_api_address.clone() {
+        // HTTP API server task (for remote control)
+        tasks.push(tokio::task::spawn(http_api::start_server(
+            client.clone(),
+            state.clone(),
+            address,
+        )));
+    }
+
+    // token refresher task, which is started once reconnected in offline mode
+    if !state.is_offline() {
+        tasks.push(tokio::task::spawn({
+            let client = client.clone();
+            async move {
+                client::start_token_refresher(client).await;
+            }
+        }));
+    }
+
+    // Spotify Connect notification watcher task
+    tasks.push(tokio::task::spawn({
+        let client = client.clone();
+        let state = state.clone();
+        let client_pub = client_pub.clone();
+        async move {
+            client::start_connect_notification_watcher(client, state, client_pub).await;
+        }
+    }));
+
+    // context prefetcher task
+    tasks.push(tokio::task::spawn({
+        let state = state.clone();
+        let client_pub = client_pub.clone();
+        async move {
+            client::start_context_prefetcher(state, client_pub).await;
+        }
+    }));
+
     // client event handler task
     tasks.push(tokio::task::spawn({
         let state = state.clone();
@@ -192,6 +405,12 @@ async fn start_app(state: &state::SharedState) -> Result<()> {
             let state = state.clone();
             move || ui::run(state)
         });
+
+        // config watcher task
+        tokio::task::spawn_blocking({
+            let state = state.clone();
+            move || event::start_config_watcher(state)
+        });
     }
 
     #[cfg(feature = "media-control")]
@@ -234,18 +453,38 @@ fn main() -> Result<()> {
     let args = cli::init_cli()?.get_matches();
 
     // initialize the application's cache and config folders
-    let config_folder: std::path::PathBuf = args
-        .get_one::<String>("config-folder")
-        .expect("config-folder should have default value")
-        .into();
+    // the legacy folders are only migrated if the default folders are used
+    let config_folder: std::path::PathBuf =
+        if args.value_source("config-folder") == Some(clap::parser::ValueSource::DefaultValue) {
+            config::migrate_config_folder()?
+        } else {
+            args.get_one::<String>("config-folder")
+                .expect("config-folder should have default value")
+                .into()
+        };
     if !config_folder.exists() {
         std::fs::create_dir_all(&config_folder)?;
     }
 
-    let cache_folder: std::path::PathBuf = args
-        .get_one::<String>("cache-folder")
-        .expect("cache-folder should have a default value")
-        .into();
+    let profile = args.get_one::<String>("profile").map(String::as_str);
+    if let Some(profile) = profile {
+        let profile_config_folder = config::get_profile_config_folder(&config_folder, profile);
+        if !profile_config_folder.exists() {
+            std::fs::create_dir_all(&profile_config_folder)?;
+        }
+    }
+
+    let mut cache_folder: std::path::PathBuf =
+        if args.value_source("cache-folder") == Some(clap::parser::ValueSource::DefaultValue) {
+            config::migrate_cache_folder()?
+        } else {
+            args.get_one::<String>("cache-folder")
+                .expect("cache-folder should have a default value")
+                .into()
+        };
+    if let Some(profile) = profile {
+        cache_folder = config::get_profile_cache_folder(&cache_folder, profile);
+    }
     let cache_audio_folder = cache_folder.join("audio");
     if !cache_audio_folder.exists() {
         std::fs::create_dir_all(&cache_audio_folder)?;
@@ -255,9 +494,19 @@ fn main() -> Result<()> {
         std::fs::create_dir_all(&cache_image_folder)?;
     }
 
+    // walk the user through the initial configurations on the first launch,
+    // the configurations of a profile are written into the profile's config folder
+    if args.subcommand().is_none() && std::io::stdin().is_terminal() {
+        let wizard_config_folder = match profile {
+            Some(profile) => config::get_profile_config_folder(&config_folder, profile),
+            None => config_folder.clone(),
+        };
+        config::run_first_launch_wizard(&wizard_config_folder)?;
+    }
+
     // initialize the application configs
     {
-        let mut configs = config::Configs::new(&config_folder, &cache_folder)?;
+        let mut configs = config::Configs::new(&config_folder, &cache_folder, profile)?;
         if let Some(theme) = args.get_one::<String>("theme") {
             // override the theme config if user specifies a `theme` cli argument
             theme.clone_into(&mut configs.app_config.theme);
@@ -268,7 +517,8 @@ fn main() -> Result<()> {
     match args.subcommand() {
         None => {
             // initialize the application's log
-            init_logging(&cache_folder).context("failed to initialize application's logging")?;
+            init_logging(&cache_folder, args.get_one::<String>("log-level"))
+                .context("failed to initialize application's logging")?;
 
             // log the application's configurations
             tracing::info!("Configurations: {:?}", config::get_config());
@@ -298,7 +548,36 @@ fn main() -> Result<()> {
             }
 
             let state = std::sync::Arc::new(state::State::new(is_daemon));
-            start_app(&state)
+
+            // open the context specified by CLI arguments
+            let start_context = cli::get_start_context(&args)?;
+            if let Some(id) = &start_context {
+                state.ui.lock().new_page(state::PageState::Context {
+                    id: None,
+                    context_page_type: state::ContextPageType::Browsing(id.clone()),
+                    state: None,
+                });
+            }
+
+            let data_source = if let Some(path) = args.get_one::<std::path::PathBuf>("mock-data") {
+                Some(client::DataSource::Mock(client::MockSpotifyApi::from_file(
+                    path,
+                )?))
+            } else if let Some(folder) = args.get_one::<std::path::PathBuf>("replay") {
+                Some(client::DataSource::Replay(client::ReplaySpotifyApi::new(
+                    folder.clone(),
+                )))
+            } else {
+                args.get_one::<std::path::PathBuf>("record")
+                    .map(|folder| client::DataSource::Record(folder.clone()))
+            };
+
+            start_app(
+                &state,
+                start_context.map(|id| (id, args.get_flag("play"))),
+                args.get_flag("offline"),
+                data_source,
+            )
         }
         Some((cmd, args)) => cli::handle_cli_subcommand(cmd, args),
     }
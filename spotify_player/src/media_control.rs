@@ -1,21 +1,36 @@
 #![allow(unused_imports)]
-use souvlaki::MediaPosition;
 use souvlaki::{MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback, PlatformConfig};
+use souvlaki::{MediaPosition, SeekDirection};
 
-use crate::utils;
 use crate::{
     client::{ClientRequest, PlayerRequest},
     state::SharedState,
     utils::map_join,
 };
+use crate::{config, utils};
 
 fn update_control_metadata(
     state: &SharedState,
     controls: &mut MediaControls,
     prev_track_info: &mut String,
+    prev_volume: &mut Option<u32>,
 ) -> Result<(), souvlaki::Error> {
     let player = state.player.read();
 
+    // report the device's volume, which is only supported by the MPRIS interface
+    let volume = player
+        .buffered_playback
+        .as_ref()
+        .and_then(|p| p.volume)
+        .or_else(|| player.playback.as_ref()?.device.volume_percent);
+    if volume != *prev_volume {
+        #[cfg(all(unix, not(target_os = "macos")))]
+        if let Some(volume) = volume {
+            controls.set_volume(f64::from(volume) / 100.0)?;
+        }
+        *prev_volume = volume;
+    }
+
     match player.current_playing_track() {
         None => {}
         Some(track) => {
@@ -50,6 +65,25 @@ fn update_control_metadata(
     Ok(())
 }
 
+/// Seek the current track forward or backward by a given duration
+fn seek_by(
+    state: &SharedState,
+    client_pub: &flume::Sender<ClientRequest>,
+    direction: SeekDirection,
+    dur: chrono::Duration,
+) {
+    let progress = state.player.read().playback_progress();
+    if let Some(progress) = progress {
+        let position = match direction {
+            SeekDirection::Forward => progress + dur,
+            SeekDirection::Backward => std::cmp::max(chrono::Duration::zero(), progress - dur),
+        };
+        client_pub
+            .send(ClientRequest::Player(PlayerRequest::SeekTrack(position)))
+            .unwrap_or_default();
+    }
+}
+
 /// Start the application's media control event watcher
 pub fn start_event_watcher(
     state: SharedState,
@@ -74,6 +108,7 @@ pub fn start_event_watcher(
     };
     let mut controls = MediaControls::new(config)?;
 
+    let event_state = state.clone();
     controls.attach(move |e| {
         tracing::info!("Got a media control event: {e:?}");
         match e {
@@ -82,7 +117,7 @@ pub fn start_event_watcher(
                     .send(ClientRequest::Player(PlayerRequest::Resume))
                     .unwrap_or_default();
             }
-            MediaControlEvent::Pause => {
+            MediaControlEvent::Pause | MediaControlEvent::Stop => {
                 client_pub
                     .send(ClientRequest::Player(PlayerRequest::Pause))
                     .unwrap_or_default();
@@ -99,6 +134,16 @@ pub fn start_event_watcher(
                         .unwrap_or_default();
                 }
             }
+            MediaControlEvent::Seek(direction) => {
+                let secs = config::get_config().app_config.seek_step_in_secs;
+                let step = chrono::Duration::seconds(i64::from(secs));
+                seek_by(&event_state, &client_pub, direction, step);
+            }
+            MediaControlEvent::SeekBy(direction, dur) => {
+                if let Ok(dur) = chrono::Duration::from_std(dur) {
+                    seek_by(&event_state, &client_pub, direction, dur);
+                }
+            }
             MediaControlEvent::Next => {
                 client_pub
                     .send(ClientRequest::Player(PlayerRequest::NextTrack))
@@ -126,8 +171,9 @@ pub fn start_event_watcher(
     // [1]: https://github.com/Sinono3/souvlaki/blob/b4d47bb2797ffdd625c17192df640510466762e1/src/platform/linux/mod.rs#L450
     let refresh_duration = std::time::Duration::from_millis(1000);
     let mut track_info = String::new();
+    let mut volume = None;
     loop {
-        update_control_metadata(&state, &mut controls, &mut track_info)?;
+        update_control_metadata(&state, &mut controls, &mut track_info, &mut volume)?;
         std::thread::sleep(refresh_duration);
 
         // this must be run repeatedly to ensure that
@@ -0,0 +1,137 @@
+use std::fmt::Write as _;
+
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+
+use crate::state::*;
+
+/// Start the metrics server, which exposes the application's diagnostics
+/// in Prometheus text format at the `/metrics` endpoint
+pub async fn start_server(state: SharedState, address: String) {
+    let router = Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(state);
+
+    tracing::info!("Starting the metrics server at {address}");
+    let listener = match tokio::net::TcpListener::bind(&address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            tracing::warn!("Failed to bind the metrics server to {address}: {err:#}");
+            return;
+        }
+    };
+    if let Err(err) = axum::serve(listener, router).await {
+        tracing::error!("Encountered an error when running the metrics server: {err:#}");
+    }
+}
+
+async fn metrics(State(state): State<SharedState>) -> impl IntoResponse {
+    let text = render_metrics(&state.diagnostics.lock());
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], text)
+}
+
+/// writes a metric's `HELP` and `TYPE` lines
+fn write_header(s: &mut String, name: &str, typ: &str, help: &str) {
+    writeln!(s, "# HELP {name} {help}").unwrap();
+    writeln!(s, "# TYPE {name} {typ}").unwrap();
+}
+
+/// writes a metric with a single unlabeled value
+fn write_metric(s: &mut String, name: &str, typ: &str, help: &str, value: impl std::fmt::Display) {
+    write_header(s, name, typ, help);
+    writeln!(s, "{name} {value}").unwrap();
+}
+
+/// renders the application's diagnostics in Prometheus text format
+fn render_metrics(diagnostics: &Diagnostics) -> String {
+    let mut s = String::new();
+
+    write_metric(
+        &mut s,
+        "spotify_player_client_requests_total",
+        "counter",
+        "The number of handled client requests",
+        diagnostics.n_requests,
+    );
+
+    write_metric(
+        &mut s,
+        "spotify_player_client_request_errors_total",
+        "counter",
+        "The number of failed client requests",
+        diagnostics.n_failed_requests,
+    );
+
+    let name = "spotify_player_client_request_duration_seconds";
+    write_header(
+        &mut s,
+        name,
+        "histogram",
+        "The latency of handled client requests",
+    );
+    let mut count = 0;
+    for (bound, n) in REQUEST_LATENCY_BUCKETS_IN_SECS
+        .iter()
+        .zip(diagnostics.request_latency_buckets)
+    {
+        count += n;
+        writeln!(s, "{name}_bucket{{le=\"{bound}\"}} {count}").unwrap();
+    }
+    writeln!(s, "{name}_bucket{{le=\"+Inf\"}} {}", diagnostics.n_requests).unwrap();
+    writeln!(
+        s,
+        "{name}_sum {}",
+        diagnostics.request_latency_sum.as_secs_f64()
+    )
+    .unwrap();
+    writeln!(s, "{name}_count {}", diagnostics.n_requests).unwrap();
+
+    write_metric(
+        &mut s,
+        "spotify_player_pending_client_requests",
+        "gauge",
+        "The number of client requests waiting to be handled",
+        diagnostics.pending_requests,
+    );
+
+    write_metric(
+        &mut s,
+        "spotify_player_rate_limited_responses_total",
+        "counter",
+        "The number of responses rejected by Spotify API because of rate limiting",
+        diagnostics.n_rate_limited_responses,
+    );
+
+    write_metric(
+        &mut s,
+        "spotify_player_events_total",
+        "counter",
+        "The number of handled terminal events",
+        diagnostics.n_events,
+    );
+
+    let name = "spotify_player_cache_lookups_total";
+    write_header(
+        &mut s,
+        name,
+        "counter",
+        "The number of lookups into in-memory caches",
+    );
+    for (cache, (hits, misses)) in &diagnostics.cache_lookups {
+        writeln!(s, "{name}{{cache=\"{cache}\",result=\"hit\"}} {hits}").unwrap();
+        writeln!(s, "{name}{{cache=\"{cache}\",result=\"miss\"}} {misses}").unwrap();
+    }
+
+    let name = "spotify_player_cache_hit_ratio";
+    write_header(
+        &mut s,
+        name,
+        "gauge",
+        "The ratio of lookups into in-memory caches that are hits",
+    );
+    for (cache, (hits, misses)) in &diagnostics.cache_lookups {
+        let ratio = *hits as f64 / (hits + misses).max(1) as f64;
+        writeln!(s, "{name}{{cache=\"{cache}\"}} {ratio}").unwrap();
+    }
+
+    s
+}
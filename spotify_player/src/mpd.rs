@@ -0,0 +1,704 @@
+use std::{
+    fmt::Write as _,
+    hash::{Hash, Hasher},
+};
+
+use anyhow::Result;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{tcp::OwnedReadHalf, TcpListener, TcpStream},
+};
+
+use crate::{
+    cli::{self, Command, GetRequest, Key, Request},
+    client::Client,
+    state::*,
+    utils::map_join,
+};
+
+/// the MPD protocol's version reported to clients
+const PROTOCOL_VERSION: &str = "0.21.0";
+/// the interval to check for state changes to notify the idling connections
+const IDLE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+/// the maximum length (in bytes) of a command's line, a connection sending a longer line is closed
+const MAX_LINE_LENGTH: usize = 4096;
+/// the maximum number of commands in a command list
+const MAX_COMMAND_LIST_LENGTH: usize = 256;
+
+// MPD's error codes
+const ACK_ERROR_ARG: u32 = 2;
+const ACK_ERROR_UNKNOWN: u32 = 5;
+const ACK_ERROR_SYSTEM: u32 = 52;
+
+/// the supported commands, which are reported by the `commands` command
+const COMMANDS: [&str; 25] = [
+    "close",
+    "command_list_begin",
+    "command_list_end",
+    "command_list_ok_begin",
+    "commands",
+    "currentsong",
+    "idle",
+    "next",
+    "noidle",
+    "notcommands",
+    "outputs",
+    "pause",
+    "ping",
+    "play",
+    "playid",
+    "playlistinfo",
+    "plchanges",
+    "previous",
+    "random",
+    "seekcur",
+    "setvol",
+    "status",
+    "stop",
+    "tagtypes",
+    "volume",
+];
+
+/// An error of an MPD command, which is sent as an `ACK` response
+struct MpdError {
+    code: u32,
+    msg: String,
+}
+
+impl MpdError {
+    fn new(code: u32, msg: impl Into<String>) -> Self {
+        Self {
+            code,
+            msg: msg.into(),
+        }
+    }
+
+    fn to_ack(&self, index: usize, command: &str) -> String {
+        format!("ACK [{}@{index}] {{{command}}} {}\n", self.code, self.msg)
+    }
+}
+
+/// A snapshot of the player's state, which is compared to determine
+/// the changed subsystems reported to idling connections
+#[derive(PartialEq)]
+struct Snapshot {
+    track: Option<String>,
+    is_playing: Option<bool>,
+    volume: Option<u32>,
+    options: Option<(bool, rspotify_model::RepeatState)>,
+}
+
+impl Snapshot {
+    fn new(state: &SharedState) -> Self {
+        let player = state.player.read();
+        let playback = player.buffered_playback.as_ref();
+        Self {
+            track: current_track_uri(&player),
+            is_playing: playback.map(|p| p.is_playing),
+            volume: playback.and_then(|p| p.volume),
+            options: playback.map(|p| (p.shuffle_state, p.repeat_state)),
+        }
+    }
+
+    /// gets the subsystems changed between two snapshots
+    fn changes(&self, other: &Self) -> Vec<&'static str> {
+        let mut changes = vec![];
+        if self.track != other.track {
+            changes.extend(["playlist", "player"]);
+        } else if self.is_playing != other.is_playing {
+            changes.push("player");
+        }
+        if self.volume != other.volume {
+            changes.push("mixer");
+        }
+        if self.options != other.options {
+            changes.push("options");
+        }
+        changes
+    }
+}
+
+/// Start the MPD server, which exposes a subset of the [MPD protocol](https://mpd.readthedocs.io/en/latest/protocol.html)
+/// so that MPD clients can control the application's playback.
+///
+/// The playlist reported to clients consists of the current track followed by the playback's queue.
+pub async fn start_server(client: Client, state: SharedState, address: String) {
+    tracing::info!("Starting the MPD server at {address}");
+    let listener = match TcpListener::bind(&address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            tracing::warn!("Failed to bind the MPD server to {address}: {err:#}");
+            return;
+        }
+    };
+
+    loop {
+        match listener.accept().await {
+            Err(err) => tracing::warn!("Failed to accept an MPD connection: {err:#}"),
+            Ok((stream, _)) => {
+                let client = client.clone();
+                let state = state.clone();
+                tokio::task::spawn(async move {
+                    if let Err(err) = handle_connection(client, state, stream).await {
+                        tracing::warn!("Failed to handle an MPD connection: {err:#}");
+                    }
+                });
+            }
+        }
+    }
+}
+
+async fn handle_connection(client: Client, state: SharedState, stream: TcpStream) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    writer
+        .write_all(format!("OK MPD {PROTOCOL_VERSION}\n").as_bytes())
+        .await?;
+
+    let mut reader = BufReader::new(reader);
+    // the commands of a command list and whether `list_OK` is sent after each command
+    let mut command_list: Option<(bool, Vec<String>)> = None;
+    while let Some(line) = read_line(&mut reader).await? {
+        let line = line.trim();
+        let response = match line {
+            "command_list_begin" | "command_list_ok_begin" => {
+                command_list = Some((line == "command_list_ok_begin", vec![]));
+                continue;
+            }
+            "command_list_end" => match command_list.take() {
+                Some((list_ok, commands)) => {
+                    run_command_list(&client, &state, &commands, list_ok).await
+                }
+                None => MpdError::new(ACK_ERROR_ARG, "not in command list mode")
+                    .to_ack(0, "command_list_end"),
+            },
+            _ if command_list.is_some() => match command_list {
+                Some((_, ref mut commands)) if commands.len() < MAX_COMMAND_LIST_LENGTH => {
+                    commands.push(line.to_string());
+                    continue;
+                }
+                // like MPD, the connection is closed if the command list is too long
+                _ => {
+                    let ack = MpdError::new(ACK_ERROR_ARG, "too many commands in command list")
+                        .to_ack(MAX_COMMAND_LIST_LENGTH, "command_list_end");
+                    writer.write_all(ack.as_bytes()).await?;
+                    return Ok(());
+                }
+            },
+            "close" => return Ok(()),
+            _ if line == "idle" || line.starts_with("idle ") => {
+                match idle(&state, &mut reader, line).await? {
+                    Some(response) => response,
+                    None => return Ok(()),
+                }
+            }
+            // `noidle` outside of an `idle` command is ignored
+            "noidle" => continue,
+            _ => run_command_list(&client, &state, &[line.to_string()], false).await,
+        };
+        writer.write_all(response.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Read a line sent by a client, returns `None` if the connection is closed
+async fn read_line(reader: &mut BufReader<OwnedReadHalf>) -> Result<Option<String>> {
+    let mut buf = vec![];
+    // the line's length is limited, so that a client can't make the application buffer
+    // an arbitrary amount of data
+    let n = (&mut *reader)
+        .take(MAX_LINE_LENGTH as u64)
+        .read_until(b'\n', &mut buf)
+        .await?;
+    if n == 0 {
+        return Ok(None);
+    }
+    if buf.last() != Some(&b'\n') && n == MAX_LINE_LENGTH {
+        anyhow::bail!("line exceeds {MAX_LINE_LENGTH} bytes");
+    }
+    Ok(Some(String::from_utf8(buf)?))
+}
+
+/// Run a list of commands and return the response, which is terminated by `OK`
+/// or by an `ACK` error of the first failed command
+async fn run_command_list(
+    client: &Client,
+    state: &SharedState,
+    commands: &[String],
+    list_ok: bool,
+) -> String {
+    let mut response = String::new();
+    for (index, line) in commands.iter().enumerate() {
+        let args = parse_args(line);
+        let name = args.first().map(String::as_str).unwrap_or_default();
+        match run_command(client, state, name, &args[args.len().min(1)..]).await {
+            Ok(output) => {
+                response.push_str(&output);
+                if list_ok {
+                    response.push_str("list_OK\n");
+                }
+            }
+            Err(err) => {
+                tracing::warn!("Failed to handle an MPD command {line:?}: {}", err.msg);
+                response.push_str(&err.to_ack(index, name));
+                return response;
+            }
+        }
+    }
+    response.push_str("OK\n");
+    response
+}
+
+/// Wait until one of the subsystems specified by an `idle` command is changed
+/// or the client cancels the command with `noidle`.
+///
+/// Returns `None` if the connection should be closed.
+async fn idle(
+    state: &SharedState,
+    reader: &mut BufReader<OwnedReadHalf>,
+    line: &str,
+) -> Result<Option<String>> {
+    let subsystems = parse_args(line).split_off(1);
+    let last = Snapshot::new(state);
+    loop {
+        tokio::select! {
+            line = read_line(reader) => {
+                // `noidle` is the only command allowed while idling
+                return Ok(match line? {
+                    Some(line) if line.trim() == "noidle" => Some("OK\n".to_string()),
+                    _ => None,
+                });
+            }
+            _ = tokio::time::sleep(IDLE_POLL_INTERVAL) => {
+                let changes = Snapshot::new(state)
+                    .changes(&last)
+                    .into_iter()
+                    .filter(|c| subsystems.is_empty() || subsystems.iter().any(|s| s == c))
+                    .collect::<Vec<_>>();
+                if !changes.is_empty() {
+                    let mut response = String::new();
+                    for change in changes {
+                        writeln!(response, "changed: {change}").unwrap();
+                    }
+                    response.push_str("OK\n");
+                    return Ok(Some(response));
+                }
+            }
+        }
+    }
+}
+
+/// splits a command's line into the command's name and arguments,
+/// which can be quoted with double quotes and escaped with backslashes
+fn parse_args(line: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut arg = String::new();
+        if c == '"' {
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => arg.extend(chars.next()),
+                    _ => arg.push(c),
+                }
+            }
+        } else {
+            arg.push(c);
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                arg.push(c);
+            }
+        }
+        args.push(arg);
+    }
+    args
+}
+
+fn parse_arg<T: std::str::FromStr>(arg: Option<&String>) -> Result<T, MpdError> {
+    let arg = arg.ok_or_else(|| MpdError::new(ACK_ERROR_ARG, "missing argument"))?;
+    arg.parse()
+        .map_err(|_| MpdError::new(ACK_ERROR_ARG, format!("invalid argument: {arg}")))
+}
+
+async fn run_command(
+    client: &Client,
+    state: &SharedState,
+    name: &str,
+    args: &[String],
+) -> Result<String, MpdError> {
+    let command = match name {
+        "ping" | "clearerror" | "notcommands" => return Ok(String::new()),
+        "commands" => return Ok(COMMANDS.map(|c| format!("command: {c}\n")).concat()),
+        "tagtypes" => {
+            // only the list of tag types is supported, the `tagtypes` sub-commands are no-op
+            return Ok(["Artist", "Album", "Title"]
+                .map(|t| format!("tagtype: {t}\n"))
+                .concat());
+        }
+        "outputs" => {
+            return Ok("outputid: 0\noutputname: spotify_player\noutputenabled: 1\n".into())
+        }
+        "status" => return Ok(status(state)),
+        "currentsong" => {
+            let player = state.player.read();
+            let mut s = String::new();
+            if let Some(track) = player.current_playing_track() {
+                write_track(&mut s, track, 0);
+            }
+            return Ok(s);
+        }
+        "playlistinfo" | "plchanges" => return playlist_info(client, state).await,
+        "play" | "playid" => match args.first() {
+            // the current track is the only track in the playlist that can be played
+            Some(arg) if parse_arg::<u32>(Some(arg))? != 0 => {
+                return Err(MpdError::new(
+                    ACK_ERROR_ARG,
+                    "only the current song can be played",
+                ))
+            }
+            _ => Command::Play,
+        },
+        "pause" => match args.first().map(String::as_str) {
+            None => Command::PlayPause,
+            Some("1") => Command::Pause,
+            Some("0") => Command::Play,
+            Some(arg) => {
+                return Err(MpdError::new(
+                    ACK_ERROR_ARG,
+                    format!("invalid argument: {arg}"),
+                ))
+            }
+        },
+        "stop" => Command::Pause,
+        "next" => Command::Next,
+        "previous" => Command::Previous,
+        "setvol" => {
+            let percent: i8 = parse_arg(args.first())?;
+            if !(0..=100).contains(&percent) {
+                return Err(MpdError::new(
+                    ACK_ERROR_ARG,
+                    "volume must be in range [0, 100]",
+                ));
+            }
+            Command::Volume {
+                percent,
+                is_offset: false,
+            }
+        }
+        "volume" => {
+            let percent: i8 = parse_arg(args.first())?;
+            if !(-100..=100).contains(&percent) {
+                return Err(MpdError::new(
+                    ACK_ERROR_ARG,
+                    "volume change must be in range [-100, 100]",
+                ));
+            }
+            Command::Volume {
+                percent,
+                is_offset: true,
+            }
+        }
+        "seekcur" => {
+            let arg = args
+                .first()
+                .ok_or_else(|| MpdError::new(ACK_ERROR_ARG, "missing argument"))?;
+            let offset_ms = (parse_arg::<f64>(Some(arg))? * 1000.0) as i64;
+            // an argument prefixed by `+` or `-` is relative to the current position
+            if arg.starts_with(['+', '-']) {
+                Command::Seek(offset_ms)
+            } else {
+                let progress = state.player.read().playback_progress().unwrap_or_default();
+                Command::Seek(offset_ms - progress.num_milliseconds())
+            }
+        }
+        "random" => {
+            let random = parse_arg::<u8>(args.first())? != 0;
+            let shuffle = state
+                .player
+                .read()
+                .buffered_playback
+                .as_ref()
+                .map(|p| p.shuffle_state);
+            if shuffle == Some(random) {
+                return Ok(String::new());
+            }
+            Command::Shuffle
+        }
+        _ => {
+            return Err(MpdError::new(
+                ACK_ERROR_UNKNOWN,
+                format!("unknown command \"{name}\""),
+            ))
+        }
+    };
+
+    tracing::info!("Handling an MPD command: {command:?}");
+    let result =
+        cli::handle_socket_request(client, &Some(state.clone()), Request::Playback(command)).await;
+    state.mark_changed();
+    match result {
+        Ok(_) => Ok(String::new()),
+        Err(err) => Err(MpdError::new(ACK_ERROR_SYSTEM, format!("{err:#}"))),
+    }
+}
+
+fn current_track_uri(player: &PlayerState) -> Option<String> {
+    player
+        .current_playing_track()
+        .and_then(|track| track.id.as_ref().map(|id| id.uri()))
+}
+
+/// gets the playlist's version, which changes whenever the current track is changed
+fn playlist_version(player: &PlayerState) -> u32 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    current_track_uri(player).hash(&mut hasher);
+    hasher.finish() as u32
+}
+
+fn status(state: &SharedState) -> String {
+    let player = state.player.read();
+    let mut s = String::new();
+
+    let playback = player.buffered_playback.as_ref();
+    if let Some(volume) = playback.and_then(|p| p.volume) {
+        writeln!(s, "volume: {volume}").unwrap();
+    }
+    let repeat = playback.is_some_and(|p| p.repeat_state != rspotify_model::RepeatState::Off);
+    let single = playback.is_some_and(|p| p.repeat_state == rspotify_model::RepeatState::Track);
+    let random = playback.is_some_and(|p| p.shuffle_state);
+    writeln!(s, "repeat: {}", u8::from(repeat)).unwrap();
+    writeln!(s, "random: {}", u8::from(random)).unwrap();
+    writeln!(s, "single: {}", u8::from(single)).unwrap();
+    writeln!(s, "consume: 0").unwrap();
+    writeln!(s, "playlist: {}", playlist_version(&player)).unwrap();
+    let playlist_length = player
+        .queue
+        .as_ref()
+        .map(|q| q.queue.len())
+        .unwrap_or_default()
+        + usize::from(player.current_playing_track().is_some());
+    writeln!(s, "playlistlength: {playlist_length}").unwrap();
+    let playback_state = match playback {
+        Some(playback) if playback.is_playing => "play",
+        Some(_) => "pause",
+        None => "stop",
+    };
+    writeln!(s, "state: {playback_state}").unwrap();
+
+    if let Some(track) = player.current_playing_track() {
+        let elapsed = player.playback_progress().unwrap_or_default();
+        let elapsed = elapsed.num_milliseconds() as f64 / 1000.0;
+        let duration = track.duration.num_milliseconds() as f64 / 1000.0;
+        writeln!(s, "song: 0\nsongid: 0").unwrap();
+        writeln!(s, "time: {}:{}", elapsed as u64, duration as u64).unwrap();
+        writeln!(s, "elapsed: {elapsed:.3}\nduration: {duration:.3}").unwrap();
+    }
+    s
+}
+
+/// writes the tags of a track at position `pos` in the playlist
+fn write_track(s: &mut String, track: &rspotify_model::FullTrack, pos: usize) {
+    let uri = track.id.as_ref().map(|id| id.uri()).unwrap_or_default();
+    write_song(
+        s,
+        &uri,
+        &track.name,
+        &map_join(&track.artists, |a| &a.name, ", "),
+        &track.album.name,
+        track.duration,
+        pos,
+    );
+}
+
+fn write_song(
+    s: &mut String,
+    uri: &str,
+    title: &str,
+    artist: &str,
+    album: &str,
+    duration: chrono::Duration,
+    pos: usize,
+) {
+    let duration = duration.num_milliseconds() as f64 / 1000.0;
+    writeln!(s, "file: {uri}").unwrap();
+    writeln!(s, "Title: {title}").unwrap();
+    writeln!(s, "Artist: {artist}").unwrap();
+    writeln!(s, "Album: {album}").unwrap();
+    writeln!(s, "Time: {}", duration as u64).unwrap();
+    writeln!(s, "duration: {duration:.3}").unwrap();
+    writeln!(s, "Pos: {pos}\nId: {pos}").unwrap();
+}
+
+/// gets the URI of a track or an episode
+fn playable_uri(item: &rspotify_model::PlayableItem) -> Option<String> {
+    match item {
+        rspotify_model::PlayableItem::Track(track) => track.id.as_ref().map(|id| id.uri()),
+        rspotify_model::PlayableItem::Episode(episode) => Some(episode.id.uri()),
+    }
+}
+
+/// gets the playlist consisting of the current track followed by the playback's queue.
+///
+/// The queue is served from the application's state while it's up-to-date with the current track,
+/// so that clients polling the playlist don't send a queue request on every command.
+async fn playlist_info(client: &Client, state: &SharedState) -> Result<String, MpdError> {
+    let cached_queue = {
+        let player = state.player.read();
+        let current_track = current_track_uri(&player);
+        player.queue.clone().filter(|queue| {
+            queue.currently_playing.as_ref().and_then(playable_uri) == current_track
+        })
+    };
+    let queue = match cached_queue {
+        Some(queue) => queue,
+        None => {
+            let request = Request::Get(GetRequest::Key(Key::Queue));
+            let queue = cli::handle_socket_request(client, &Some(state.clone()), request)
+                .await
+                .and_then(|data| {
+                    Ok(serde_json::from_slice::<rspotify_model::CurrentUserQueue>(
+                        &data,
+                    )?)
+                })
+                .map_err(|err| MpdError::new(ACK_ERROR_SYSTEM, format!("{err:#}")))?;
+            state.player.write().queue = Some(queue.clone());
+            queue
+        }
+    };
+
+    let mut s = String::new();
+    for (pos, item) in queue
+        .currently_playing
+        .iter()
+        .chain(queue.queue.iter())
+        .enumerate()
+    {
+        match item {
+            rspotify_model::PlayableItem::Track(track) => write_track(&mut s, track, pos),
+            rspotify_model::PlayableItem::Episode(episode) => write_song(
+                &mut s,
+                &episode.id.uri(),
+                &episode.name,
+                &episode.show.publisher,
+                &episode.show.name,
+                episode.duration,
+                pos,
+            ),
+        }
+    }
+    Ok(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{auth::AuthConfig, config};
+
+    /// starts a connection handler, returns the connected stream and the handler's task
+    async fn connect() -> (BufReader<TcpStream>, tokio::task::JoinHandle<Result<()>>) {
+        static INIT_CONFIG: std::sync::Once = std::sync::Once::new();
+        INIT_CONFIG.call_once(|| {
+            let folder = std::env::temp_dir().join("spotify-player-mpd-tests");
+            std::fs::create_dir_all(&folder).unwrap();
+            config::set_config(config::Configs::new(&folder, &folder, None).unwrap());
+        });
+        let client = Client::new(None, AuthConfig::default(), String::new());
+        let state = std::sync::Arc::new(State::new(false));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut stream = BufReader::new(
+            TcpStream::connect(listener.local_addr().unwrap())
+                .await
+                .unwrap(),
+        );
+        let (conn, _) = listener.accept().await.unwrap();
+        let handler = tokio::task::spawn(handle_connection(client, state, conn));
+
+        assert_eq!(
+            read_response(&mut stream).await,
+            format!("OK MPD {PROTOCOL_VERSION}\n")
+        );
+        (stream, handler)
+    }
+
+    /// reads a response, which is terminated by an `OK` or `ACK` line
+    async fn read_response(stream: &mut BufReader<TcpStream>) -> String {
+        let mut response = String::new();
+        loop {
+            let n = stream.read_line(&mut response).await.unwrap();
+            let last = response.lines().last().unwrap_or_default();
+            if n == 0 || last.starts_with("OK") || last.starts_with("ACK") {
+                return response;
+            }
+        }
+    }
+
+    async fn send(stream: &mut BufReader<TcpStream>, lines: &[&str]) -> String {
+        let request = lines.iter().map(|l| format!("{l}\n")).collect::<String>();
+        stream
+            .get_mut()
+            .write_all(request.as_bytes())
+            .await
+            .unwrap();
+        read_response(stream).await
+    }
+
+    #[test]
+    fn quoted_arguments_are_unescaped() {
+        assert_eq!(
+            parse_args(r#"find  "say \"hi\"" plain "back\\slash" """#),
+            ["find", r#"say "hi""#, "plain", r"back\slash", ""]
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_commands_are_acked() {
+        let (mut stream, _) = connect().await;
+        assert_eq!(
+            send(&mut stream, &["foo bar"]).await,
+            "ACK [5@0] {foo} unknown command \"foo\"\n"
+        );
+        assert_eq!(send(&mut stream, &["ping"]).await, "OK\n");
+    }
+
+    #[tokio::test]
+    async fn command_lists_are_run_until_the_first_error() {
+        let (mut stream, _) = connect().await;
+        assert_eq!(
+            send(
+                &mut stream,
+                &["command_list_ok_begin", "ping", "ping", "command_list_end"]
+            )
+            .await,
+            "list_OK\nlist_OK\nOK\n"
+        );
+        assert_eq!(
+            send(
+                &mut stream,
+                &[
+                    "command_list_begin",
+                    "ping",
+                    "foo",
+                    "ping",
+                    "command_list_end"
+                ]
+            )
+            .await,
+            "ACK [5@1] {foo} unknown command \"foo\"\n"
+        );
+        assert_eq!(
+            send(&mut stream, &["command_list_end"]).await,
+            "ACK [2@0] {command_list_end} not in command list mode\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn long_lines_close_the_connection() {
+        let (mut stream, handler) = connect().await;
+        let line = "a".repeat(MAX_LINE_LENGTH);
+        assert_eq!(send(&mut stream, &[&line]).await, "");
+        assert!(handler.await.unwrap().is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use rumqttc::{AsyncClient, Event, EventLoop, LastWill, MqttOptions, Packet, QoS};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    client::{ClientRequest, PlayerRequest},
+    config::MqttConfig,
+    state::*,
+    utils::map_join,
+};
+
+/// the interval to check the playback's state for changes to publish
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// the playback commands accepted on the `<topic>/command` topic
+const COMMANDS: [(&str, &str); 5] = [
+    ("play_pause", "Play/Pause"),
+    ("play", "Play"),
+    ("pause", "Pause"),
+    ("next", "Next"),
+    ("previous", "Previous"),
+];
+
+#[derive(Serialize, PartialEq)]
+/// The metadata of a track published to the `<topic>/track` topic
+struct MqttTrack {
+    uri: String,
+    name: String,
+    artists: String,
+    album: String,
+    duration_ms: i64,
+}
+
+/// gets the current playback's state (`playing`, `paused` or `stopped`) and the current track
+fn current_state(state: &SharedState) -> (&'static str, Option<MqttTrack>) {
+    let player = state.player.read();
+    let playback_state = match player.buffered_playback {
+        Some(ref playback) if playback.is_playing => "playing",
+        Some(_) => "paused",
+        None => "stopped",
+    };
+    let track = player.current_playing_track().map(|track| MqttTrack {
+        uri: track.id.as_ref().map(|id| id.uri()).unwrap_or_default(),
+        name: track.name.clone(),
+        artists: map_join(&track.artists, |a| &a.name, ", "),
+        album: track.album.name.clone(),
+        duration_ms: track.duration.num_milliseconds(),
+    });
+    (playback_state, track)
+}
+
+/// Start publishing the playback's state to an MQTT broker.
+///
+/// Upon changes, the playback's state is published to the `<topic>/state` topic and
+/// the current track's metadata (in JSON format) is published to the `<topic>/track` topic.
+/// Messages are retained, so that new subscribers receive the latest state.
+/// Playback commands received on the `<topic>/command` topic are forwarded to the client.
+pub async fn start_publisher(
+    state: SharedState,
+    client_pub: flume::Sender<ClientRequest>,
+    configs: MqttConfig,
+) {
+    let mut options = MqttOptions::new(&configs.client_id, &configs.host, configs.port);
+    options.set_keep_alive(std::time::Duration::from_secs(30));
+    options.set_last_will(LastWill::new(
+        format!("{}/availability", configs.topic),
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if let Some(ref username) = configs.username {
+        options.set_credentials(
+            username,
+            configs
+                .password
+                .as_ref()
+                .map(|p| p.expose())
+                .unwrap_or_default(),
+        );
+    }
+
+    tracing::info!(
+        "Publishing the playback's state to the MQTT broker at {}:{}",
+        configs.host,
+        configs.port
+    );
+    let (client, eventloop) = AsyncClient::new(options, 16);
+    tokio::task::spawn(handle_events(
+        eventloop,
+        client.clone(),
+        client_pub,
+        configs.clone(),
+    ));
+
+    let mut last_state = None;
+    let mut last_track = None;
+    loop {
+        let (playback_state, track) = current_state(&state);
+
+        if last_state != Some(playback_state) {
+            publish(&client, &configs.topic, "state", playback_state.to_string()).await;
+            last_state = Some(playback_state);
+        }
+        if last_track.as_ref() != Some(&track) {
+            match serde_json::to_string(&track) {
+                Ok(payload) => publish(&client, &configs.topic, "track", payload).await,
+                Err(err) => tracing::warn!("Failed to serialize the current track: {err:#}"),
+            }
+            last_track = Some(track);
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Poll the MQTT connection's events, which is required to make progress on the connection.
+///
+/// Upon (re)connecting to the broker, the application subscribes to the command topic,
+/// marks itself as available and publishes the Home Assistant discovery messages (if enabled).
+async fn handle_events(
+    mut eventloop: EventLoop,
+    client: AsyncClient,
+    client_pub: flume::Sender<ClientRequest>,
+    configs: MqttConfig,
+) {
+    let command_topic = format!("{}/command", configs.topic);
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                tracing::info!("Connected to the MQTT broker");
+                // the client's requests are handled by the event loop, so they are sent
+                // in a separate task to avoid blocking the event loop
+                tokio::task::spawn({
+                    let client = client.clone();
+                    let configs = configs.clone();
+                    let command_topic = command_topic.clone();
+                    async move {
+                        if let Err(err) = client.subscribe(&command_topic, QoS::AtLeastOnce).await {
+                            tracing::warn!("Failed to subscribe to {command_topic}: {err:#}");
+                        }
+                        publish(&client, &configs.topic, "availability", "online".into()).await;
+                        if configs.home_assistant_discovery {
+                            publish_discovery(&client, &configs).await;
+                        }
+                    }
+                });
+            }
+            Ok(Event::Incoming(Packet::Publish(message))) if message.topic == command_topic => {
+                let command = String::from_utf8_lossy(&message.payload);
+                tracing::info!("Got an MQTT command: {command}");
+                let request = match command.trim() {
+                    "play_pause" => PlayerRequest::ResumePause,
+                    "play" => PlayerRequest::Resume,
+                    "pause" => PlayerRequest::Pause,
+                    "next" => PlayerRequest::NextTrack,
+                    "previous" => PlayerRequest::PreviousTrack,
+                    _ => {
+                        tracing::warn!("Unknown MQTT command: {command}");
+                        continue;
+                    }
+                };
+                client_pub
+                    .send(ClientRequest::Player(request))
+                    .unwrap_or_default();
+            }
+            Ok(_) => {}
+            Err(err) => {
+                // the connection is re-established on the next poll after an error
+                tracing::warn!("MQTT connection error: {err:#}");
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
+
+/// Publish the Home Assistant MQTT discovery messages, which register the playback's sensors
+/// and the playback command buttons under a `spotify_player` device
+async fn publish_discovery(client: &AsyncClient, configs: &MqttConfig) {
+    let topic = &configs.topic;
+    let node_id = configs
+        .client_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    let device = json!({
+        "identifiers": [node_id],
+        "name": "spotify_player",
+        "manufacturer": "spotify_player",
+        "sw_version": env!("CARGO_PKG_VERSION"),
+    });
+    let availability_topic = format!("{topic}/availability");
+
+    let sensors = [
+        (
+            "state",
+            json!({
+                "name": "Playback",
+                "icon": "mdi:spotify",
+                "state_topic": format!("{topic}/state"),
+                "json_attributes_topic": format!("{topic}/track"),
+                "json_attributes_template": "{{ (value_json or {}) | tojson }}",
+            }),
+        ),
+        (
+            "title",
+            json!({
+                "name": "Media title",
+                "icon": "mdi:music",
+                "state_topic": format!("{topic}/track"),
+                "value_template": "{{ value_json.name if value_json else '' }}",
+            }),
+        ),
+        (
+            "artist",
+            json!({
+                "name": "Media artist",
+                "icon": "mdi:account-music",
+                "state_topic": format!("{topic}/track"),
+                "value_template": "{{ value_json.artists if value_json else '' }}",
+            }),
+        ),
+    ];
+    let buttons = COMMANDS.map(|(command, name)| {
+        (
+            command,
+            json!({
+                "name": name,
+                "command_topic": format!("{topic}/command"),
+                "payload_press": command,
+            }),
+        )
+    });
+
+    let entities = sensors
+        .into_iter()
+        .map(|(id, config)| ("sensor", id, config))
+        .chain(
+            buttons
+                .into_iter()
+                .map(|(id, config)| ("button", id, config)),
+        );
+    for (component, id, mut config) in entities {
+        config["unique_id"] = format!("{node_id}_{id}").into();
+        config["availability_topic"] = availability_topic.clone().into();
+        config["device"] = device.clone();
+
+        let discovery_topic = format!(
+            "{}/{component}/{node_id}/{id}/config",
+            configs.home_assistant_prefix
+        );
+        if let Err(err) = client
+            .publish(&discovery_topic, QoS::AtLeastOnce, true, config.to_string())
+            .await
+        {
+            tracing::warn!("Failed to publish an MQTT message to {discovery_topic}: {err:#}");
+        }
+    }
+}
+
+async fn publish(client: &AsyncClient, topic: &str, subtopic: &str, payload: String) {
+    if let Err(err) = client
+        .publish(
+            format!("{topic}/{subtopic}"),
+            QoS::AtLeastOnce,
+            true,
+            payload,
+        )
+        .await
+    {
+        tracing::warn!("Failed to publish an MQTT message to {topic}/{subtopic}: {err:#}");
+    }
+}
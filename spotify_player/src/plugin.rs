@@ -0,0 +1,209 @@
+use std::{collections::HashMap, time::SystemTime};
+
+use anyhow::{Context as _, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use wasmtime::{Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::{
+    client::{ClientRequest, PlayerRequest},
+    command::Command,
+    config,
+    state::*,
+    utils::map_join,
+};
+
+/// the maximum amount of fuel (roughly the number of WASM instructions) consumed by a plugin command,
+/// which prevents a misbehaving plugin from blocking the application
+const MAX_PLUGIN_FUEL: u64 = 100_000_000;
+/// the maximum size (in bytes) of a plugin's linear memory
+const MAX_PLUGIN_MEMORY_SIZE: usize = 64 * 1024 * 1024;
+
+static ENGINE: Lazy<Engine> = Lazy::new(|| {
+    let mut config = wasmtime::Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).expect("WASM engine's configurations should be valid")
+});
+/// the compiled plugin modules keyed by the plugins' names together with the files' modified times,
+/// a plugin is recompiled once its file is modified
+static MODULES: Lazy<parking_lot::Mutex<HashMap<String, (SystemTime, Module)>>> =
+    Lazy::new(Default::default);
+
+#[derive(Serialize)]
+/// The input message passed to a plugin
+struct PluginInput {
+    abi_version: u32,
+    command: String,
+    playback: Option<PluginPlayback>,
+    track: Option<PluginTrack>,
+}
+
+#[derive(Serialize)]
+struct PluginPlayback {
+    is_playing: bool,
+    progress_ms: i64,
+    volume: Option<u32>,
+    shuffle: bool,
+    device: String,
+}
+
+#[derive(Serialize)]
+struct PluginTrack {
+    uri: String,
+    name: String,
+    artists: String,
+    album: String,
+    duration_ms: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+/// An action in the output message of a plugin
+enum PluginAction {
+    Command { name: Command },
+    Volume { percent: u8 },
+    Seek { position_ms: i64 },
+    Play { uri: String },
+    AddToQueue { uri: String },
+    Log { message: String },
+}
+
+/// An action requested by a plugin, which is dispatched by the application's event handler
+pub enum PluginRequest {
+    Command(Command),
+    Request(Box<ClientRequest>),
+}
+
+/// Get the compiled module of a plugin, compiling the plugin's file if not cached
+fn get_module(name: &str) -> Result<Module> {
+    let path = config::get_config()
+        .config_folder
+        .join("plugins")
+        .join(format!("{name}.wasm"));
+    let modified = std::fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .with_context(|| format!("failed to load the plugin file {}", path.display()))?;
+
+    let mut modules = MODULES.lock();
+    if let Some((_, module)) = modules.get(name).filter(|(time, _)| *time == modified) {
+        return Ok(module.clone());
+    }
+
+    tracing::info!("Loading plugin {name} from {}", path.display());
+    let module = Module::from_file(&ENGINE, &path)
+        .with_context(|| format!("failed to load the plugin file {}", path.display()))?;
+    modules.insert(name.to_string(), (modified, module.clone()));
+    Ok(module)
+}
+
+/// Remove the compiled plugin modules, so that plugins are reloaded on their next run
+pub fn clear_modules() {
+    MODULES.lock().clear();
+}
+
+fn plugin_input(command: &str, state: &SharedState) -> PluginInput {
+    let player = state.player.read();
+    let playback = player
+        .buffered_playback
+        .as_ref()
+        .map(|playback| PluginPlayback {
+            is_playing: playback.is_playing,
+            progress_ms: player
+                .playback_progress()
+                .map(|p| p.num_milliseconds())
+                .unwrap_or_default(),
+            volume: playback.volume,
+            shuffle: playback.shuffle_state,
+            device: playback.device_name.clone(),
+        });
+    let track = player.current_playing_track().map(|track| PluginTrack {
+        uri: track.id.as_ref().map(|id| id.uri()).unwrap_or_default(),
+        name: track.name.clone(),
+        artists: map_join(&track.artists, |a| &a.name, ", "),
+        album: track.album.name.clone(),
+        duration_ms: track.duration.num_milliseconds(),
+    });
+
+    PluginInput {
+        abi_version: 1,
+        command: command.to_string(),
+        playback,
+        track,
+    }
+}
+
+/// Run a plugin's command and return the actions requested by the plugin.
+///
+/// The plugin's command and the application's state are passed to the plugin as a JSON message
+/// written into the plugin's memory, and the plugin returns a JSON list of actions
+/// (see the plugin ABI documented in `docs/config.md`).
+pub fn run_plugin_command(
+    plugin: &str,
+    command: &str,
+    state: &SharedState,
+) -> Result<Vec<PluginRequest>> {
+    let module = get_module(plugin)?;
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_PLUGIN_MEMORY_SIZE)
+        .instances(1)
+        .build();
+    let mut store = Store::new(&ENGINE, limits);
+    store.limiter(|limits: &mut StoreLimits| limits);
+    store.set_fuel(MAX_PLUGIN_FUEL)?;
+    // plugins are sandboxed without any imported functions
+    let instance = Linker::new(&ENGINE).instantiate(&mut store, &module)?;
+
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .context("plugin doesn't export its memory")?;
+    let alloc = instance.get_typed_func::<i32, i32>(&mut store, "spotify_player_alloc")?;
+    let run = instance.get_typed_func::<(i32, i32), i64>(&mut store, "spotify_player_run")?;
+
+    let input = serde_json::to_vec(&plugin_input(command, state))?;
+    let len = i32::try_from(input.len())?;
+    let ptr = alloc.call(&mut store, len)?;
+    memory.write(&mut store, ptr as u32 as usize, &input)?;
+
+    let output = run.call(&mut store, (ptr, len))?;
+    let (ptr, len) = ((output >> 32) as u32 as usize, output as u32 as usize);
+    // the output is read in place, so that an invalid length returned by the plugin
+    // can't make the application allocate an arbitrary amount of memory
+    let buf = ptr
+        .checked_add(len)
+        .and_then(|end| memory.data(&store).get(ptr..end))
+        .with_context(|| format!("plugin {plugin} returned an output outside of its memory"))?;
+
+    let actions: Vec<PluginAction> = serde_json::from_slice(buf)
+        .with_context(|| format!("invalid output of plugin {plugin}"))?;
+    actions
+        .into_iter()
+        .filter_map(|action| to_plugin_request(plugin, action).transpose())
+        .collect()
+}
+
+fn to_plugin_request(plugin: &str, action: PluginAction) -> Result<Option<PluginRequest>> {
+    let request = match action {
+        PluginAction::Command { name } => return Ok(Some(PluginRequest::Command(name))),
+        PluginAction::Log { message } => {
+            tracing::info!("[plugin {plugin}] {message}");
+            return Ok(None);
+        }
+        PluginAction::Volume { percent } => {
+            ClientRequest::Player(PlayerRequest::Volume(percent.min(100)))
+        }
+        PluginAction::Seek { position_ms } => ClientRequest::Player(PlayerRequest::SeekTrack(
+            chrono::Duration::milliseconds(position_ms.max(0)),
+        )),
+        PluginAction::Play { uri } => {
+            let playback = match ContextId::from_uri(&uri) {
+                Ok(id) => Playback::Context(id, None),
+                Err(_) => Playback::URIs(vec![TrackId::from_uri(&uri)?.into_static()], None),
+            };
+            ClientRequest::Player(PlayerRequest::StartPlayback(playback, None))
+        }
+        PluginAction::AddToQueue { uri } => {
+            ClientRequest::AddTrackToQueue(TrackId::from_uri(&uri)?.into_static())
+        }
+    };
+    Ok(Some(PluginRequest::Request(Box::new(request))))
+}
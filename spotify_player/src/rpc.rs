@@ -0,0 +1,362 @@
+use std::{
+    os::unix::fs::{DirBuilderExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{unix::OwnedReadHalf, UnixListener, UnixStream},
+    sync::mpsc,
+};
+
+use crate::{
+    cli::{self, Command, GetRequest, IdOrName, Key, Request},
+    client::Client,
+    state::SharedState,
+};
+
+/// the maximum duration to wait for a state change before checking
+/// whether a subscribed connection is closed
+const SUBSCRIPTION_WAIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+/// the maximum length of a message in bytes
+const MAX_LINE_LENGTH: usize = 64 * 1024;
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct VolumeParams {
+    percent: i8,
+    #[serde(default)]
+    offset: bool,
+}
+
+#[derive(Deserialize)]
+struct SeekParams {
+    offset_ms: i64,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    query: String,
+}
+
+#[derive(Deserialize)]
+struct QueueParams {
+    id: Option<String>,
+    name: Option<String>,
+}
+
+/// A method call of a JSON-RPC request
+enum Method {
+    Request(Request),
+    Subscribe,
+}
+
+/// gets the path of the JSON-RPC socket, which is placed inside the `spotify-player` folder
+/// of `$XDG_RUNTIME_DIR` (or of the application's cache folder if the variable is unset)
+fn socket_path() -> PathBuf {
+    let configs = crate::config::get_config();
+    let name = match configs.profile {
+        Some(ref profile) => format!("spotify-player-{profile}.sock"),
+        None => "spotify-player.sock".to_string(),
+    };
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| configs.cache_folder.clone())
+        .join("spotify-player")
+        .join(name)
+}
+
+/// creates the socket's folder, which is only accessible by the current user
+fn create_socket_folder(folder: &Path) -> std::io::Result<()> {
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(folder)?;
+    // the folder may exist with broader permissions
+    std::fs::set_permissions(folder, std::fs::Permissions::from_mode(0o700))
+}
+
+/// Start the JSON-RPC server, which exposes the CLI's control surface over a Unix socket.
+///
+/// Each message is a JSON-RPC 2.0 request (or response/notification) terminated by a newline.
+pub async fn start_server(client: Client, state: SharedState) {
+    let path = socket_path();
+    // the socket is created inside a private folder, so that no other user can connect to it
+    // before its permissions are restricted
+    if let Some(folder) = path.parent() {
+        if let Err(err) = create_socket_folder(folder) {
+            tracing::warn!("Failed to create the JSON-RPC socket's folder: {err:#}");
+            return;
+        }
+    }
+    if path.exists() {
+        // don't take over the socket of another running instance
+        if UnixStream::connect(&path).await.is_ok() {
+            tracing::warn!(
+                "JSON-RPC socket {} is used by another application's instance",
+                path.display()
+            );
+            return;
+        }
+        std::fs::remove_file(&path).unwrap_or_default();
+    }
+
+    tracing::info!("Starting a JSON-RPC server at {}", path.display());
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(err) => {
+            tracing::warn!("Failed to create the JSON-RPC socket: {err:#}");
+            return;
+        }
+    };
+    // only the current user can connect to the socket
+    if let Err(err) = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)) {
+        tracing::warn!("Failed to restrict the JSON-RPC socket's permissions: {err:#}");
+        return;
+    }
+
+    loop {
+        match listener.accept().await {
+            Err(err) => tracing::warn!("Failed to accept a JSON-RPC connection: {err:#}"),
+            Ok((stream, _)) => {
+                let client = client.clone();
+                let state = state.clone();
+                tokio::task::spawn(async move {
+                    if let Err(err) = handle_connection(client, state, stream).await {
+                        tracing::warn!("Failed to handle a JSON-RPC connection: {err:#}");
+                    }
+                });
+            }
+        }
+    }
+}
+
+async fn handle_connection(client: Client, state: SharedState, stream: UnixStream) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+
+    // messages are sent via a channel, so that responses and subscription's notifications
+    // can be written to the connection concurrently
+    let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+    let writer_task = tokio::task::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            let mut buf = serde_json::to_vec(&message)?;
+            buf.push(b'\n');
+            writer.write_all(&buf).await?;
+        }
+        anyhow::Ok(())
+    });
+
+    let mut reader = BufReader::new(reader);
+    let mut subscription = None;
+    while let Some(line) = read_line(&mut reader).await? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let request: RpcRequest = match serde_json::from_str::<Value>(line) {
+            Err(err) => {
+                tx.send(error_response(Value::Null, PARSE_ERROR, err))?;
+                continue;
+            }
+            Ok(value) => match serde_json::from_value(value) {
+                Ok(request) => request,
+                Err(err) => {
+                    tx.send(error_response(Value::Null, INVALID_REQUEST, err))?;
+                    continue;
+                }
+            },
+        };
+
+        let id = request.id.clone();
+        let result = match parse_method(&request.method, request.params) {
+            Err(err) => Err(err),
+            Ok(Method::Subscribe) => {
+                if subscription.is_none() {
+                    subscription = Some(tokio::task::spawn(notify_state_changes(
+                        state.clone(),
+                        tx.clone(),
+                    )));
+                }
+                Ok(Value::Bool(true))
+            }
+            Ok(Method::Request(request)) => handle_request(&client, &state, request)
+                .await
+                .map_err(|err| (SERVER_ERROR, format!("{err:#}"))),
+        };
+
+        // requests without an `id` are notifications, which don't have responses
+        let Some(id) = id else { continue };
+        tx.send(match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err((code, msg)) => error_response(id, code, msg),
+        })?;
+    }
+
+    if let Some(subscription) = subscription {
+        subscription.abort();
+    }
+    drop(tx);
+    writer_task.await?
+}
+
+/// Read a message sent by a client, returns `None` if the connection is closed
+async fn read_line(reader: &mut BufReader<OwnedReadHalf>) -> Result<Option<String>> {
+    let mut buf = vec![];
+    // the message's length is limited, so that a client can't make the application buffer
+    // an arbitrary amount of data
+    let n = (&mut *reader)
+        .take(MAX_LINE_LENGTH as u64)
+        .read_until(b'\n', &mut buf)
+        .await?;
+    if n == 0 {
+        return Ok(None);
+    }
+    if buf.last() != Some(&b'\n') && n == MAX_LINE_LENGTH {
+        anyhow::bail!("message exceeds {MAX_LINE_LENGTH} bytes");
+    }
+    Ok(Some(String::from_utf8(buf)?))
+}
+
+fn error_response(id: Value, code: i64, msg: impl std::fmt::Display) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": msg.to_string()},
+    })
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|err| (INVALID_PARAMS, err.to_string()))
+}
+
+fn parse_method(method: &str, params: Value) -> Result<Method, (i64, String)> {
+    let request = match method {
+        "subscribe" => return Ok(Method::Subscribe),
+        "status" => Request::Get(GetRequest::Key(Key::Playback)),
+        "queue" => Request::Get(GetRequest::Key(Key::Queue)),
+        "devices" => Request::Get(GetRequest::Key(Key::Devices)),
+        "play_pause" => Request::Playback(Command::PlayPause),
+        "play" => Request::Playback(Command::Play),
+        "pause" => Request::Playback(Command::Pause),
+        "next" => Request::Playback(Command::Next),
+        "previous" => Request::Playback(Command::Previous),
+        "shuffle" => Request::Playback(Command::Shuffle),
+        "repeat" => Request::Playback(Command::Repeat),
+        "volume" => {
+            let params: VolumeParams = parse_params(params)?;
+            if !(-100..=100).contains(&params.percent) {
+                return Err((
+                    INVALID_PARAMS,
+                    "`percent` must be in range [-100, 100]".to_string(),
+                ));
+            }
+            Request::Playback(Command::Volume {
+                percent: params.percent,
+                is_offset: params.offset,
+            })
+        }
+        "seek" => {
+            let params: SeekParams = parse_params(params)?;
+            Request::Playback(Command::Seek(params.offset_ms))
+        }
+        "search" => {
+            let params: SearchParams = parse_params(params)?;
+            Request::Search {
+                query: params.query,
+            }
+        }
+        "add_to_queue" => {
+            let params: QueueParams = parse_params(params)?;
+            let id_or_name = match (params.id, params.name) {
+                (Some(id), _) => IdOrName::Id(id),
+                (None, Some(name)) => IdOrName::Name(name),
+                (None, None) => {
+                    return Err((
+                        INVALID_PARAMS,
+                        "either `id` or `name` must be specified".to_string(),
+                    ))
+                }
+            };
+            Request::Queue(id_or_name)
+        }
+        _ => return Err((METHOD_NOT_FOUND, format!("unknown method: {method}"))),
+    };
+    Ok(Method::Request(request))
+}
+
+/// Handle a JSON-RPC request with the client socket's request handler
+async fn handle_request(client: &Client, state: &SharedState, request: Request) -> Result<Value> {
+    tracing::info!("Handling a JSON-RPC request: {request:?}");
+    let data = cli::handle_socket_request(client, &Some(state.clone()), request).await;
+    state.mark_changed();
+
+    let data = data?;
+    if data.is_empty() {
+        Ok(Value::Null)
+    } else {
+        Ok(serde_json::from_slice(&data)?)
+    }
+}
+
+/// Send a `state_changed` notification with the current playback whenever
+/// the playback's state is changed
+async fn notify_state_changes(state: SharedState, tx: mpsc::UnboundedSender<Value>) {
+    let mut last_playback = None;
+    let mut generation = None;
+    loop {
+        // wait for the application's state to change before checking the playback
+        if let Some(generation) = generation {
+            tokio::task::spawn_blocking({
+                let state = state.clone();
+                move || state.wait_for_change(generation, SUBSCRIPTION_WAIT_TIMEOUT)
+            })
+            .await
+            .unwrap_or_default();
+            if tx.is_closed() {
+                return;
+            }
+            if state.generation() == generation {
+                continue;
+            }
+        }
+        generation = Some(state.generation());
+
+        let (playback, current_playback) = {
+            let player = state.player.read();
+            (
+                serde_json::to_value(&player.playback).unwrap_or_default(),
+                player.current_playback(),
+            )
+        };
+
+        if last_playback.as_ref() != Some(&playback) {
+            let notification = json!({
+                "jsonrpc": "2.0",
+                "method": "state_changed",
+                "params": {"playback": current_playback},
+            });
+            if tx.send(notification).is_err() {
+                return;
+            }
+            last_playback = Some(playback);
+        }
+    }
+}
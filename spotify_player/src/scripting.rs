@@ -0,0 +1,233 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::SystemTime,
+};
+
+use anyhow::{Context as _, Result};
+use rhai::{Dynamic, Engine, EvalAltResult, Map, AST};
+use serde::Deserialize;
+
+use crate::{
+    client::{ClientRequest, PlayerRequest},
+    command::Command,
+    config,
+    state::*,
+    utils::map_join,
+};
+
+/// the maximum number of operations performed by a script,
+/// which prevents a misbehaving script from blocking the application
+const MAX_SCRIPT_OPERATIONS: u64 = 1_000_000;
+/// the maximum depth of function calls in a script
+const MAX_SCRIPT_CALL_LEVELS: usize = 32;
+/// the maximum size (in bytes) of a string created by a script
+const MAX_SCRIPT_STRING_SIZE: usize = 1024 * 1024;
+/// the maximum number of items in an array or object map created by a script
+const MAX_SCRIPT_COLLECTION_SIZE: usize = 100_000;
+
+thread_local! {
+    /// the compiled scripts keyed by the scripts' paths together with the files' modified times,
+    /// a script is recompiled once its file is modified
+    static COMPILED_SCRIPTS: RefCell<HashMap<PathBuf, (SystemTime, Rc<AST>)>> =
+        RefCell::new(HashMap::new());
+}
+
+/// An action requested by a script, which is dispatched after the script finishes
+pub enum ScriptAction {
+    Command(Command),
+    Request(Box<ClientRequest>),
+}
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Get the path of a user-defined script, which is stored inside the `scripts` folder
+/// of the application's config folder
+fn script_path(name: &str) -> std::path::PathBuf {
+    config::get_config()
+        .config_folder
+        .join("scripts")
+        .join(format!("{name}.rhai"))
+}
+
+/// Run a user-defined script and return the actions requested by the script
+pub fn run_script(name: &str, state: &SharedState) -> Result<Vec<ScriptAction>> {
+    let actions = Rc::new(RefCell::new(vec![]));
+    let engine = new_engine(state, &actions);
+    let ast = compile_script(&engine, &script_path(name))?;
+    engine
+        .run_ast(&ast)
+        .map_err(|err| anyhow::anyhow!("failed to run the script {name}: {err}"))?;
+
+    Ok(actions.take())
+}
+
+/// Compile a script file, the compiled script is cached until the file is modified
+fn compile_script(engine: &Engine, path: &Path) -> Result<Rc<AST>> {
+    let modified = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .with_context(|| format!("failed to read the script file {}", path.display()))?;
+    let cached = COMPILED_SCRIPTS.with_borrow(|scripts| {
+        scripts
+            .get(path)
+            .filter(|(time, _)| *time == modified)
+            .map(|(_, ast)| ast.clone())
+    });
+    if let Some(ast) = cached {
+        return Ok(ast);
+    }
+
+    let script = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read the script file {}", path.display()))?;
+    let ast = engine
+        .compile(script)
+        .map(Rc::new)
+        .map_err(|err| anyhow::anyhow!("failed to compile the script {}: {err}", path.display()))?;
+    COMPILED_SCRIPTS
+        .with_borrow_mut(|scripts| scripts.insert(path.to_path_buf(), (modified, ast.clone())));
+    Ok(ast)
+}
+
+fn push_request(actions: &RefCell<Vec<ScriptAction>>, request: ClientRequest) {
+    actions
+        .borrow_mut()
+        .push(ScriptAction::Request(Box::new(request)));
+}
+
+/// Create a script engine with the application's scripting API registered
+fn new_engine(state: &SharedState, actions: &Rc<RefCell<Vec<ScriptAction>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_SCRIPT_OPERATIONS)
+        .set_max_call_levels(MAX_SCRIPT_CALL_LEVELS)
+        .set_max_string_size(MAX_SCRIPT_STRING_SIZE)
+        .set_max_array_size(MAX_SCRIPT_COLLECTION_SIZE)
+        .set_max_map_size(MAX_SCRIPT_COLLECTION_SIZE);
+    engine.on_print(|s| tracing::info!("[script] {s}"));
+    engine.on_debug(|s, _, pos| tracing::debug!("[script] {pos:?}: {s}"));
+
+    // state queries
+    engine.register_fn("playback", {
+        let state = state.clone();
+        move || -> Dynamic {
+            let player = state.player.read();
+            let Some(playback) = player.buffered_playback.as_ref() else {
+                return Dynamic::UNIT;
+            };
+            let mut map = Map::new();
+            map.insert("is_playing".into(), playback.is_playing.into());
+            map.insert(
+                "progress_ms".into(),
+                player
+                    .playback_progress()
+                    .map(|p| p.num_milliseconds())
+                    .unwrap_or_default()
+                    .into(),
+            );
+            map.insert(
+                "volume".into(),
+                i64::from(playback.volume.unwrap_or_default()).into(),
+            );
+            map.insert("shuffle".into(), playback.shuffle_state.into());
+            map.insert(
+                "repeat".into(),
+                format!("{:?}", playback.repeat_state).to_lowercase().into(),
+            );
+            map.insert("device".into(), playback.device_name.clone().into());
+            map.into()
+        }
+    });
+    engine.register_fn("current_track", {
+        let state = state.clone();
+        move || -> Dynamic {
+            let player = state.player.read();
+            let Some(track) = player.current_playing_track() else {
+                return Dynamic::UNIT;
+            };
+            let mut map = Map::new();
+            map.insert(
+                "uri".into(),
+                track
+                    .id
+                    .as_ref()
+                    .map(|id| id.uri())
+                    .unwrap_or_default()
+                    .into(),
+            );
+            map.insert("name".into(), track.name.clone().into());
+            map.insert(
+                "artists".into(),
+                map_join(&track.artists, |a| &a.name, ", ").into(),
+            );
+            map.insert("album".into(), track.album.name.clone().into());
+            map.insert(
+                "duration_ms".into(),
+                track.duration.num_milliseconds().into(),
+            );
+            map.into()
+        }
+    });
+
+    // commands and client requests
+    engine.register_fn("run_command", {
+        let actions = actions.clone();
+        move |name: &str| -> ScriptResult<()> {
+            let command = Command::deserialize(serde::de::value::StrDeserializer::<
+                serde::de::value::Error,
+            >::new(name))
+            .map_err(|err| format!("unknown command {name}: {err}"))?;
+            actions.borrow_mut().push(ScriptAction::Command(command));
+            Ok(())
+        }
+    });
+    engine.register_fn("set_volume", {
+        let actions = actions.clone();
+        move |percent: i64| {
+            let percent = percent.clamp(0, 100) as u8;
+            push_request(
+                &actions,
+                ClientRequest::Player(PlayerRequest::Volume(percent)),
+            );
+        }
+    });
+    engine.register_fn("seek", {
+        let actions = actions.clone();
+        move |position_ms: i64| {
+            let position = chrono::Duration::milliseconds(position_ms.max(0));
+            push_request(
+                &actions,
+                ClientRequest::Player(PlayerRequest::SeekTrack(position)),
+            );
+        }
+    });
+    engine.register_fn("play", {
+        let actions = actions.clone();
+        move |uri: &str| -> ScriptResult<()> {
+            let playback = match ContextId::from_uri(uri) {
+                Ok(id) => Playback::Context(id, None),
+                Err(_) => {
+                    let id = TrackId::from_uri(uri)
+                        .map_err(|err| format!("invalid URI {uri}: {err}"))?;
+                    Playback::URIs(vec![id.into_static()], None)
+                }
+            };
+            push_request(
+                &actions,
+                ClientRequest::Player(PlayerRequest::StartPlayback(playback, None)),
+            );
+            Ok(())
+        }
+    });
+    engine.register_fn("add_to_queue", {
+        let actions = actions.clone();
+        move |uri: &str| -> ScriptResult<()> {
+            let id = TrackId::from_uri(uri).map_err(|err| format!("invalid URI {uri}: {err}"))?;
+            push_request(&actions, ClientRequest::AddTrackToQueue(id.into_static()));
+            Ok(())
+        }
+    });
+
+    engine
+}
@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use once_cell::sync::Lazy;
 use serde::{de::DeserializeOwned, Serialize};
@@ -13,12 +16,22 @@ pub enum FileCacheKey {
     FollowedArtists,
     SavedAlbums,
     SavedTracks,
+    ContextPresets,
+    LastContext,
 }
 
 /// default time-to-live cache duration
 pub static TTL_CACHE_DURATION: Lazy<std::time::Duration> =
     Lazy::new(|| std::time::Duration::from_secs(60 * 60 * 3));
 
+/// the folder (relative to the cache folder) storing the file caches of contexts
+const CONTEXT_FILE_CACHE_FOLDER: &str = "contexts";
+/// time-to-live duration of a context's file cache
+const CONTEXT_FILE_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(60 * 60 * 24);
+/// the maximum number of contexts stored in the file cache,
+/// the least recently stored contexts are evicted first
+const MAX_CONTEXT_FILE_CACHE_ENTRIES: usize = 128;
+
 /// the application's data
 pub struct AppData {
     pub user_data: UserData,
@@ -34,12 +47,17 @@ pub struct UserData {
     pub followed_artists: Vec<Artist>,
     pub saved_albums: Vec<Album>,
     pub saved_tracks: HashMap<String, Track>,
+    /// sort and filter presets of contexts, keyed by the contexts' URIs
+    pub context_presets: HashMap<String, ContextPreset>,
 }
 
 /// the application's in-memory caches
 pub struct MemoryCaches {
     pub context: ttl_cache::TtlCache<String, Context>,
     pub search: ttl_cache::TtlCache<String, SearchResults>,
+    pub track_details: ttl_cache::TtlCache<String, TrackDetails>,
+    /// the segments of tracks' audio analyses, used to approximate the visualizer's spectrum
+    pub audio_analysis: ttl_cache::TtlCache<String, Vec<rspotify::model::AudioAnalysisSegment>>,
     #[cfg(feature = "lyric-finder")]
     pub lyrics: ttl_cache::TtlCache<String, lyric_finder::LyricResult>,
     #[cfg(feature = "image")]
@@ -58,6 +76,8 @@ impl MemoryCaches {
         Self {
             context: ttl_cache::TtlCache::new(64),
             search: ttl_cache::TtlCache::new(64),
+            track_details: ttl_cache::TtlCache::new(64),
+            audio_analysis: ttl_cache::TtlCache::new(64),
             #[cfg(feature = "lyric-finder")]
             lyrics: ttl_cache::TtlCache::new(64),
             #[cfg(feature = "image")]
@@ -75,6 +95,27 @@ impl AppData {
         }
     }
 
+    /// Insert a context into the in-memory cache, re-applying the context's last sort order (if any)
+    pub fn insert_context(&mut self, id: &ContextId, context: Context) {
+        let uri = id.uri();
+        self.caches
+            .context
+            .insert(uri.clone(), context, *TTL_CACHE_DURATION);
+
+        if let Some(preset) = self.user_data.context_presets.get(&uri).cloned() {
+            if let Some(tracks) = self.context_tracks(id) {
+                preset.sort_tracks(tracks);
+            }
+        }
+
+        // precompute the tracks' search texts, so searching inside the context is a plain scan
+        if let Some(tracks) = self.context_tracks(id) {
+            tracks.iter().for_each(|t| {
+                t.search_text();
+            });
+        }
+    }
+
     /// Get a list of tracks inside a given context
     pub fn context_tracks(&mut self, id: &ContextId) -> Option<&mut Vec<Track>> {
         self.caches.context.get_mut(&id.uri()).map(|c| match c {
@@ -104,6 +145,8 @@ impl UserData {
                 .unwrap_or_default(),
             saved_tracks: load_data_from_file_cache(FileCacheKey::SavedTracks, cache_folder)
                 .unwrap_or_default(),
+            context_presets: load_data_from_file_cache(FileCacheKey::ContextPresets, cache_folder)
+                .unwrap_or_default(),
         }
     }
 
@@ -158,3 +201,82 @@ where
         None
     }
 }
+
+fn context_file_cache_path(cache_folder: &Path, uri: &str) -> PathBuf {
+    cache_folder
+        .join(CONTEXT_FILE_CACHE_FOLDER)
+        .join(format!("{}.json", uri.replace(':', "_")))
+}
+
+/// stores a context into the file cache, evicting the least recently stored
+/// contexts if the number of stored contexts exceeds `MAX_CONTEXT_FILE_CACHE_ENTRIES`
+pub fn store_context_into_file_cache(
+    cache_folder: &Path,
+    uri: &str,
+    context: &Context,
+) -> std::io::Result<()> {
+    let path = context_file_cache_path(cache_folder, uri);
+    let folder = path.parent().expect("context file cache path has a parent");
+    std::fs::create_dir_all(folder)?;
+    let f = std::fs::File::create(&path)?;
+    serde_json::to_writer(f, context)?;
+
+    let mut entries = std::fs::read_dir(folder)?
+        .filter_map(|e| {
+            let e = e.ok()?;
+            Some((e.metadata().ok()?.modified().ok()?, e.path()))
+        })
+        .collect::<Vec<_>>();
+    if entries.len() > MAX_CONTEXT_FILE_CACHE_ENTRIES {
+        entries.sort();
+        for (_, path) in &entries[..entries.len() - MAX_CONTEXT_FILE_CACHE_ENTRIES] {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// loads a context from the file cache, returns `None` if the context is not stored
+/// or its file cache is expired
+pub fn load_context_from_file_cache(cache_folder: &Path, uri: &str) -> Option<Context> {
+    let path = context_file_cache_path(cache_folder, uri);
+    let modified = std::fs::metadata(&path).ok()?.modified().ok()?;
+    if modified
+        .elapsed()
+        .map_or(true, |d| d >= CONTEXT_FILE_CACHE_TTL)
+    {
+        return None;
+    }
+    read_context_file_cache(&path, uri)
+}
+
+/// loads a context from the file cache regardless of its expiry,
+/// which is used to revalidate an expired context instead of refetching it
+pub fn load_stale_context_from_file_cache(cache_folder: &Path, uri: &str) -> Option<Context> {
+    let path = context_file_cache_path(cache_folder, uri);
+    if !path.exists() {
+        return None;
+    }
+    read_context_file_cache(&path, uri)
+}
+
+fn read_context_file_cache(path: &Path, uri: &str) -> Option<Context> {
+    let f = std::fs::File::open(path).ok()?;
+    match serde_json::from_reader(std::io::BufReader::new(f)) {
+        Ok(context) => Some(context),
+        Err(err) => {
+            tracing::error!("Failed to load context {uri} from the file cache: {err:#}");
+            None
+        }
+    }
+}
+
+/// removes a context from the file cache, e.g. after the context is modified
+pub fn remove_context_from_file_cache(cache_folder: &Path, uri: &str) {
+    let path = context_file_cache_path(cache_folder, uri);
+    if path.exists() {
+        if let Err(err) = std::fs::remove_file(&path) {
+            tracing::error!("Failed to remove context {uri} from the file cache: {err:#}");
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, VecDeque};
+
+/// the maximum number of recent client requests kept for diagnostics
+const MAX_RECENT_REQUESTS: usize = 10;
+/// the number of consecutive requests failed because of network or server errors
+/// to consider Spotify API unreachable
+const CIRCUIT_BREAKER_THRESHOLD: u32 = 5;
+/// the duration between two consecutive probe requests while Spotify API is unreachable
+const CIRCUIT_BREAKER_PROBE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+/// the duration to pause outgoing requests upon a rate-limited response without a `Retry-After` value
+const DEFAULT_RATE_LIMIT_PAUSE: std::time::Duration = std::time::Duration::from_secs(5);
+/// the upper bounds (in seconds) of the client request latency histogram's buckets
+pub const REQUEST_LATENCY_BUCKETS_IN_SECS: [f64; 8] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Default, Debug)]
+/// Diagnostic information about the application's health
+pub struct Diagnostics {
+    /// the expiry time of the current authentication token
+    pub token_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// the most recent handled client requests with their latencies
+    pub recent_requests: VecDeque<RequestStats>,
+    /// the number of handled client requests
+    pub n_requests: u64,
+    /// the number of failed client requests
+    pub n_failed_requests: u64,
+    /// the number of handled client requests in each bucket of `REQUEST_LATENCY_BUCKETS_IN_SECS`,
+    /// the last bucket counts requests slower than the largest bucket's bound
+    pub request_latency_buckets: [u64; REQUEST_LATENCY_BUCKETS_IN_SECS.len() + 1],
+    /// the total latency of handled client requests
+    pub request_latency_sum: std::time::Duration,
+    /// the number of handled terminal events
+    pub n_events: u64,
+    /// the number of responses rejected by Spotify API because of rate limiting
+    pub n_rate_limited_responses: u64,
+    /// the `Retry-After` value (in seconds) of the last rate-limited response
+    pub last_retry_after_in_secs: Option<u64>,
+    /// the time until which outgoing requests are paused because of rate limiting
+    pub rate_limited_until: Option<std::time::Instant>,
+    /// the number of hits and misses of each in-memory cache
+    pub cache_lookups: BTreeMap<&'static str, (u64, u64)>,
+    /// the number of client requests waiting to be handled
+    pub pending_requests: usize,
+    /// the number of consecutive client requests failed because of network or server errors
+    pub consecutive_transient_failures: u32,
+    /// the time Spotify API is considered unreachable since (if any), during which
+    /// outgoing requests are blocked except periodic probe requests
+    pub unreachable_since: Option<std::time::Instant>,
+    /// the time of the last request let through while Spotify API is unreachable
+    pub last_probe_time: Option<std::time::Instant>,
+    /// whether the application is subscribed to the user's Spotify Connect notifications,
+    /// during which the playback is updated upon notifications instead of polling
+    pub is_connect_subscribed: bool,
+}
+
+#[derive(Debug)]
+/// Statistics of a handled client request
+pub struct RequestStats {
+    pub request: String,
+    pub latency: std::time::Duration,
+    pub succeeded: bool,
+}
+
+impl Diagnostics {
+    /// records a handled client request
+    pub fn record_request(&mut self, stats: RequestStats) {
+        self.n_requests += 1;
+        let latency = stats.latency.as_secs_f64();
+        let bucket = REQUEST_LATENCY_BUCKETS_IN_SECS
+            .iter()
+            .position(|&bound| latency <= bound)
+            .unwrap_or(REQUEST_LATENCY_BUCKETS_IN_SECS.len());
+        self.request_latency_buckets[bucket] += 1;
+        self.request_latency_sum += stats.latency;
+
+        if stats.succeeded {
+            self.consecutive_transient_failures = 0;
+            self.unreachable_since = None;
+        } else {
+            self.n_failed_requests += 1;
+        }
+        if self.recent_requests.len() == MAX_RECENT_REQUESTS {
+            self.recent_requests.pop_front();
+        }
+        self.recent_requests.push_back(stats);
+    }
+
+    /// records a request failed because of a network or server error, Spotify API is considered
+    /// unreachable after `CIRCUIT_BREAKER_THRESHOLD` consecutive failures
+    pub fn record_transient_failure(&mut self) {
+        self.consecutive_transient_failures += 1;
+        if self.unreachable_since.is_none()
+            && self.consecutive_transient_failures >= CIRCUIT_BREAKER_THRESHOLD
+        {
+            let now = std::time::Instant::now();
+            self.unreachable_since = Some(now);
+            self.last_probe_time = Some(now);
+        }
+    }
+
+    /// checks if Spotify API is considered unreachable
+    pub fn is_api_unreachable(&self) -> bool {
+        self.unreachable_since.is_some()
+    }
+
+    /// checks if a probe request is due while Spotify API is unreachable
+    pub fn is_probe_due(&self) -> bool {
+        self.is_api_unreachable()
+            && self
+                .last_probe_time
+                .is_none_or(|t| t.elapsed() >= CIRCUIT_BREAKER_PROBE_INTERVAL)
+    }
+
+    /// checks if an outgoing request can be sent, which is blocked while Spotify API
+    /// is unreachable unless the request is used as a probe
+    pub fn try_send_request(&mut self) -> bool {
+        if !self.is_api_unreachable() {
+            return true;
+        }
+        if self.is_probe_due() {
+            self.last_probe_time = Some(std::time::Instant::now());
+            return true;
+        }
+        false
+    }
+
+    /// records a handled terminal event
+    pub fn record_event(&mut self) {
+        self.n_events += 1;
+    }
+
+    /// records a lookup into an in-memory cache
+    pub fn record_cache_lookup(&mut self, cache: &'static str, hit: bool) {
+        let (hits, misses) = self.cache_lookups.entry(cache).or_default();
+        if hit {
+            *hits += 1;
+        } else {
+            *misses += 1;
+        }
+    }
+
+    /// records a response rejected because of rate limiting
+    /// and pauses outgoing requests for the response's `Retry-After` duration
+    pub fn record_rate_limited_response(&mut self, retry_after_in_secs: Option<u64>) {
+        self.n_rate_limited_responses += 1;
+        self.last_retry_after_in_secs = retry_after_in_secs;
+
+        let pause = retry_after_in_secs
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(DEFAULT_RATE_LIMIT_PAUSE);
+        let until = std::time::Instant::now() + pause;
+        self.rate_limited_until = Some(self.rate_limited_until.map_or(until, |t| t.max(until)));
+    }
+
+    /// gets the remaining duration (in seconds, rounded up) until outgoing requests are resumed
+    /// if they are paused because of rate limiting
+    pub fn rate_limit_remaining_secs(&self) -> Option<u64> {
+        self.rate_limited_until
+            .and_then(|t| t.checked_duration_since(std::time::Instant::now()))
+            .filter(|d| !d.is_zero())
+            .map(|d| d.as_secs() + u64::from(d.subsec_nanos() > 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(succeeded: bool) -> RequestStats {
+        RequestStats {
+            request: "GetCurrentPlayback".to_string(),
+            latency: std::time::Duration::from_millis(10),
+            succeeded,
+        }
+    }
+
+    #[test]
+    fn api_is_unreachable_after_consecutive_transient_failures() {
+        let mut diagnostics = Diagnostics::default();
+        for _ in 1..CIRCUIT_BREAKER_THRESHOLD {
+            diagnostics.record_request(request(false));
+            diagnostics.record_transient_failure();
+        }
+        assert!(!diagnostics.is_api_unreachable());
+        assert!(diagnostics.try_send_request());
+
+        diagnostics.record_request(request(false));
+        diagnostics.record_transient_failure();
+        assert!(diagnostics.is_api_unreachable());
+        // requests are blocked until the next probe is due
+        assert!(!diagnostics.try_send_request());
+
+        // a successful request closes the circuit
+        diagnostics.record_request(request(true));
+        assert!(!diagnostics.is_api_unreachable());
+        assert!(diagnostics.try_send_request());
+    }
+
+    #[test]
+    fn a_probe_request_is_let_through_periodically() {
+        let mut diagnostics = Diagnostics::default();
+        for _ in 0..CIRCUIT_BREAKER_THRESHOLD {
+            diagnostics.record_transient_failure();
+        }
+        assert!(!diagnostics.try_send_request());
+
+        diagnostics.last_probe_time =
+            Some(std::time::Instant::now() - CIRCUIT_BREAKER_PROBE_INTERVAL);
+        assert!(diagnostics.is_probe_due());
+        assert!(diagnostics.try_send_request());
+        // only one probe is sent per interval
+        assert!(!diagnostics.try_send_request());
+    }
+}
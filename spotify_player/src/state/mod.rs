@@ -1,11 +1,13 @@
 mod constant;
 mod data;
+mod diagnostics;
 mod model;
 mod player;
 mod ui;
 
 pub use constant::*;
 pub use data::*;
+pub use diagnostics::*;
 pub use model::*;
 pub use player::*;
 pub use ui::*;
@@ -14,6 +16,10 @@ use crate::config;
 
 pub use parking_lot::{Mutex, RwLock};
 
+use parking_lot::Condvar;
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
 /// Application's shared state (wrapped inside an std::sync::Arc)
 pub type SharedState = std::sync::Arc<State>;
 
@@ -22,8 +28,21 @@ pub struct State {
     pub ui: Mutex<UIState>,
     pub player: RwLock<PlayerState>,
     pub data: RwLock<AppData>,
+    pub diagnostics: Mutex<Diagnostics>,
 
     pub is_daemon: bool,
+    /// whether the application runs without a connection to Spotify,
+    /// during which the data stored in the cache folder is browsed
+    offline: AtomicBool,
+    /// whether the contexts' sort and filter presets are changed since they were last persisted
+    context_presets_changed: AtomicBool,
+
+    /// a counter increased on every state change, which is used by the UI
+    /// to skip redrawing frames when nothing has changed
+    generation: AtomicU64,
+    /// a condition variable notified on every state change, which is used by the UI
+    /// to wait for state changes instead of polling the state
+    change_notifier: (Mutex<()>, Condvar),
 }
 
 impl State {
@@ -35,6 +54,10 @@ impl State {
             // update the UI's theme based on the `theme` config option
             ui.theme = theme;
         }
+        ui.is_queue_pane_visible = configs.app_config.enable_queue_pane;
+        if let Some(page) = start_page(&configs) {
+            ui.new_page(page);
+        }
 
         let app_data = AppData::new(&configs.cache_folder);
 
@@ -42,10 +65,86 @@ impl State {
             ui: Mutex::new(ui),
             player: RwLock::new(PlayerState::default()),
             data: RwLock::new(app_data),
+            diagnostics: Mutex::new(Diagnostics::default()),
             is_daemon,
+            offline: AtomicBool::new(false),
+            context_presets_changed: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+            change_notifier: (Mutex::new(()), Condvar::new()),
         }
     }
 
+    /// records a lookup into an in-memory cache for diagnostics, returns whether the lookup is a hit
+    pub fn record_cache_lookup(&self, cache: &'static str, hit: bool) -> bool {
+        self.diagnostics.lock().record_cache_lookup(cache, hit);
+        hit
+    }
+
+    /// updates the sort and filter preset of a context, the presets are only persisted
+    /// into the cache folder by a later `flush_context_presets` call
+    pub fn update_context_preset(&self, uri: String, f: impl FnOnce(&mut ContextPreset)) {
+        f(self
+            .data
+            .write()
+            .user_data
+            .context_presets
+            .entry(uri)
+            .or_default());
+        self.context_presets_changed.store(true, Ordering::Relaxed);
+    }
+
+    /// persists the contexts' sort and filter presets into the cache folder
+    /// if they are changed since they were last persisted
+    pub fn flush_context_presets(&self) {
+        if !self.context_presets_changed.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        let presets = self.data.read().user_data.context_presets.clone();
+
+        // the presets are written without holding the lock to avoid blocking the UI on file I/O
+        if let Err(err) = store_data_into_file_cache(
+            FileCacheKey::ContextPresets,
+            &config::get_config().cache_folder,
+            &presets,
+        ) {
+            tracing::error!("Failed to store context presets into the file cache: {err:#}");
+        }
+    }
+
+    /// marks the application's state as changed, so that the UI will be redrawn
+    pub fn mark_changed(&self) {
+        // the lock is held to avoid missing the notification if
+        // the UI is about to wait in `wait_for_change`
+        let _guard = self.change_notifier.0.lock();
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.change_notifier.1.notify_all();
+    }
+
+    /// blocks the current thread until the application's state changes from `generation`
+    /// or `timeout` elapses
+    pub fn wait_for_change(&self, generation: u64, timeout: std::time::Duration) {
+        let mut guard = self.change_notifier.0.lock();
+        if self.generation() == generation {
+            self.change_notifier.1.wait_for(&mut guard, timeout);
+        }
+    }
+
+    /// gets the current generation of the application's state
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// checks if the application runs in offline mode
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    /// enters or leaves the offline mode
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::Relaxed);
+        self.mark_changed();
+    }
+
     #[cfg(feature = "streaming")]
     pub fn is_streaming_enabled(&self) -> bool {
         let configs = config::get_config();
@@ -54,3 +153,37 @@ impl State {
                 && self.is_daemon)
     }
 }
+
+/// gets the page opened on startup based on the `start_page` config option
+fn start_page(configs: &config::Configs) -> Option<PageState> {
+    let context_page = |id| PageState::Context {
+        id: None,
+        context_page_type: ContextPageType::Browsing(id),
+        state: None,
+    };
+
+    match configs.app_config.start_page {
+        config::StartPage::Library => None,
+        config::StartPage::LikedTracks => Some(context_page(ContextId::Tracks(
+            USER_LIKED_TRACKS_ID.to_owned(),
+        ))),
+        config::StartPage::Search => Some(PageState::Search {
+            line_input: crate::ui::single_line_input::LineInput::default(),
+            current_query: String::new(),
+            state: SearchPageUIState::new(),
+        }),
+        config::StartPage::LastContext => {
+            let uri = load_data_from_file_cache::<String>(
+                FileCacheKey::LastContext,
+                &configs.cache_folder,
+            )?;
+            match ContextId::from_uri(&uri) {
+                Ok(id) => Some(context_page(id)),
+                Err(err) => {
+                    tracing::warn!("Failed to open the last context {uri}: {err:#}");
+                    None
+                }
+            }
+        }
+    }
+}
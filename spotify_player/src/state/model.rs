@@ -1,12 +1,12 @@
 pub use rspotify::model as rspotify_model;
 use rspotify::model::CurrentPlaybackContext;
-pub use rspotify::model::{AlbumId, ArtistId, Id, PlaylistId, TrackId, UserId};
+pub use rspotify::model::{AlbumId, ArtistId, EpisodeId, Id, PlaylistId, TrackId, UserId};
 
 use crate::utils::map_join;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(untagged)]
 /// A Spotify context (playlist, album, artist)
 pub enum Context {
@@ -66,7 +66,7 @@ pub struct SearchResults {
     pub playlists: Vec<Playlist>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 /// A track order
 pub enum TrackOrder {
     AddedAt,
@@ -76,6 +76,14 @@ pub enum TrackOrder {
     Duration,
 }
 
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+/// The last sort order and filter applied to a context's tracks
+pub struct ContextPreset {
+    pub order: Option<TrackOrder>,
+    pub reversed: bool,
+    pub filter: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 /// A Spotify item (track, album, artist, playlist)
 pub enum Item {
@@ -97,6 +105,7 @@ pub enum ItemId {
 pub struct PlaybackMetadata {
     pub device_name: String,
     pub device_id: Option<String>,
+    pub device_type: rspotify_model::DeviceType,
     pub volume: Option<u32>,
     pub is_playing: bool,
     pub repeat_state: rspotify_model::RepeatState,
@@ -114,6 +123,7 @@ pub struct PlaybackMetadata {
 pub struct Device {
     pub id: String,
     pub name: String,
+    pub device_type: rspotify_model::DeviceType,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -125,8 +135,33 @@ pub struct Track {
     pub album: Option<Album>,
     pub duration: std::time::Duration,
     pub explicit: bool,
-    #[serde(skip)]
+    #[serde(default)]
+    pub disc_number: i32,
+    #[serde(default)]
+    pub track_number: u32,
+    /// the time (Unix timestamp in seconds) the track was added to its playlist,
+    /// `0` (omitted when serialized) if the track isn't a playlist's item
+    #[serde(default, skip_serializing_if = "Track::is_added_at_unknown")]
     pub added_at: u64,
+    /// whether the track is playable in the user's market, unplayable tracks
+    /// are shown in the track tables but are skipped on playback
+    #[serde(default = "Track::default_is_playable")]
+    pub is_playable: bool,
+    /// the podcast episode represented by the track if it's a playlist's episode item,
+    /// in which case the track's `id` holds the episode's ID as a placeholder
+    #[serde(default)]
+    pub episode: Option<Box<Episode>>,
+    /// the track's lowercase description matched against search queries,
+    /// which is computed once instead of on every search
+    #[serde(skip)]
+    pub search_text: once_cell::sync::OnceCell<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+/// A podcast episode inside a playlist
+pub struct Episode {
+    pub id: EpisodeId<'static>,
+    pub show: String,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -153,6 +188,13 @@ pub struct Playlist {
     pub name: String,
     pub owner: (String, UserId<'static>),
     pub desc: String,
+    #[serde(default)]
+    pub public: Option<bool>,
+    #[serde(default)]
+    pub n_tracks: u32,
+    /// the playlist's version identifier, which changes whenever the playlist is modified
+    #[serde(default)]
+    pub snapshot_id: String,
 }
 
 #[derive(Clone, Debug)]
@@ -162,7 +204,27 @@ pub struct Category {
     pub name: String,
 }
 
+#[derive(Clone, Debug)]
+/// Additional details of a Spotify track
+pub struct TrackDetails {
+    pub album: String,
+    pub release_date: String,
+    pub popularity: u32,
+    /// audio features of the track, `None` if they are not available
+    pub features: Option<rspotify_model::AudioFeatures>,
+}
+
 impl Context {
+    /// gets the context's name
+    pub fn name(&self) -> &str {
+        match self {
+            Context::Album { ref album, .. } => &album.name,
+            Context::Playlist { ref playlist, .. } => &playlist.name,
+            Context::Artist { ref artist, .. } => &artist.name,
+            Context::Tracks { ref desc, .. } => desc,
+        }
+    }
+
     /// gets the context's description
     pub fn description(&self) -> String {
         match self {
@@ -195,6 +257,20 @@ impl Context {
 }
 
 impl ContextId {
+    /// parses a context ID from a playlist, album or artist URI
+    pub fn from_uri(uri: &str) -> anyhow::Result<Self> {
+        let uri = crate::utils::parse_uri(uri);
+        if uri.starts_with("spotify:playlist:") {
+            Ok(Self::Playlist(PlaylistId::from_uri(&uri)?.into_static()))
+        } else if uri.starts_with("spotify:album:") {
+            Ok(Self::Album(AlbumId::from_uri(&uri)?.into_static()))
+        } else if uri.starts_with("spotify:artist:") {
+            Ok(Self::Artist(ArtistId::from_uri(&uri)?.into_static()))
+        } else {
+            anyhow::bail!("{uri} is not a playlist, album or artist URI")
+        }
+    }
+
     pub fn uri(&self) -> String {
         match self {
             Self::Album(id) => id.uri(),
@@ -205,6 +281,18 @@ impl ContextId {
     }
 }
 
+impl ContextPreset {
+    /// applies the preset's sort order to a list of tracks
+    pub fn sort_tracks(&self, tracks: &mut [Track]) {
+        if let Some(order) = self.order {
+            tracks.sort_by(|x, y| order.compare(x, y));
+        }
+        if self.reversed {
+            tracks.reverse();
+        }
+    }
+}
+
 impl TrackOrder {
     pub fn compare(&self, x: &Track, y: &Track) -> std::cmp::Ordering {
         match *self {
@@ -223,14 +311,34 @@ impl Device {
         Some(Self {
             id: device.id?,
             name: device.name,
+            device_type: device._type,
         })
     }
 }
 
 impl Track {
-    /// gets the track's artists information
+    /// gets the track's Spotify URI, which is the episode's URI if the track is an episode
+    pub fn uri(&self) -> String {
+        match self.episode {
+            Some(ref episode) => episode.id.uri(),
+            None => self.id.uri(),
+        }
+    }
+
+    /// gets the track's Spotify link, which is the episode's link if the track is an episode
+    pub fn url(&self) -> String {
+        match self.episode {
+            Some(ref episode) => episode.id.url(),
+            None => self.id.url(),
+        }
+    }
+
+    /// gets the track's artists information, which is the show's name if the track is an episode
     pub fn artists_info(&self) -> String {
-        map_join(&self.artists, |a| &a.name, ", ")
+        match self.episode {
+            Some(ref episode) => episode.show.clone(),
+            None => map_join(&self.artists, |a| &a.name, ", "),
+        }
     }
 
     /// gets the track's album information
@@ -250,49 +358,128 @@ impl Track {
         }
     }
 
-    /// tries to convert from a `rspotify_model::SimplifiedTrack` into `Track`
-    pub fn try_from_simplified_track(track: rspotify_model::SimplifiedTrack) -> Option<Self> {
-        if track.is_playable.unwrap_or(true) {
-            let id = match track.linked_from {
-                Some(d) => d.id,
-                None => track.id?,
-            };
-            Some(Self {
-                id,
-                name: track.name,
-                artists: from_simplified_artists_to_artists(track.artists),
-                album: None,
-                duration: track.duration.to_std().expect("valid chrono duration"),
-                explicit: track.explicit,
-                added_at: 0,
-            })
-        } else {
-            None
+    fn default_is_playable() -> bool {
+        true
+    }
+
+    fn is_added_at_unknown(added_at: &u64) -> bool {
+        *added_at == 0
+    }
+
+    /// constructs an unplayable placeholder for a playlist's item at `position` whose
+    /// track is unavailable, e.g. a removed track or a local file
+    pub fn unavailable(position: usize, added_at: u64) -> Self {
+        Self {
+            id: TrackId::from_id(format!("unavailable{position}")).expect("valid Spotify ID"),
+            name: "Unavailable".to_string(),
+            artists: vec![],
+            album: None,
+            duration: std::time::Duration::ZERO,
+            explicit: false,
+            disc_number: 0,
+            track_number: position as u32 + 1,
+            added_at,
+            is_playable: false,
+            episode: None,
+            search_text: Default::default(),
         }
     }
 
-    /// tries to convert from a `rspotify_model::FullTrack` into `Track`
-    pub fn try_from_full_track(track: rspotify_model::FullTrack) -> Option<Self> {
-        if track.is_playable.unwrap_or(true) {
-            let id = match track.linked_from {
-                Some(d) => d.id,
-                None => track.id?,
-            };
-            Some(Self {
-                id,
-                name: track.name,
-                artists: from_simplified_artists_to_artists(track.artists),
-                album: Album::try_from_simplified_album(track.album),
-                duration: track.duration.to_std().expect("valid chrono duration"),
-                explicit: track.explicit,
-                added_at: 0,
-            })
-        } else {
-            None
+    /// constructs a track representing a playlist's podcast episode
+    pub fn from_episode(
+        id: EpisodeId<'static>,
+        name: String,
+        show: String,
+        duration: std::time::Duration,
+        explicit: bool,
+        is_playable: bool,
+        added_at: u64,
+    ) -> Self {
+        Self {
+            id: TrackId::from_id(id.id().to_owned()).expect("valid Spotify ID"),
+            name,
+            artists: vec![],
+            album: None,
+            duration,
+            explicit,
+            disc_number: 0,
+            track_number: 0,
+            added_at,
+            is_playable,
+            episode: Some(Box::new(Episode { id, show })),
+            search_text: Default::default(),
         }
     }
+
+    /// tries to convert from a `rspotify_model::SimplifiedTrack` into `Track`,
+    /// an unplayable track is kept and marked as such
+    pub fn try_from_simplified_track(track: rspotify_model::SimplifiedTrack) -> Option<Self> {
+        let id = match track.linked_from {
+            Some(d) => d.id,
+            None => track.id?,
+        };
+        Some(Self {
+            id,
+            name: track.name,
+            artists: from_simplified_artists_to_artists(track.artists),
+            album: None,
+            duration: track.duration.to_std().expect("valid chrono duration"),
+            explicit: track.explicit,
+            disc_number: track.disc_number,
+            track_number: track.track_number,
+            added_at: 0,
+            is_playable: track.is_playable.unwrap_or(true),
+            episode: None,
+            search_text: Default::default(),
+        })
+    }
+
+    /// tries to convert from a `rspotify_model::FullTrack` into `Track`,
+    /// an unplayable track is kept and marked as such
+    pub fn try_from_full_track(track: rspotify_model::FullTrack) -> Option<Self> {
+        let id = match track.linked_from {
+            Some(d) => d.id,
+            None => track.id?,
+        };
+        Some(Self {
+            id,
+            name: track.name,
+            artists: from_simplified_artists_to_artists(track.artists),
+            album: Album::try_from_simplified_album(track.album),
+            duration: track.duration.to_std().expect("valid chrono duration"),
+            explicit: track.explicit,
+            disc_number: track.disc_number,
+            track_number: track.track_number,
+            added_at: 0,
+            is_playable: track.is_playable.unwrap_or(true),
+            episode: None,
+            search_text: Default::default(),
+        })
+    }
+}
+
+/// An item that can be filtered by the search popup
+pub trait Searchable: std::fmt::Display {
+    /// gets the lowercase text matched against a search query
+    fn search_text(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string().to_lowercase())
+    }
+}
+
+impl Searchable for Track {
+    fn search_text(&self) -> Cow<'_, str> {
+        Cow::Borrowed(
+            self.search_text
+                .get_or_init(|| self.to_string().to_lowercase()),
+        )
+    }
 }
 
+impl Searchable for Album {}
+impl Searchable for Artist {}
+impl Searchable for Playlist {}
+impl Searchable for Category {}
+
 impl std::fmt::Display for Track {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -374,6 +561,13 @@ impl std::fmt::Display for Artist {
     }
 }
 
+/// removes HTML tags from a text, e.g. a playlist's description
+// TODO: may also need to do HTML escaping here
+pub fn remove_html_tags(text: &str) -> String {
+    let re = regex::Regex::new("(<.*?>|</.*?>)").expect("valid regex");
+    re.replace_all(text, "").to_string()
+}
+
 /// a helper function to convert a vector of `rspotify_model::SimplifiedArtist`
 /// into a vector of `Artist`.
 fn from_simplified_artists_to_artists(
@@ -396,17 +590,16 @@ impl From<rspotify_model::SimplifiedPlaylist> for Playlist {
                 playlist.owner.id,
             ),
             desc: String::new(),
+            public: playlist.public,
+            n_tracks: playlist.tracks.total,
+            snapshot_id: playlist.snapshot_id,
         }
     }
 }
 
 impl From<rspotify_model::FullPlaylist> for Playlist {
     fn from(playlist: rspotify_model::FullPlaylist) -> Self {
-        // remove HTML tags from the description
-        // TODO: may also need to do HTML escaping here
-        let re = regex::Regex::new("(<.*?>|</.*?>)").expect("valid regex");
-        let desc = playlist.description.unwrap_or_default();
-        let desc = re.replace_all(&desc, "").to_string();
+        let desc = remove_html_tags(&playlist.description.unwrap_or_default());
 
         Self {
             id: playlist.id,
@@ -417,7 +610,37 @@ impl From<rspotify_model::FullPlaylist> for Playlist {
                 playlist.owner.id,
             ),
             desc,
+            public: playlist.public,
+            n_tracks: playlist.tracks.total,
+            snapshot_id: playlist.snapshot_id,
+        }
+    }
+}
+
+impl Playlist {
+    /// gets the playlist's badge describing its visibility
+    pub fn badge(&self) -> Option<&'static str> {
+        if self.collaborative {
+            Some("collaborative")
+        } else {
+            match self.public {
+                Some(true) => Some("public"),
+                Some(false) => Some("private"),
+                None => None,
+            }
+        }
+    }
+
+    /// gets the playlist's detailed information, including its owner, number of tracks and badge
+    pub fn detailed_info(&self) -> String {
+        let mut info = format!(
+            "{} • {} • {} tracks",
+            self.name, self.owner.0, self.n_tracks
+        );
+        if let Some(badge) = self.badge() {
+            info.push_str(&format!(" [{badge}]"));
         }
+        info
     }
 }
 
@@ -490,6 +713,7 @@ impl PlaybackMetadata {
         Self {
             device_name: p.device.name.clone(),
             device_id: p.device.id.clone(),
+            device_type: p.device._type.clone(),
             is_playing: p.is_playing,
             volume: p.device.volume_percent,
             repeat_state: p.repeat_state,
@@ -499,3 +723,64 @@ impl PlaybackMetadata {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(id: &str, name: &str, added_at: u64) -> Track {
+        Track {
+            id: TrackId::from_id(id).unwrap().into_static(),
+            name: name.to_owned(),
+            artists: vec![],
+            album: None,
+            duration: std::time::Duration::from_secs(180),
+            explicit: false,
+            disc_number: 1,
+            track_number: 1,
+            added_at,
+            is_playable: true,
+            episode: None,
+            search_text: Default::default(),
+        }
+    }
+
+    fn names(tracks: &[Track]) -> Vec<&str> {
+        tracks.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[test]
+    fn context_preset_sorts_tracks() {
+        let mut tracks = vec![
+            track("0000000000000000000001", "b", 3),
+            track("0000000000000000000002", "c", 1),
+            track("0000000000000000000003", "a", 2),
+        ];
+
+        let mut preset = ContextPreset {
+            order: Some(TrackOrder::TrackName),
+            ..Default::default()
+        };
+        preset.sort_tracks(&mut tracks);
+        assert_eq!(names(&tracks), ["a", "b", "c"]);
+
+        preset.order = Some(TrackOrder::AddedAt);
+        preset.reversed = true;
+        preset.sort_tracks(&mut tracks);
+        assert_eq!(names(&tracks), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn context_preset_round_trips_its_filter() {
+        let preset = ContextPreset {
+            order: Some(TrackOrder::Duration),
+            reversed: true,
+            filter: Some("daft punk".to_owned()),
+        };
+        let preset: ContextPreset =
+            serde_json::from_str(&serde_json::to_string(&preset).unwrap()).unwrap();
+        assert_eq!(preset.order, Some(TrackOrder::Duration));
+        assert!(preset.reversed);
+        assert_eq!(preset.filter.as_deref(), Some("daft punk"));
+    }
+}
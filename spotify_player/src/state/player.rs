@@ -12,9 +12,33 @@ pub struct PlayerState {
     pub buffered_playback: Option<PlaybackMetadata>,
 
     pub queue: Option<rspotify_model::CurrentUserQueue>,
+
+    #[cfg(feature = "streaming")]
+    /// the integrated player's settings changed at runtime (if any)
+    pub integrated_player_settings: Option<IntegratedPlayerSettings>,
+}
+
+#[cfg(feature = "streaming")]
+/// Settings of the integrated player which can be changed at runtime
+#[derive(Debug, Clone, Copy)]
+pub struct IntegratedPlayerSettings {
+    pub normalization: bool,
+    pub gapless: bool,
 }
 
 impl PlayerState {
+    #[cfg(feature = "streaming")]
+    /// Get the integrated player's settings, which default to the `device` configurations
+    pub fn integrated_player_settings(&self) -> IntegratedPlayerSettings {
+        self.integrated_player_settings.unwrap_or_else(|| {
+            let device = &crate::config::get_config().app_config.device;
+            IntegratedPlayerSettings {
+                normalization: device.normalization,
+                gapless: device.gapless,
+            }
+        })
+    }
+
     /// Get the current playback
     ///
     /// # Note
@@ -37,6 +61,7 @@ impl PlayerState {
         if let Some(ref p) = self.buffered_playback {
             playback.device.name.clone_from(&p.device_name);
             playback.device.id.clone_from(&p.device_id);
+            playback.device._type.clone_from(&p.device_type);
             playback.is_playing = p.is_playing;
             playback.device.volume_percent = p.volume;
             playback.repeat_state = p.repeat_state;
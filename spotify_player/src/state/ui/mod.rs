@@ -10,6 +10,9 @@ use super::*;
 pub use page::*;
 pub use popup::*;
 
+/// the duration an error message reported to the user is displayed
+const ERROR_MESSAGE_DURATION: std::time::Duration = std::time::Duration::from_secs(5);
+
 #[derive(Default, Debug)]
 pub struct ImageRenderInfo {
     pub url: String,
@@ -24,6 +27,8 @@ pub struct UIState {
     pub is_running: bool,
     pub theme: config::Theme,
     pub input_key_sequence: key::KeySequence,
+    /// the time of the last key event in the pending input key sequence
+    pub input_key_sequence_time: std::time::Instant,
 
     pub history: Vec<PageState>,
     pub popup: Option<PopupState>,
@@ -32,6 +37,37 @@ pub struct UIState {
     /// which is mainly used to handle mouse click events (for seeking command)
     pub playback_progress_bar_rect: tui::layout::Rect,
 
+    /// The rectangles representing the segments of the navigation path (breadcrumb)
+    /// together with the segments' positions in the navigation history,
+    /// which are mainly used to handle mouse click events (for jumping back to a page)
+    pub breadcrumb_rects: Vec<(tui::layout::Rect, usize)>,
+
+    /// whether to show the queue pane next to the main layout
+    pub is_queue_pane_visible: bool,
+    /// the minimum level of logs shown in the log pane, `None` if the pane is hidden
+    pub log_pane_level: Option<tracing::Level>,
+    /// whether to show the audio visualizer pane below the main layout
+    pub is_visualizer_pane_visible: bool,
+    /// the track table's columns hidden by user
+    pub hidden_track_table_columns: std::collections::HashSet<TrackTableColumn>,
+    /// whether to export the next rendered frame as a screenshot
+    pub is_screenshot_requested: bool,
+
+    /// the recorded keyboard macros, keyed by their registers
+    pub macros: std::collections::HashMap<char, Vec<key::Key>>,
+    /// the register and the keys of the keyboard macro being recorded (if any)
+    pub recording_macro: Option<(char, Vec<key::Key>)>,
+    /// a `RecordMacro` or `ReplayMacro` command waiting for the macro's register key
+    pub pending_macro_command: Option<crate::command::Command>,
+    /// whether a keyboard macro is being replayed
+    pub is_replaying_macro: bool,
+
+    /// the command-line arguments to restart the application with after it stops running (if any)
+    pub restart_args: Option<Vec<std::ffi::OsString>>,
+
+    /// the latest error reported to the user and the time it was reported
+    pub error_message: Option<(String, std::time::Instant)>,
+
     #[cfg(feature = "image")]
     pub last_cover_image_render_info: ImageRenderInfo,
 }
@@ -68,6 +104,46 @@ impl UIState {
         });
     }
 
+    /// Clear the pending input key sequence if no key is pressed within the key sequence timeout.
+    ///
+    /// Returns `true` if the key sequence is cleared.
+    pub fn expire_input_key_sequence(&mut self) -> bool {
+        let timeout = config::get_config().app_config.key_sequence_timeout_in_ms;
+        if timeout > 0
+            && !self.input_key_sequence.keys.is_empty()
+            && self.input_key_sequence_time.elapsed() >= std::time::Duration::from_millis(timeout)
+        {
+            self.input_key_sequence.keys.clear();
+            return true;
+        }
+        false
+    }
+
+    /// Report an error to the user, which is displayed for a few seconds
+    pub fn report_error(&mut self, message: String) {
+        self.error_message = Some((message, std::time::Instant::now()));
+    }
+
+    /// Clear the reported error message after it has been displayed for a few seconds.
+    ///
+    /// Returns `true` if the error message is cleared.
+    pub fn expire_error_message(&mut self) -> bool {
+        if self
+            .error_message
+            .as_ref()
+            .is_some_and(|(_, time)| time.elapsed() >= ERROR_MESSAGE_DURATION)
+        {
+            self.error_message = None;
+            return true;
+        }
+        false
+    }
+
+    /// Return whether the search popup filtering the current page's items is opened.
+    pub fn is_search_popup_open(&self) -> bool {
+        matches!(self.popup, Some(PopupState::Search { .. }))
+    }
+
     /// Return whether there exists a focused popup.
     ///
     /// Currently, only search popup is not focused when it's opened.
@@ -79,7 +155,7 @@ impl UIState {
     }
 
     /// Get a list of items possibly filtered by a search query if exists a search popup
-    pub fn search_filtered_items<'a, T: std::fmt::Display>(&self, items: &'a [T]) -> Vec<&'a T> {
+    pub fn search_filtered_items<'a, T: Searchable>(&self, items: &'a [T]) -> Vec<&'a T> {
         match self.popup {
             Some(PopupState::Search { ref query }) => {
                 let query = query.to_lowercase();
@@ -89,7 +165,7 @@ impl UIState {
                         if query.is_empty() {
                             true
                         } else {
-                            let t = t.to_string().to_lowercase();
+                            let t = t.search_text();
                             query.split(' ').any(|q| !q.is_empty() && t.contains(q))
                         }
                     })
@@ -106,6 +182,7 @@ impl Default for UIState {
             is_running: true,
             theme: Default::default(),
             input_key_sequence: key::KeySequence { keys: vec![] },
+            input_key_sequence_time: std::time::Instant::now(),
 
             history: vec![PageState::Library {
                 state: LibraryPageUIState::new(),
@@ -113,9 +190,66 @@ impl Default for UIState {
             popup: None,
 
             playback_progress_bar_rect: Default::default(),
+            breadcrumb_rects: vec![],
+
+            is_queue_pane_visible: false,
+            log_pane_level: None,
+            is_visualizer_pane_visible: false,
+            hidden_track_table_columns: Default::default(),
+            is_screenshot_requested: false,
+
+            macros: Default::default(),
+            recording_macro: None,
+            pending_macro_command: None,
+            is_replaying_macro: false,
+
+            restart_args: None,
+
+            error_message: None,
 
             #[cfg(feature = "image")]
             last_cover_image_render_info: Default::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Category;
+
+    fn categories(names: &[&str]) -> Vec<Category> {
+        names
+            .iter()
+            .map(|name| Category {
+                id: name.to_string(),
+                name: name.to_string(),
+            })
+            .collect()
+    }
+
+    fn filtered_names(ui: &UIState, items: &[Category]) -> Vec<String> {
+        ui.search_filtered_items(items)
+            .into_iter()
+            .map(|c| c.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn search_popup_filters_items_by_any_query_word() {
+        let items = categories(&["Jazz", "Hip Hop", "Rock"]);
+        let mut ui = UIState::default();
+        assert_eq!(filtered_names(&ui, &items), ["Jazz", "Hip Hop", "Rock"]);
+
+        ui.popup = Some(PopupState::Search {
+            query: "ROCK jaz".to_owned(),
+        });
+        assert!(ui.is_search_popup_open());
+        assert_eq!(filtered_names(&ui, &items), ["Jazz", "Rock"]);
+
+        ui.popup = Some(PopupState::Search {
+            query: String::new(),
+        });
+        assert_eq!(filtered_names(&ui, &items), ["Jazz", "Hip Hop", "Rock"]);
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::{HashMap, HashSet};
+
 use crate::{state::model::*, ui::single_line_input::LineInput, utils};
 use tui::widgets::{ListState, TableState};
 
@@ -72,6 +74,7 @@ pub enum ContextPageType {
 pub enum ContextPageUIState {
     Playlist {
         track_table: TableState,
+        album_grouping: Option<AlbumGroupingState>,
     },
     Album {
         track_table: TableState,
@@ -84,7 +87,44 @@ pub enum ContextPageUIState {
     },
     Tracks {
         track_table: TableState,
+        album_grouping: Option<AlbumGroupingState>,
+    },
+}
+
+#[derive(Clone, Debug, Default)]
+/// UI state of a track table whose tracks are grouped by album
+pub struct AlbumGroupingState {
+    /// URIs of the collapsed albums
+    pub collapsed_albums: HashSet<String>,
+}
+
+#[derive(Clone, Debug)]
+/// A row in an album-grouped or disc-grouped track table
+pub enum TrackTableRow {
+    AlbumHeader {
+        /// the album's URI, empty for tracks without an album
+        uri: String,
+        album: String,
+        n_tracks: usize,
+        collapsed: bool,
+    },
+    DiscHeader {
+        disc_number: i32,
+        n_tracks: usize,
     },
+    /// a track represented by its position in the track table
+    Track(usize),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// A column of a track table
+pub enum TrackTableColumn {
+    Liked,
+    Number,
+    Title,
+    Artists,
+    Album,
+    Duration,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -142,6 +182,22 @@ impl PageState {
         }
     }
 
+    /// The page's title, which is used to represent the page in the navigation path.
+    pub fn title(&self) -> String {
+        match self {
+            PageState::Library { .. } => String::from("Library"),
+            PageState::Context {
+                context_page_type, ..
+            } => context_page_type.title(),
+            PageState::Search { .. } => String::from("Search"),
+            PageState::Browse { .. } => String::from("Browse"),
+            #[cfg(feature = "lyric-finder")]
+            PageState::Lyric { .. } => String::from("Lyric"),
+            PageState::Queue { .. } => String::from("Queue"),
+            PageState::CommandHelp { .. } => String::from("Commands"),
+        }
+    }
+
     /// Select a `id`-th item in the currently focused window of the page.
     pub fn select(&mut self, id: usize) {
         if let Some(mut state) = self.focus_window_state_mut() {
@@ -155,8 +211,56 @@ impl PageState {
             .map(|state| state.selected())?
     }
 
+    /// The rows of the page's track table if its tracks are grouped, which can be
+    /// - tracks grouped by album if the album grouping is enabled
+    /// - tracks grouped by disc if the page is an album page with multiple discs
+    pub fn grouped_track_table_rows(&self, tracks: &[&Track]) -> Option<Vec<TrackTableRow>> {
+        if let Some(album_grouping) = self.album_grouping() {
+            return Some(album_grouping.group_tracks(tracks));
+        }
+        match self {
+            Self::Context {
+                state: Some(ContextPageUIState::Album { .. }),
+                ..
+            } => group_tracks_by_disc(tracks),
+            _ => None,
+        }
+    }
+
+    /// The album grouping state of the page's track table (if any).
+    pub fn album_grouping(&self) -> Option<&AlbumGroupingState> {
+        match self {
+            Self::Context {
+                state:
+                    Some(
+                        ContextPageUIState::Playlist { album_grouping, .. }
+                        | ContextPageUIState::Tracks { album_grouping, .. },
+                    ),
+                ..
+            } => album_grouping.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// The mutable album grouping state of the page's track table.
+    ///
+    /// Returns `None` if the page doesn't support grouping tracks by album.
+    pub fn album_grouping_mut(&mut self) -> Option<&mut Option<AlbumGroupingState>> {
+        match self {
+            Self::Context {
+                state:
+                    Some(
+                        ContextPageUIState::Playlist { album_grouping, .. }
+                        | ContextPageUIState::Tracks { album_grouping, .. },
+                    ),
+                ..
+            } => Some(album_grouping),
+            _ => None,
+        }
+    }
+
     /// The currently focused window state of the page.
-    pub fn focus_window_state_mut(&mut self) -> Option<MutableWindowState> {
+    pub fn focus_window_state_mut(&mut self) -> Option<MutableWindowState<'_>> {
         match self {
             Self::Library {
                 state:
@@ -191,10 +295,10 @@ impl PageState {
                 SearchFocusState::Playlists => Some(MutableWindowState::List(playlist_list)),
             },
             Self::Context { state, .. } => state.as_mut().map(|state| match state {
-                ContextPageUIState::Tracks { track_table } => {
+                ContextPageUIState::Tracks { track_table, .. } => {
                     MutableWindowState::Table(track_table)
                 }
-                ContextPageUIState::Playlist { track_table } => {
+                ContextPageUIState::Playlist { track_table, .. } => {
                     MutableWindowState::Table(track_table)
                 }
                 ContextPageUIState::Album { track_table } => MutableWindowState::Table(track_table),
@@ -267,6 +371,7 @@ impl ContextPageUIState {
     pub fn new_playlist() -> Self {
         Self::Playlist {
             track_table: utils::new_table_state(),
+            album_grouping: None,
         }
     }
 
@@ -288,10 +393,121 @@ impl ContextPageUIState {
     pub fn new_tracks() -> Self {
         Self::Tracks {
             track_table: utils::new_table_state(),
+            album_grouping: None,
         }
     }
 }
 
+impl AlbumGroupingState {
+    /// Group a list of tracks by album into a list of track table rows.
+    ///
+    /// Albums are identified by their URIs, so different albums with the same name are separate
+    /// groups. Albums are ordered by their first appearance in the list. Tracks of a collapsed
+    /// album are not included in the returned rows.
+    pub fn group_tracks(&self, tracks: &[&Track]) -> Vec<TrackTableRow> {
+        let mut albums: Vec<(String, String, Vec<usize>)> = vec![];
+        let mut album_positions = HashMap::new();
+        for (id, track) in tracks.iter().enumerate() {
+            let uri = track.album.as_ref().map(|a| a.id.uri()).unwrap_or_default();
+            let pos = *album_positions.entry(uri.clone()).or_insert_with(|| {
+                albums.push((uri, track.album_info(), vec![]));
+                albums.len() - 1
+            });
+            albums[pos].2.push(id);
+        }
+
+        let mut rows = vec![];
+        for (uri, album, ids) in albums {
+            let collapsed = self.collapsed_albums.contains(&uri);
+            rows.push(TrackTableRow::AlbumHeader {
+                uri,
+                album,
+                n_tracks: ids.len(),
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend(ids.into_iter().map(TrackTableRow::Track));
+            }
+        }
+        rows
+    }
+
+    /// Collapse or expand an album group given the album's URI
+    pub fn toggle_collapsed(&mut self, uri: &str) {
+        if !self.collapsed_albums.remove(uri) {
+            self.collapsed_albums.insert(uri.to_owned());
+        }
+    }
+}
+
+impl TrackTableColumn {
+    /// all columns of a track table in their display order
+    pub const ALL: [Self; 6] = [
+        Self::Liked,
+        Self::Number,
+        Self::Title,
+        Self::Artists,
+        Self::Album,
+        Self::Duration,
+    ];
+
+    /// The column's header
+    pub fn header(&self) -> &'static str {
+        match self {
+            Self::Liked => "",
+            Self::Number => "#",
+            Self::Title => "Title",
+            Self::Artists => "Artists",
+            Self::Album => "Album",
+            Self::Duration => "Duration",
+        }
+    }
+
+    /// The column's name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Liked => "Liked",
+            Self::Number => "Number",
+            header => header.header(),
+        }
+    }
+
+    /// Whether the column can be hidden
+    pub fn is_hideable(&self) -> bool {
+        // the title column is always shown to identify tracks in the table
+        *self != Self::Title
+    }
+}
+
+/// Group a list of tracks by disc into a list of track table rows.
+///
+/// Returns `None` if all the tracks belong to a single disc.
+fn group_tracks_by_disc(tracks: &[&Track]) -> Option<Vec<TrackTableRow>> {
+    let mut discs: Vec<(i32, Vec<usize>)> = vec![];
+    for (id, track) in tracks.iter().enumerate() {
+        match discs
+            .iter_mut()
+            .find(|(disc, _)| *disc == track.disc_number)
+        {
+            Some((_, ids)) => ids.push(id),
+            None => discs.push((track.disc_number, vec![id])),
+        }
+    }
+    if discs.len() <= 1 {
+        return None;
+    }
+
+    let mut rows = vec![];
+    for (disc_number, ids) in discs {
+        rows.push(TrackTableRow::DiscHeader {
+            disc_number,
+            n_tracks: ids.len(),
+        });
+        rows.extend(ids.into_iter().map(TrackTableRow::Track));
+    }
+    Some(rows)
+}
+
 impl<'a> MutableWindowState<'a> {
     pub fn select(&mut self, id: usize) {
         match self {
@@ -409,3 +625,90 @@ impl_focusable!(
     [Artists, Playlists],
     [Playlists, Input]
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rspotify::model::{AlbumId, TrackId};
+
+    fn track(id: &str, album_id: &str, album_name: &str) -> Track {
+        Track {
+            id: TrackId::from_id(id).unwrap().into_static(),
+            name: id.to_owned(),
+            artists: vec![],
+            album: Some(Album {
+                id: AlbumId::from_id(album_id).unwrap().into_static(),
+                release_date: String::new(),
+                name: album_name.to_owned(),
+                artists: vec![],
+            }),
+            duration: std::time::Duration::from_secs(180),
+            explicit: false,
+            disc_number: 1,
+            track_number: 1,
+            added_at: 0,
+            is_playable: true,
+            episode: None,
+            search_text: Default::default(),
+        }
+    }
+
+    fn headers(rows: &[TrackTableRow]) -> Vec<(String, usize, bool)> {
+        rows.iter()
+            .filter_map(|row| match row {
+                TrackTableRow::AlbumHeader {
+                    album,
+                    n_tracks,
+                    collapsed,
+                    ..
+                } => Some((album.clone(), *n_tracks, *collapsed)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn albums_with_the_same_name_are_grouped_separately() {
+        let tracks = [
+            track(
+                "0000000000000000000001",
+                "000000000000000000000a",
+                "Greatest Hits",
+            ),
+            track(
+                "0000000000000000000002",
+                "000000000000000000000b",
+                "Greatest Hits",
+            ),
+            track(
+                "0000000000000000000003",
+                "000000000000000000000a",
+                "Greatest Hits",
+            ),
+        ];
+        let tracks = tracks.iter().collect::<Vec<_>>();
+
+        let mut grouping = AlbumGroupingState::default();
+        let rows = grouping.group_tracks(&tracks);
+        assert_eq!(
+            headers(&rows),
+            [
+                ("Greatest Hits".to_string(), 2, false),
+                ("Greatest Hits".to_string(), 1, false)
+            ]
+        );
+        assert_eq!(rows.len(), 5);
+
+        // collapsing an album doesn't collapse another album with the same name
+        grouping.toggle_collapsed("spotify:album:000000000000000000000a");
+        let rows = grouping.group_tracks(&tracks);
+        assert_eq!(
+            headers(&rows),
+            [
+                ("Greatest Hits".to_string(), 2, true),
+                ("Greatest Hits".to_string(), 1, false)
+            ]
+        );
+        assert_eq!(rows.len(), 3);
+    }
+}
@@ -24,6 +24,22 @@ pub enum PopupState {
         desc: LineInput,
         current_field: PlaylistCreateCurrentField,
     },
+    TrackTableColumnList(ListState),
+    #[cfg(feature = "streaming")]
+    /// a list of the integrated player's settings
+    PlaybackSettings(ListState),
+    /// a list of profiles, `None` represents the default profile
+    ProfileList(Vec<Option<String>>, ListState),
+    TrackDetails(Track),
+    Diagnostics,
+    /// a QR code of an item's Spotify link
+    Share {
+        name: String,
+        url: String,
+    },
+    #[cfg(feature = "streaming")]
+    /// a confirmation before clearing the integrated device's audio cache
+    ConfirmClearAudioCache,
 }
 
 #[derive(Debug, Clone)]
@@ -59,7 +75,17 @@ impl PopupState {
             Self::ArtistList(.., list_state) => Some(list_state),
             Self::ThemeList(.., list_state) => Some(list_state),
             Self::ActionList(.., list_state) => Some(list_state),
-            Self::Search { .. } | Self::PlaylistCreate { .. } => None,
+            Self::TrackTableColumnList(list_state) => Some(list_state),
+            #[cfg(feature = "streaming")]
+            Self::PlaybackSettings(list_state) => Some(list_state),
+            Self::ProfileList(.., list_state) => Some(list_state),
+            Self::Search { .. }
+            | Self::PlaylistCreate { .. }
+            | Self::TrackDetails(..)
+            | Self::Diagnostics
+            | Self::Share { .. } => None,
+            #[cfg(feature = "streaming")]
+            Self::ConfirmClearAudioCache => None,
         }
     }
 
@@ -73,7 +99,17 @@ impl PopupState {
             Self::ArtistList(.., list_state) => Some(list_state),
             Self::ThemeList(.., list_state) => Some(list_state),
             Self::ActionList(.., list_state) => Some(list_state),
-            Self::Search { .. } | Self::PlaylistCreate { .. } => None,
+            Self::TrackTableColumnList(list_state) => Some(list_state),
+            #[cfg(feature = "streaming")]
+            Self::PlaybackSettings(list_state) => Some(list_state),
+            Self::ProfileList(.., list_state) => Some(list_state),
+            Self::Search { .. }
+            | Self::PlaylistCreate { .. }
+            | Self::TrackDetails(..)
+            | Self::Diagnostics
+            | Self::Share { .. } => None,
+            #[cfg(feature = "streaming")]
+            Self::ConfirmClearAudioCache => None,
         }
     }
 
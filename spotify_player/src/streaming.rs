@@ -1,18 +1,22 @@
 use crate::{client::Client, config, state::SharedState};
 use librespot_connect::spirc::Spirc;
 use librespot_core::{
+    cache::Cache,
     config::{ConnectConfig, DeviceType},
-    spotify_id,
+    spotify_id::{self, FileId},
 };
 use librespot_playback::mixer::MixerConfig;
 use librespot_playback::{
-    audio_backend,
+    audio_backend::{self, Sink, SinkError, SinkResult},
     config::{AudioFormat, Bitrate, PlayerConfig},
-    mixer::{self, Mixer},
+    convert::Converter,
+    decoder::AudioPacket,
+    mixer::{self, Mixer, VolumeGetter},
     player,
 };
 use rspotify::model::TrackId;
 use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 #[cfg(not(any(
     feature = "rodio-backend",
@@ -159,6 +163,240 @@ fn execute_player_event_hook_command(
     Ok(())
 }
 
+/// the interval to reopen the audio backend's sink after it fails
+const SINK_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// the number of volume changes of a fade
+const FADE_STEPS: u32 = 20;
+
+/// the attenuation factor (stored as `f64` bits) applied to the integrated player's volume by fades
+static FADE_FACTOR: AtomicU64 = AtomicU64::new(1.0f64.to_bits());
+/// a counter increased on every new fade, which is used to stop fades that are superseded
+static FADE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn fade_factor() -> f64 {
+    f64::from_bits(FADE_FACTOR.load(Ordering::Relaxed))
+}
+
+fn set_fade_factor(factor: f64) {
+    FADE_FACTOR.store(factor.to_bits(), Ordering::Relaxed);
+}
+
+/// Gradually changes the integrated player's volume to `target` (a fraction of the device's volume)
+/// over the `device.fade_duration_in_ms` duration
+pub async fn fade(target: f64) {
+    let generation = FADE_GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+    let duration = config::get_config().app_config.device.fade_duration_in_ms;
+    let start = fade_factor();
+    if duration == 0 {
+        set_fade_factor(target);
+        return;
+    }
+
+    for step in 1..=FADE_STEPS {
+        tokio::time::sleep(std::time::Duration::from_millis(duration) / FADE_STEPS).await;
+        if FADE_GENERATION.load(Ordering::Relaxed) != generation {
+            return;
+        }
+        set_fade_factor(fade_step_factor(start, target, step));
+    }
+}
+
+/// gets the attenuation factor at a step of a fade from `start` to `target`
+fn fade_step_factor(start: f64, target: f64, step: u32) -> f64 {
+    start + (target - start) * f64::from(step) / f64::from(FADE_STEPS)
+}
+
+/// A volume getter applying the fade factor on top of the mixer's volume
+struct FadingVolume(Box<dyn VolumeGetter + Send>);
+
+impl VolumeGetter for FadingVolume {
+    fn attenuation_factor(&self) -> f64 {
+        self.0.attenuation_factor() * fade_factor()
+    }
+}
+
+/// An audio sink wrapping the configured backend's sink.
+///
+/// `librespot` exits the application whenever the audio sink fails, so errors of the
+/// underlying sink are reported to the user instead. While the sink is failing, it's
+/// periodically reopened, and audio packets are consumed in real time so that
+/// the playback's progress stays in sync with the played audio.
+struct ReportingSink {
+    backend: audio_backend::SinkBuilder,
+    device: Option<String>,
+    sink: Option<Box<dyn Sink>>,
+    state: SharedState,
+    /// the last time the sink failed to be opened, started or written to (if any)
+    last_failure: Option<std::time::Instant>,
+}
+
+impl ReportingSink {
+    fn new(
+        backend: audio_backend::SinkBuilder,
+        device: Option<String>,
+        state: SharedState,
+    ) -> Self {
+        let mut sink = Self {
+            backend,
+            device,
+            sink: None,
+            state,
+            last_failure: None,
+        };
+        if let Err(err) = sink.open() {
+            sink.report(err);
+        }
+        sink
+    }
+
+    /// opens the backend's sink
+    fn open(&mut self) -> SinkResult<()> {
+        let (backend, device) = (self.backend, self.device.clone());
+        // some backends (e.g. `rodio`) panic when failing to open the output device
+        match std::panic::catch_unwind(move || backend(device, AudioFormat::default())) {
+            Ok(sink) => {
+                self.sink = Some(sink);
+                Ok(())
+            }
+            Err(_) => Err(SinkError::NotConnected(
+                "failed to open the audio output device".to_string(),
+            )),
+        }
+    }
+
+    /// reopens and restarts the sink if it failed more than `SINK_RETRY_INTERVAL` ago,
+    /// returns whether the sink is usable
+    fn try_recover(&mut self) -> bool {
+        let Some(last_failure) = self.last_failure else {
+            return true;
+        };
+        if last_failure.elapsed() < SINK_RETRY_INTERVAL {
+            return false;
+        }
+
+        let result = self
+            .open()
+            .and_then(|()| self.sink.as_mut().map_or(Ok(()), |sink| sink.start()));
+        match result {
+            Ok(()) => {
+                tracing::info!("Audio backend recovered");
+                self.last_failure = None;
+                true
+            }
+            Err(err) => {
+                tracing::debug!("Failed to reopen the audio backend: {err}");
+                self.sink = None;
+                self.last_failure = Some(std::time::Instant::now());
+                false
+            }
+        }
+    }
+
+    fn report(&mut self, err: SinkError) {
+        tracing::error!("Audio backend failed: {err}");
+        self.state
+            .ui
+            .lock()
+            .report_error(format!("Audio backend failed: {err}"));
+        self.sink = None;
+        self.last_failure = Some(std::time::Instant::now());
+    }
+}
+
+impl Sink for ReportingSink {
+    fn start(&mut self) -> SinkResult<()> {
+        if self.last_failure.is_some() {
+            // a failed sink is started once it's reopened
+            self.try_recover();
+            return Ok(());
+        }
+        if let Some(sink) = self.sink.as_mut() {
+            if let Err(err) = sink.start() {
+                self.report(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn stop(&mut self) -> SinkResult<()> {
+        if let Some(sink) = self.sink.as_mut() {
+            if let Err(err) = sink.stop() {
+                self.report(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, packet: AudioPacket, converter: &mut Converter) -> SinkResult<()> {
+        let samples = packet.samples().map(|s| s.len()).unwrap_or_default();
+        if let Ok(samples) = packet.samples() {
+            crate::visualizer::update_spectrum(samples);
+        }
+
+        if self.try_recover() {
+            if let Some(sink) = self.sink.as_mut() {
+                match sink.write(packet, converter) {
+                    Ok(()) => return Ok(()),
+                    Err(err) => self.report(err),
+                }
+            }
+        }
+
+        // consume the packet in real time instead of skipping through the track
+        std::thread::sleep(std::time::Duration::from_secs_f64(
+            samples as f64 / f64::from(librespot_playback::SAMPLES_PER_SECOND),
+        ));
+        Ok(())
+    }
+}
+
+/// Removes the audio files cached by `librespot` inside `folder`.
+///
+/// Only files named after `librespot` file IDs are removed, and they are removed via
+/// the cache so that its size limiter stays in sync. Returns the number of removed files.
+pub fn clear_audio_cache(cache: &Cache, folder: &std::path::Path) -> anyhow::Result<usize> {
+    if !folder.exists() {
+        return Ok(0);
+    }
+
+    let mut n_files = 0;
+    // cached files are stored as `{folder}/{first 2 chars of ID}/{remaining chars of ID}`
+    for dir in std::fs::read_dir(folder)? {
+        let dir = dir?;
+        if !dir.file_type()?.is_dir() {
+            continue;
+        }
+        let prefix = dir.file_name().to_string_lossy().into_owned();
+        for file in std::fs::read_dir(dir.path())? {
+            let name = file?.file_name();
+            let Some(id) = parse_file_id(&format!("{prefix}{}", name.to_string_lossy())) else {
+                continue;
+            };
+            if cache.remove_file(id).is_ok() {
+                n_files += 1;
+            }
+        }
+        // the folder is only removed if it's empty
+        if let Err(err) = std::fs::remove_dir(dir.path()) {
+            tracing::debug!("Kept the audio cache folder {:?}: {err}", dir.path());
+        }
+    }
+    Ok(n_files)
+}
+
+/// parses a `librespot` file ID from its hex representation
+fn parse_file_id(s: &str) -> Option<FileId> {
+    if s.len() != 40 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut id = [0_u8; 20];
+    for (i, byte) in id.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(s.get(2 * i..2 * i + 2)?, 16).ok()?;
+    }
+    Some(FileId(id))
+}
+
 /// Create a new streaming connection
 pub async fn new_connection(client: Client, state: SharedState) -> Spirc {
     let session = client.session().await;
@@ -187,14 +425,26 @@ pub async fn new_connection(client: Client, state: SharedState) -> Spirc {
         Box::new(mixer::softmixer::SoftMixer::open(MixerConfig::default())) as Box<dyn Mixer>;
     mixer.set_volume(volume);
 
-    let backend = audio_backend::find(None).expect("should be able to find an audio backend");
+    let backend = audio_backend::find(device.audio_backend.clone())
+        .expect("should be able to find an audio backend");
+    tracing::info!(
+        "Using audio backend {:?} with output device {:?}",
+        device.audio_backend,
+        device.audio_device
+    );
+    let settings = state.player.read().integrated_player_settings();
     let player_config = PlayerConfig {
         bitrate: device
             .bitrate
             .to_string()
             .parse::<Bitrate>()
             .unwrap_or_default(),
-        normalisation: device.normalization,
+        gapless: settings.gapless,
+        normalisation: settings.normalization,
+        normalisation_pregain_db: device.normalization_pregain.clamp(
+            *config::NORMALIZATION_PREGAIN_RANGE.start(),
+            *config::NORMALIZATION_PREGAIN_RANGE.end(),
+        ),
         ..Default::default()
     };
 
@@ -206,8 +456,12 @@ pub async fn new_connection(client: Client, state: SharedState) -> Spirc {
     let (player, mut channel) = player::Player::new(
         player_config,
         session.clone(),
-        mixer.get_soft_volume(),
-        move || backend(None, AudioFormat::default()),
+        Box::new(FadingVolume(mixer.get_soft_volume())),
+        {
+            let state = state.clone();
+            let audio_device = device.audio_device.clone();
+            move || Box::new(ReportingSink::new(backend, audio_device, state))
+        },
     );
 
     let player_event_task = tokio::task::spawn({
@@ -225,15 +479,24 @@ pub async fn new_connection(client: Client, state: SharedState) -> Spirc {
                                 if let Some(playback) = player.buffered_playback.as_mut() {
                                     playback.is_playing = true;
                                 }
+                                // fade in the volume muted upon pausing
+                                tokio::task::spawn(fade(1.0));
                             }
                             PlayerEvent::Paused { .. } => {
                                 let mut player = state.player.write();
                                 if let Some(playback) = player.buffered_playback.as_mut() {
                                     playback.is_playing = false;
                                 }
+                                // mute the volume so that the playback is faded in when resumed,
+                                // even if it is paused by another device without fading out
+                                if configs.app_config.device.fade_duration_in_ms > 0 {
+                                    FADE_GENERATION.fetch_add(1, Ordering::Relaxed);
+                                    set_fade_factor(0.0);
+                                }
                             }
                             _ => {}
                         }
+                        state.mark_changed();
                         client.update_playback(&state);
 
                         // execute a player event hook command
@@ -263,3 +526,39 @@ pub async fn new_connection(client: Client, state: SharedState) -> Spirc {
 
     spirc
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fade_steps_reach_target() {
+        assert_eq!(fade_step_factor(1.0, 0.0, 0), 1.0);
+        assert_eq!(fade_step_factor(1.0, 0.0, FADE_STEPS / 2), 0.5);
+        assert_eq!(fade_step_factor(1.0, 0.0, FADE_STEPS), 0.0);
+        assert_eq!(fade_step_factor(0.25, 1.0, FADE_STEPS), 1.0);
+
+        // a fade is monotonic towards its target
+        let factors = (0..=FADE_STEPS)
+            .map(|step| fade_step_factor(0.2, 0.8, step))
+            .collect::<Vec<_>>();
+        assert!(factors.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn file_ids_are_parsed_from_hex() {
+        let id = parse_file_id("00ff10ab00000000000000000000000000000001").unwrap();
+        assert_eq!(id.0[..4], [0x00, 0xff, 0x10, 0xab]);
+        assert_eq!(id.0[19], 0x01);
+
+        assert!(parse_file_id("00ff10ab").is_none());
+        assert!(parse_file_id("+0ff10ab00000000000000000000000000000001").is_none());
+        assert!(parse_file_id("zzff10ab00000000000000000000000000000001").is_none());
+        assert!(parse_file_id("audio-cache-notes.txt").is_none());
+    }
+
+    #[test]
+    fn fade_factor_defaults_to_full_volume() {
+        assert_eq!(fade_factor(), 1.0);
+    }
+}
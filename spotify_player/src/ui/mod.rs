@@ -1,12 +1,25 @@
 use crate::{config, state::*};
 use anyhow::{Context as AnyhowContext, Result};
+use std::io::Write;
 use tui::{layout::*, style::*, text::*, widgets::*, Frame};
 
 type Terminal = tui::Terminal<tui::backend::CrosstermBackend<std::io::Stdout>>;
 
+/// the minimum terminal's width required to render the application
+const MIN_TERMINAL_WIDTH: u16 = 40;
+/// the minimum terminal's height required to render the application
+const MIN_TERMINAL_HEIGHT: u16 = 12;
+/// the minimum width of the main layout to render the queue pane next to it
+const QUEUE_PANE_MIN_WIDTH: u16 = 120;
+/// the minimum height of the main layout to render the log pane below it
+const LOG_PANE_MIN_HEIGHT: u16 = 30;
+/// the minimum height of the main layout to render the visualizer pane below it
+const VISUALIZER_PANE_MIN_HEIGHT: u16 = 24;
+
 mod page;
 mod playback;
 mod popup;
+mod screenshot;
 pub mod single_line_input;
 mod utils;
 
@@ -14,19 +27,43 @@ mod utils;
 pub fn run(state: SharedState) -> Result<()> {
     let mut terminal = init_ui().context("failed to initialize the application's UI")?;
 
-    let ui_refresh_duration = std::time::Duration::from_millis(
-        config::get_config().app_config.app_refresh_duration_in_ms,
-    );
+    let configs = config::get_config();
+    let ui_refresh_duration =
+        std::time::Duration::from_millis(configs.app_config.app_refresh_duration_in_ms);
+    // the minimum duration between two consecutive rendered frames
+    let min_frame_duration =
+        std::time::Duration::from_secs(1) / std::cmp::max(configs.app_config.max_fps, 1);
     let mut last_terminal_size = None;
+    let mut last_frame: Option<(_, std::time::Instant)> = None;
+    let mut last_terminal_title = None;
 
     loop {
+        // the duration to wait for a frame pending because of the `max_fps` limit
+        let pending_frame_delay;
+
         {
             let mut ui = state.ui.lock();
             if !ui.is_running {
                 clean_up(terminal).context("clean up UI resources")?;
+                state.flush_context_presets();
+                // the exporter is shut down before restarting, which replaces the current process
+                #[cfg(feature = "opentelemetry")]
+                crate::logging::shutdown_otlp_exporter();
+                if let Some(args) = ui.restart_args.take() {
+                    crate::utils::restart_application(args).context("restart the application")?;
+                }
                 std::process::exit(0);
             }
 
+            // clear the pending key sequence (and its indicator) after the key sequence timeout
+            // and the reported error message after it has been displayed for a few seconds
+            if ui.expire_input_key_sequence() {
+                state.mark_changed();
+            }
+            if ui.expire_error_message() {
+                state.mark_changed();
+            }
+
             let terminal_size = terminal.size()?;
             if Some(terminal_size) != last_terminal_size {
                 last_terminal_size = Some(terminal_size);
@@ -37,19 +74,108 @@ pub fn run(state: SharedState) -> Result<()> {
                 }
             }
 
-            if let Err(err) = terminal.draw(|frame| {
-                // set the background and foreground colors for the application
-                let rect = frame.size();
-                let block = Block::default().style(ui.theme.app());
-                frame.render_widget(block, rect);
+            // A key identifying the application's frame. The application is only redrawn
+            // if the key changes, i.e. the state is updated, the terminal is resized,
+            // the playback's progress changes, some text is being scrolled,
+            // new logs are shown in the log pane or the health of Spotify API changes.
+            let frame_key = (
+                state.generation(),
+                terminal_size,
+                state
+                    .player
+                    .read()
+                    .playback_progress()
+                    .map(|p| p.num_seconds()),
+                utils::marquee_step(),
+                ui.log_pane_level
+                    .is_some()
+                    .then(crate::logging::log_counter),
+                ui.is_visualizer_pane_visible
+                    .then(crate::visualizer::frame_step),
+                {
+                    let diagnostics = state.diagnostics.lock();
+                    (
+                        diagnostics.rate_limit_remaining_secs(),
+                        diagnostics.is_api_unreachable(),
+                    )
+                },
+            );
+
+            // the cover image is rendered in a separate frame after its area is cleared
+            #[cfg(feature = "image")]
+            let force_redraw = !ui.last_cover_image_render_info.rendered
+                && !ui.last_cover_image_render_info.url.is_empty();
+            #[cfg(not(feature = "image"))]
+            let force_redraw = false;
+
+            let (needs_redraw, frame_delay) = match last_frame {
+                None => (true, None),
+                Some((ref key, time)) => {
+                    if force_redraw || *key != frame_key {
+                        let elapsed = time.elapsed();
+                        if elapsed >= min_frame_duration {
+                            (true, None)
+                        } else {
+                            (false, Some(min_frame_duration - elapsed))
+                        }
+                    } else {
+                        (false, None)
+                    }
+                }
+            };
+            pending_frame_delay = frame_delay;
+
+            if needs_redraw {
+                if configs.app_config.enable_terminal_title {
+                    update_terminal_title(&mut terminal, &state, &mut last_terminal_title);
+                }
+
+                utils::reset_marquee_state();
+                match terminal.draw(|frame| {
+                    // set the background and foreground colors for the application
+                    let rect = frame.size();
+                    let block = Block::default().style(ui.theme.app());
+                    frame.render_widget(block, rect);
 
-                render_application(frame, &state, &mut ui, rect);
-            }) {
-                tracing::error!("Failed to render the application: {err:#}");
+                    if rect.width < MIN_TERMINAL_WIDTH || rect.height < MIN_TERMINAL_HEIGHT {
+                        render_terminal_too_small_message(frame, rect);
+                    } else {
+                        render_application(frame, &state, &mut ui, rect);
+                    }
+                }) {
+                    Ok(completed_frame) => {
+                        if ui.is_screenshot_requested {
+                            ui.is_screenshot_requested = false;
+                            match screenshot::export_screenshot(completed_frame.buffer) {
+                                Ok((text_path, ansi_path)) => tracing::info!(
+                                    "Exported the application's screenshot to {} and {}",
+                                    text_path.display(),
+                                    ansi_path.display()
+                                ),
+                                Err(err) => {
+                                    tracing::error!("Failed to export the screenshot: {err:#}")
+                                }
+                            }
+                        }
+                    }
+                    Err(err) => tracing::error!("Failed to render the application: {err:#}"),
+                }
+                last_frame = Some((frame_key, std::time::Instant::now()));
             }
         }
 
-        std::thread::sleep(ui_refresh_duration);
+        match pending_frame_delay {
+            Some(delay) => std::thread::sleep(delay),
+            // wake up on state changes (e.g. user inputs, new data) or after `app_refresh_duration_in_ms`
+            // to check for time-based changes (e.g. playback's progress, scrolling texts)
+            None => state.wait_for_change(
+                last_frame
+                    .as_ref()
+                    .map(|(key, _)| key.0)
+                    .unwrap_or_default(),
+                ui_refresh_duration,
+            ),
+        }
     }
 }
 
@@ -62,12 +188,44 @@ fn init_ui() -> Result<Terminal> {
         crossterm::terminal::EnterAlternateScreen,
         crossterm::event::EnableMouseCapture
     )?;
+    if config::get_config().app_config.enable_terminal_title {
+        // save the terminal's current title onto the terminal's title stack,
+        // so that it can be restored when quitting the application
+        stdout.write_all(b"\x1b[22;0t")?;
+        stdout.flush()?;
+    }
     let backend = tui::backend::CrosstermBackend::new(stdout);
     let mut terminal = tui::Terminal::new(backend)?;
     terminal.clear()?;
     Ok(terminal)
 }
 
+/// Update the terminal's title based on the current playing track
+fn update_terminal_title(
+    terminal: &mut Terminal,
+    state: &SharedState,
+    last_title: &mut Option<String>,
+) {
+    let title = match state.player.read().current_playing_track() {
+        Some(track) => format!(
+            "{} – {}",
+            crate::utils::map_join(&track.artists, |a| &a.name, ", "),
+            track.name
+        ),
+        None => "spotify_player".to_string(),
+    };
+
+    if last_title.as_ref() != Some(&title) {
+        if let Err(err) = crossterm::execute!(
+            terminal.backend_mut(),
+            crossterm::terminal::SetTitle(&title)
+        ) {
+            tracing::warn!("Failed to update the terminal's title: {err:#}");
+        }
+        *last_title = Some(title);
+    }
+}
+
 /// Clean up UI resources before quitting the application
 fn clean_up(mut terminal: Terminal) -> Result<()> {
     crossterm::terminal::disable_raw_mode()?;
@@ -76,10 +234,36 @@ fn clean_up(mut terminal: Terminal) -> Result<()> {
         crossterm::terminal::LeaveAlternateScreen,
         crossterm::event::DisableMouseCapture,
     )?;
+    if config::get_config().app_config.enable_terminal_title {
+        // restore the terminal's title saved on the terminal's title stack
+        terminal.backend_mut().write_all(b"\x1b[23;0t")?;
+        terminal.backend_mut().flush()?;
+    }
     terminal.show_cursor()?;
     Ok(())
 }
 
+/// Render a message asking to enlarge the terminal whose size is too small to render the application
+fn render_terminal_too_small_message(frame: &mut Frame, rect: Rect) {
+    let text = format!(
+        "Terminal too small: {}x{}\nRequired: {MIN_TERMINAL_WIDTH}x{MIN_TERMINAL_HEIGHT}",
+        rect.width, rect.height
+    );
+    // vertically center the message
+    let rect = Layout::vertical([
+        Constraint::Fill(0),
+        Constraint::Length(2),
+        Constraint::Fill(0),
+    ])
+    .split(rect)[1];
+    frame.render_widget(
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        rect,
+    );
+}
+
 /// Render the application
 fn render_application(frame: &mut Frame, state: &SharedState, ui: &mut UIStateGuard, rect: Rect) {
     // rendering order: shortcut help popup -> playback window -> other popups -> main layout
@@ -95,6 +279,74 @@ fn render_application(frame: &mut Frame, state: &SharedState, ui: &mut UIStateGu
     render_main_layout(is_active, frame, state, ui, rect);
 }
 
+/// Render a pane showing the most recent application's logs
+fn render_log_pane(frame: &mut Frame, ui: &UIStateGuard, level: tracing::Level, rect: Rect) {
+    let rect = utils::construct_and_render_block(
+        &format!("Logs (level: {level})"),
+        &ui.theme,
+        Borders::ALL,
+        frame,
+        rect,
+    );
+
+    let lines = crate::logging::recent_log_entries(level, rect.height as usize)
+        .into_iter()
+        .map(|entry| {
+            let style = match entry.level {
+                tracing::Level::ERROR => Style::default().fg(Color::Red),
+                tracing::Level::WARN => Style::default().fg(Color::Yellow),
+                _ => Style::default(),
+            };
+            Line::styled(entry.line, style)
+        })
+        .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(lines), rect);
+}
+
+/// Render a pane visualizing the spectrum of the playing audio, which is computed from
+/// the audio played by the integrated player or approximated from the track's audio analysis
+fn render_visualizer_pane(frame: &mut Frame, state: &SharedState, ui: &UIStateGuard, rect: Rect) {
+    let rect =
+        utils::construct_and_render_block("Visualizer", &ui.theme, Borders::ALL, frame, rect);
+
+    let spectrum = crate::visualizer::played_spectrum().or_else(|| {
+        let player = state.player.read();
+        if !player.buffered_playback.as_ref()?.is_playing {
+            return None;
+        }
+        let uri = player.current_playing_track()?.id.as_ref()?.uri();
+        let data = state.data.read();
+        let segments = data.caches.audio_analysis.get(&uri)?;
+        crate::visualizer::approximated_spectrum(segments, player.playback_progress()?)
+    });
+    let Some(spectrum) = spectrum.filter(|s| !s.is_empty()) else {
+        frame.render_widget(Paragraph::new("No audio to visualize"), rect);
+        return;
+    };
+
+    // spread the bars across the pane's width
+    let n_bars = spectrum.len() as u16;
+    let bar_gap = u16::from(rect.width >= n_bars * 2);
+    let bar_width = std::cmp::max((rect.width + bar_gap) / n_bars, 1 + bar_gap) - bar_gap;
+
+    let bars = spectrum
+        .iter()
+        .map(|height| {
+            Bar::default()
+                .value((height * 100.0) as u64)
+                .text_value(String::new())
+        })
+        .collect::<Vec<_>>();
+    let color = ui.theme.playback_progress_bar().fg.unwrap_or(Color::Green);
+    let chart = BarChart::default()
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(bar_width)
+        .bar_gap(bar_gap)
+        .bar_style(Style::default().fg(color))
+        .max(100);
+    frame.render_widget(chart, rect);
+}
+
 /// Render the application's main layout
 fn render_main_layout(
     is_active: bool,
@@ -104,6 +356,42 @@ fn render_main_layout(
     rect: Rect,
 ) {
     let page_type = ui.current_page().page_type();
+    ui.breadcrumb_rects.clear();
+
+    // render the log pane at the bottom of the main layout unless the layout is too short
+    let rect = match ui.log_pane_level {
+        Some(level) if rect.height >= LOG_PANE_MIN_HEIGHT => {
+            let chunks =
+                Layout::vertical([Constraint::Fill(0), Constraint::Length(10)]).split(rect);
+            render_log_pane(frame, ui, level, chunks[1]);
+            chunks[0]
+        }
+        _ => rect,
+    };
+
+    // render the visualizer pane at the bottom of the main layout unless the layout is too short
+    let rect = if ui.is_visualizer_pane_visible && rect.height >= VISUALIZER_PANE_MIN_HEIGHT {
+        let chunks = Layout::vertical([Constraint::Fill(0), Constraint::Length(8)]).split(rect);
+        render_visualizer_pane(frame, state, ui, chunks[1]);
+        chunks[0]
+    } else {
+        rect
+    };
+
+    // render the queue pane on the right of the main layout unless the queue page is shown
+    // or the layout is too narrow
+    let rect = if ui.is_queue_pane_visible
+        && page_type != PageType::Queue
+        && rect.width >= QUEUE_PANE_MIN_WIDTH
+    {
+        let chunks =
+            Layout::horizontal([Constraint::Fill(0), Constraint::Percentage(35)]).split(rect);
+        page::render_queue_pane(frame, state, ui, chunks[1]);
+        chunks[0]
+    } else {
+        rect
+    };
+
     match page_type {
         PageType::Library => page::render_library_page(is_active, frame, state, ui, rect),
         PageType::Search => page::render_search_page(is_active, frame, state, ui, rect),
@@ -1,4 +1,7 @@
-use std::collections::{btree_map::Entry, BTreeMap};
+use std::{
+    borrow::Cow,
+    collections::{btree_map::Entry, BTreeMap},
+};
 
 use crate::utils::format_duration;
 
@@ -10,6 +13,11 @@ const COMMAND_TABLE_CONSTRAINTS: [Constraint; 3] = [
     Constraint::Percentage(50),
 ];
 
+/// the minimum width of a track table to render the album column
+const TRACK_TABLE_ALBUM_MIN_WIDTH: u16 = 80;
+/// the minimum width of a track table to render the duration column
+const TRACK_TABLE_DURATION_MIN_WIDTH: u16 = 50;
+
 // UI codes to render a page.
 // A `render_*_page` function should follow (not strictly) the below steps
 // 1. get data from the application's states
@@ -226,13 +234,19 @@ pub fn render_context_page(
     let data = state.data.read();
     match data.caches.context.get(&id.uri()) {
         Some(context) => {
-            // render context description
-            let chunks = Layout::vertical([Constraint::Length(1), Constraint::Fill(0)]).split(rect);
+            // render the navigation path and context description
+            let chunks = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Fill(0),
+            ])
+            .split(rect);
+            render_breadcrumb(frame, ui, &data, chunks[0]);
             frame.render_widget(
                 Paragraph::new(context.description()).style(ui.theme.page_desc()),
-                chunks[0],
+                chunks[1],
             );
-            let rect = chunks[1];
+            let rect = chunks[2];
 
             match context {
                 Context::Artist {
@@ -355,7 +369,7 @@ pub fn render_library_page(
         &ui.theme,
         ui.search_filtered_items(&data.user_data.playlists)
             .into_iter()
-            .map(|p| (p.to_string(), curr_context_uri == Some(p.id.uri())))
+            .map(|p| (p.detailed_info(), curr_context_uri == Some(p.id.uri())))
             .collect(),
         is_active && focus_state == LibraryFocusState::Playlists,
     );
@@ -590,37 +604,41 @@ pub fn render_commands_help_page(frame: &mut Frame, ui: &mut UIStateGuard, rect:
     frame.render_widget(help_table, rect);
 }
 
+fn get_playable_name(item: &rspotify::model::PlayableItem) -> String {
+    use rspotify::model::{FullEpisode, FullTrack, PlayableItem};
+    match item {
+        PlayableItem::Track(FullTrack { ref name, .. }) => name,
+        PlayableItem::Episode(FullEpisode { ref name, .. }) => name,
+    }
+    .to_string()
+}
+
+fn get_playable_artists(item: &rspotify::model::PlayableItem) -> String {
+    use rspotify::model::{FullEpisode, FullTrack, PlayableItem};
+    match item {
+        PlayableItem::Track(FullTrack { ref artists, .. }) => artists
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        PlayableItem::Episode(FullEpisode { .. }) => String::new(),
+    }
+}
+
+fn get_playable_duration(item: &rspotify::model::PlayableItem) -> String {
+    use rspotify::model::{FullEpisode, FullTrack, PlayableItem};
+    match item {
+        PlayableItem::Track(FullTrack { ref duration, .. }) => format_duration(duration),
+        PlayableItem::Episode(FullEpisode { ref duration, .. }) => format_duration(duration),
+    }
+}
+
 pub fn render_queue_page(
     frame: &mut Frame,
     state: &SharedState,
     ui: &mut UIStateGuard,
     rect: Rect,
 ) {
-    use rspotify::model::{FullEpisode, FullTrack, PlayableItem};
-    fn get_playable_name(item: &PlayableItem) -> String {
-        match item {
-            PlayableItem::Track(FullTrack { ref name, .. }) => name,
-            PlayableItem::Episode(FullEpisode { ref name, .. }) => name,
-        }
-        .to_string()
-    }
-    fn get_playable_artists(item: &PlayableItem) -> String {
-        match item {
-            PlayableItem::Track(FullTrack { ref artists, .. }) => artists
-                .iter()
-                .map(|a| a.name.as_str())
-                .collect::<Vec<_>>()
-                .join(", "),
-            PlayableItem::Episode(FullEpisode { .. }) => String::new(),
-        }
-    }
-    fn get_playable_duration(item: &PlayableItem) -> String {
-        match item {
-            PlayableItem::Track(FullTrack { ref duration, .. }) => format_duration(duration),
-            PlayableItem::Episode(FullEpisode { ref duration, .. }) => format_duration(duration),
-        }
-    }
-
     // 1. Get data
     let player = state.player.read();
     let queue = match player.queue {
@@ -678,6 +696,39 @@ pub fn render_queue_page(
     frame.render_widget(queue_table, rect);
 }
 
+/// Render a pane showing the upcoming items in the queue
+pub fn render_queue_pane(frame: &mut Frame, state: &SharedState, ui: &UIStateGuard, rect: Rect) {
+    let rect = construct_and_render_block("Up Next", &ui.theme, Borders::ALL, frame, rect);
+
+    let player = state.player.read();
+    let queue = match player.queue {
+        Some(ref q) => &q.queue,
+        None => {
+            frame.render_widget(Paragraph::new("Loading..."), rect);
+            return;
+        }
+    };
+
+    let queue_table = Table::new(
+        queue
+            .iter()
+            .take(rect.height as usize)
+            .map(|x| {
+                Row::new(vec![
+                    Cell::from(get_playable_name(x)),
+                    Cell::from(get_playable_artists(x)),
+                ])
+            })
+            .collect::<Vec<_>>(),
+        [Constraint::Percentage(60), Constraint::Percentage(40)],
+    )
+    .header(
+        Row::new(vec![Cell::from("Title"), Cell::from("Artists")]).style(ui.theme.table_header()),
+    );
+
+    frame.render_widget(queue_table, rect);
+}
+
 /// Render windows for an artist context page, which includes
 /// - A top track table
 /// - An album list
@@ -786,12 +837,12 @@ fn render_artist_context_page_windows(
     );
 }
 
-fn render_track_table(
+fn render_track_table<'a>(
     frame: &mut Frame,
     rect: Rect,
     is_active: bool,
     state: &SharedState,
-    tracks: Vec<&Track>,
+    tracks: Vec<&'a Track>,
     ui: &mut UIStateGuard,
     data: &DataReadGuard,
 ) {
@@ -800,84 +851,267 @@ fn render_track_table(
     let mut playing_track_uri = "".to_string();
     let mut playing_id = "";
     if let Some(ref playback) = state.player.read().playback {
-        if let Some(rspotify_model::PlayableItem::Track(ref track)) = playback.item {
-            playing_track_uri = track.id.as_ref().map(|id| id.uri()).unwrap_or_default();
+        let uri = match playback.item {
+            Some(rspotify_model::PlayableItem::Track(ref track)) => {
+                track.id.as_ref().map(|id| id.uri())
+            }
+            Some(rspotify_model::PlayableItem::Episode(ref episode)) => Some(episode.id.uri()),
+            None => None,
+        };
+        if let Some(uri) = uri {
+            playing_track_uri = uri;
 
             playing_id = if playback.is_playing {
-                &configs.app_config.play_icon
+                configs.app_config.get_play_icon()
             } else {
-                &configs.app_config.pause_icon
+                configs.app_config.get_pause_icon()
             };
         }
     }
 
-    let n_tracks = tracks.len();
-    let rows = tracks
+    // drop the columns hidden by user and the optional columns if the table is too narrow to render them
+    let columns = TrackTableColumn::ALL
         .into_iter()
-        .enumerate()
-        .map(|(id, t)| {
-            let (id, style) = if playing_track_uri == t.id.uri() {
+        .filter(|c| match c {
+            TrackTableColumn::Album if rect.width < TRACK_TABLE_ALBUM_MIN_WIDTH => false,
+            TrackTableColumn::Duration if rect.width < TRACK_TABLE_DURATION_MIN_WIDTH => false,
+            c => !ui.hidden_track_table_columns.contains(c),
+        })
+        .collect::<Vec<_>>();
+
+    let column_constraints = columns
+        .iter()
+        .map(|c| match c {
+            TrackTableColumn::Liked => {
+                Constraint::Length(configs.app_config.get_liked_icon().chars().count() as u16)
+            }
+            TrackTableColumn::Number => Constraint::Length(4),
+            TrackTableColumn::Title => Constraint::Fill(4),
+            TrackTableColumn::Artists => Constraint::Fill(3),
+            TrackTableColumn::Album => Constraint::Fill(5),
+            TrackTableColumn::Duration => Constraint::Fill(1),
+        })
+        .collect::<Vec<_>>();
+    // widths of the table's columns, used to scroll (marquee) the selected row's
+    // fields that don't fit into their columns
+    let column_widths = Layout::horizontal(column_constraints.clone())
+        .spacing(2)
+        .split(rect)
+        .iter()
+        .map(|r| r.width as usize)
+        .collect::<Vec<_>>();
+
+    // Only construct rows of the tracks that are visible in the table's window.
+    // Constructing rows for all tracks on every frame is slow for a large track list.
+    // -1 for the table's header
+    let n_visible_rows = rect.height.saturating_sub(1) as usize;
+    // rows of the grouped track table (if any)
+    let grouped_rows = ui.current_page().grouped_track_table_rows(&tracks);
+    // tracks grouped by disc are numbered by their positions in the disc
+    let is_disc_grouped = matches!(
+        grouped_rows.as_ref().and_then(|rows| rows.first()),
+        Some(TrackTableRow::DiscHeader { .. })
+    );
+    let n_rows = grouped_rows
+        .as_ref()
+        .map(|rows| rows.len())
+        .unwrap_or(tracks.len());
+    let mut visible_table_state = TableState::default();
+    let visible_range = match track_table_state_mut(ui) {
+        Some(table_state) => utils::adjust_virtualized_table_state(
+            table_state,
+            &mut visible_table_state,
+            n_rows,
+            n_visible_rows,
+        ),
+        None => 0..0,
+    };
+    let selected_row_id = visible_table_state
+        .selected()
+        .map(|id| id + visible_range.start);
+
+    // constructs a row representing a group's header
+    let group_header_row = |marker: &'static str, text: String| {
+        Row::new(
+            columns
+                .iter()
+                .map(|c| match c {
+                    TrackTableColumn::Number => Cell::from(marker),
+                    TrackTableColumn::Title => Cell::from(text.clone()),
+                    _ => Cell::from(""),
+                })
+                .collect::<Vec<_>>(),
+        )
+        .style(ui.theme.table_header().add_modifier(Modifier::BOLD))
+    };
+
+    let rows = visible_range
+        .map(|row_id| {
+            let id = match grouped_rows.as_ref().map(|rows| &rows[row_id]) {
+                Some(TrackTableRow::AlbumHeader {
+                    album,
+                    n_tracks,
+                    collapsed,
+                    ..
+                }) => {
+                    return group_header_row(
+                        if *collapsed { "+" } else { "-" },
+                        format!("{album} ({n_tracks} tracks)"),
+                    );
+                }
+                Some(TrackTableRow::DiscHeader {
+                    disc_number,
+                    n_tracks,
+                }) => {
+                    return group_header_row("", format!("Disc {disc_number} ({n_tracks} tracks)"));
+                }
+                Some(TrackTableRow::Track(id)) => *id,
+                None => row_id,
+            };
+            let t = tracks[id];
+            let is_selected = selected_row_id == Some(row_id);
+            let field = |text: Cow<'a, str>, column: usize| -> Cow<'a, str> {
+                if is_selected {
+                    Cow::Owned(utils::construct_marquee_text(
+                        text.into_owned(),
+                        column_widths[column],
+                    ))
+                } else {
+                    text
+                }
+            };
+            let (id, style) = if playing_track_uri == t.uri() {
                 (playing_id.to_string(), ui.theme.current_playing())
+            } else if is_disc_grouped {
+                (t.track_number.to_string(), Style::default())
             } else {
                 ((id + 1).to_string(), Style::default())
             };
-            Row::new(vec![
-                Cell::from(if data.user_data.is_liked_track(t) {
-                    &configs.app_config.liked_icon
-                } else {
-                    ""
-                }),
-                Cell::from(id),
-                Cell::from(t.display_name()),
-                Cell::from(t.artists_info()),
-                Cell::from(t.album_info()),
-                Cell::from(format!(
-                    "{}:{:02}",
-                    t.duration.as_secs() / 60,
-                    t.duration.as_secs() % 60,
-                )),
-            ])
-            .style(style)
+            let style = if t.is_playable {
+                style
+            } else {
+                ui.theme.unplayable_track()
+            };
+            let cells = columns
+                .iter()
+                .enumerate()
+                .map(|(column, c)| match c {
+                    TrackTableColumn::Liked => Cell::from(if data.user_data.is_liked_track(t) {
+                        configs.app_config.get_liked_icon()
+                    } else {
+                        ""
+                    }),
+                    TrackTableColumn::Number => Cell::from(id.clone()),
+                    TrackTableColumn::Title => Cell::from(field(t.display_name(), column)),
+                    TrackTableColumn::Artists => {
+                        Cell::from(field(Cow::Owned(t.artists_info()), column))
+                    }
+                    TrackTableColumn::Album => {
+                        Cell::from(field(Cow::Owned(t.album_info()), column))
+                    }
+                    TrackTableColumn::Duration => Cell::from(format!(
+                        "{}:{:02}",
+                        t.duration.as_secs() / 60,
+                        t.duration.as_secs() % 60,
+                    )),
+                })
+                .collect::<Vec<_>>();
+            Row::new(cells).style(style)
         })
         .collect::<Vec<_>>();
 
-    let track_table = Table::new(
-        rows,
-        [
-            Constraint::Length(configs.app_config.liked_icon.chars().count() as u16),
-            Constraint::Length(4),
-            Constraint::Fill(4),
-            Constraint::Fill(3),
-            Constraint::Fill(5),
-            Constraint::Fill(1),
-        ],
-    )
-    .header(
-        Row::new(vec![
-            Cell::from(""),
-            Cell::from("#"),
-            Cell::from("Title"),
-            Cell::from("Artists"),
-            Cell::from("Album"),
-            Cell::from("Duration"),
-        ])
-        .style(ui.theme.table_header()),
-    )
-    .column_spacing(2)
-    .highlight_style(ui.theme.selection(is_active));
-
-    if let PageState::Context {
-        state: Some(state), ..
-    } = ui.current_page_mut()
-    {
-        let track_table_state = match state {
+    let header = columns
+        .iter()
+        .map(|c| Cell::from(c.header()))
+        .collect::<Vec<_>>();
+
+    let track_table = Table::new(rows, column_constraints)
+        .header(Row::new(header).style(ui.theme.table_header()))
+        .column_spacing(2)
+        .highlight_style(ui.theme.selection(is_active));
+
+    frame.render_stateful_widget(track_table, rect, &mut visible_table_state);
+}
+
+/// Render the navigation path (breadcrumb) built from the pages in the navigation history
+fn render_breadcrumb(frame: &mut Frame, ui: &mut UIStateGuard, data: &DataReadGuard, rect: Rect) {
+    const SEPARATOR: &str = " ▸ ";
+    const ELLIPSIS: &str = "…";
+
+    let titles = ui
+        .history
+        .iter()
+        .map(|page| match page {
+            PageState::Context { id: Some(id), .. } => match data.caches.context.get(&id.uri()) {
+                Some(context) => context.name().to_string(),
+                None => page.title(),
+            },
+            _ => page.title(),
+        })
+        .collect::<Vec<_>>();
+
+    // only keep the most recent pages that fit into the available width
+    let max_width = rect.width as usize;
+    let mut start = titles.len();
+    let mut width = 0;
+    while start > 0 {
+        let title_width = titles[start - 1].chars().count() + SEPARATOR.chars().count();
+        let reserved = if start > 1 {
+            ELLIPSIS.chars().count() + SEPARATOR.chars().count()
+        } else {
+            0
+        };
+        if width + title_width + reserved > max_width && start < titles.len() {
+            break;
+        }
+        width += title_width;
+        start -= 1;
+    }
+
+    let mut spans = vec![];
+    let mut x = rect.x;
+    if start > 0 {
+        spans.push(Span::raw(ELLIPSIS));
+        spans.push(Span::raw(SEPARATOR));
+        x += (ELLIPSIS.chars().count() + SEPARATOR.chars().count()) as u16;
+    }
+    for (id, title) in titles.iter().enumerate().skip(start) {
+        let is_current_page = id + 1 == titles.len();
+        let title_width = title.chars().count() as u16;
+        if is_current_page {
+            spans.push(Span::styled(title.as_str(), ui.theme.page_desc()));
+        } else {
+            spans.push(Span::raw(title.as_str()));
+            spans.push(Span::raw(SEPARATOR));
+            ui.breadcrumb_rects.push((
+                Rect {
+                    x,
+                    y: rect.y,
+                    width: title_width.min((rect.x + rect.width).saturating_sub(x)),
+                    height: 1,
+                },
+                id,
+            ));
+        }
+        x = x.saturating_add(title_width + SEPARATOR.chars().count() as u16);
+    }
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), rect);
+}
+
+/// Get the track table's state of the current context page (if any)
+fn track_table_state_mut<'a>(ui: &'a mut UIStateGuard) -> Option<&'a mut TableState> {
+    match ui.current_page_mut() {
+        PageState::Context {
+            state: Some(state), ..
+        } => Some(match state {
             ContextPageUIState::Artist {
                 top_track_table, ..
             } => top_track_table,
-            ContextPageUIState::Playlist { track_table } => track_table,
+            ContextPageUIState::Playlist { track_table, .. } => track_table,
             ContextPageUIState::Album { track_table } => track_table,
-            ContextPageUIState::Tracks { track_table } => track_table,
-        };
-        utils::render_table_window(frame, track_table, rect, n_tracks, track_table_state);
+            ContextPageUIState::Tracks { track_table, .. } => track_table,
+        }),
+        _ => None,
     }
 }
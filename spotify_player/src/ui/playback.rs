@@ -1,4 +1,7 @@
-use super::{utils::construct_and_render_block, *};
+use super::{
+    utils::{construct_and_render_block, construct_marquee_line},
+    *,
+};
 
 /// Render a playback window showing information about the current playback, which includes
 /// - track title, artists, album
@@ -13,6 +16,8 @@ pub fn render_playback_window(
 ) -> Rect {
     let (rect, other_rect) = split_rect_for_playback_window(rect);
     let rect = construct_and_render_block("Playback", &ui.theme, Borders::ALL, frame, rect);
+    render_status_indicators(frame, state, ui, rect);
+    render_error_message(frame, ui, rect);
 
     let player = state.player.read();
     if let Some(ref playback) = player.playback {
@@ -102,7 +107,18 @@ pub fn render_playback_window(
 
             if let Some(ref playback) = player.buffered_playback {
                 let playback_text = construct_playback_text(ui, track, playback);
-                let playback_desc = Paragraph::new(playback_text).wrap(Wrap { trim: false });
+                let playback_desc = if config::get_config().app_config.enable_marquee {
+                    // scroll lines that don't fit into the playback window instead of wrapping them
+                    let width = metadata_rect.width as usize;
+                    let lines = playback_text
+                        .lines
+                        .into_iter()
+                        .map(|line| construct_marquee_line(line, width))
+                        .collect::<Vec<_>>();
+                    Paragraph::new(lines)
+                } else {
+                    Paragraph::new(playback_text).wrap(Wrap { trim: false })
+                };
                 frame.render_widget(playback_desc, metadata_rect);
             }
 
@@ -181,9 +197,9 @@ fn construct_playback_text(
                 format!(
                     "{} {}",
                     if !playback.is_playing {
-                        &configs.app_config.pause_icon
+                        configs.app_config.get_pause_icon()
                     } else {
-                        &configs.app_config.play_icon
+                        configs.app_config.get_play_icon()
                     },
                     if track.explicit {
                         format!("{} (E)", track.name)
@@ -198,23 +214,39 @@ fn construct_playback_text(
                 ui.theme.playback_artists(),
             ),
             "{album}" => (track.album.name.to_owned(), ui.theme.playback_album()),
-            "{metadata}" => (
-                format!(
-                    "repeat: {} | shuffle: {} | volume: {} | device: {}",
-                    if playback.fake_track_repeat_state {
-                        "track (fake)"
-                    } else {
-                        <&'static str>::from(playback.repeat_state)
-                    },
-                    playback.shuffle_state,
-                    match playback.mute_state {
-                        Some(volume) => format!("{volume}% (muted)"),
-                        None => format!("{}%", playback.volume.unwrap_or_default()),
-                    },
-                    playback.device_name,
-                ),
-                ui.theme.playback_metadata(),
-            ),
+            "{metadata}" => {
+                let icon_set = configs.app_config.icon_set;
+                let repeat_state = if playback.fake_track_repeat_state {
+                    "track (fake)"
+                } else {
+                    <&'static str>::from(playback.repeat_state)
+                };
+                let volume = match playback.mute_state {
+                    Some(volume) => format!("{volume}% (muted)"),
+                    None => format!("{}%", playback.volume.unwrap_or_default()),
+                };
+                // label a metadata field with an icon (if any) or its name
+                let label = |icon: Option<&str>, name: &str| match icon {
+                    Some(icon) => format!("{icon} "),
+                    None => format!("{name}: "),
+                };
+
+                (
+                    format!(
+                        "{}{repeat_state} | {}{} | {}{volume} | {}{}",
+                        label(icon_set.repeat_icon(playback.repeat_state), "repeat"),
+                        label(icon_set.shuffle_icon(playback.shuffle_state), "shuffle"),
+                        playback.shuffle_state,
+                        label(
+                            icon_set.volume_icon(playback.mute_state.is_some()),
+                            "volume"
+                        ),
+                        label(icon_set.device_icon(&playback.device_type), "device"),
+                        playback.device_name,
+                    ),
+                    ui.theme.playback_metadata(),
+                )
+            }
             _ => continue,
         };
 
@@ -328,6 +360,70 @@ fn render_playback_cover_image(state: &SharedState, ui: &mut UIStateGuard) -> Re
     Ok(())
 }
 
+/// Render the status indicators (the health of Spotify API, the keyboard macro being recorded
+/// and the pending keys of a multi-key sequence) on the right of the playback window's top border
+fn render_status_indicators(frame: &mut Frame, state: &SharedState, ui: &UIStateGuard, rect: Rect) {
+    let mut indicators = vec![];
+    if state.is_offline() {
+        indicators.push("offline".to_string());
+    }
+    {
+        let diagnostics = state.diagnostics.lock();
+        if diagnostics.is_api_unreachable() {
+            indicators.push("Spotify unreachable".to_string());
+        }
+        if let Some(secs) = diagnostics.rate_limit_remaining_secs() {
+            indicators.push(format!("rate limited ({secs}s)"));
+        }
+    }
+    if let Some((register, _)) = ui.recording_macro {
+        indicators.push(format!("recording @{register}"));
+    }
+    if !ui.input_key_sequence.keys.is_empty() {
+        indicators.push(ui.input_key_sequence.to_string());
+    }
+    if indicators.is_empty() || rect.y == 0 {
+        return;
+    }
+
+    let border_rect = Rect {
+        y: rect.y - 1,
+        height: 1,
+        ..rect
+    };
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            format!(" {} ", indicators.join(" | ")),
+            ui.theme.block_title(),
+        ))
+        .alignment(Alignment::Right),
+        border_rect,
+    );
+}
+
+/// Render the latest error reported to the user (if any) on the playback window's bottom border
+fn render_error_message(frame: &mut Frame, ui: &UIStateGuard, rect: Rect) {
+    let Some((message, _)) = ui.error_message.as_ref() else {
+        return;
+    };
+
+    let border_rect = Rect {
+        y: rect.y + rect.height,
+        height: 1,
+        ..rect
+    };
+    if border_rect.y >= frame.size().height {
+        return;
+    }
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            format!(" {message} "),
+            ui.theme.error_message(),
+        )),
+        border_rect,
+    );
+}
+
 /// Split the given area into two, the first one for the playback window
 /// and the second one for the main application's layout (popup, page, etc).
 fn split_rect_for_playback_window(rect: Rect) -> (Rect, Rect) {
@@ -83,6 +83,7 @@ pub fn render_popup(
             }
             PopupState::DeviceList { .. } => {
                 let player = state.player.read();
+                let configs = config::get_config();
 
                 let current_device_id = match player.playback {
                     Some(ref playback) => playback.device.id.as_deref().unwrap_or_default(),
@@ -91,7 +92,13 @@ pub fn render_popup(
                 let items = player
                     .devices
                     .iter()
-                    .map(|d| (format!("{} | {}", d.name, d.id), current_device_id == d.id))
+                    .map(|d| {
+                        let name = match configs.app_config.icon_set.device_icon(&d.device_type) {
+                            Some(icon) => format!("{icon} {}", d.name),
+                            None => d.name.clone(),
+                        };
+                        (format!("{name} | {}", d.id), current_device_id == d.id)
+                    })
                     .collect();
 
                 let rect = render_list_popup(frame, rect, "Devices", items, 5, ui);
@@ -149,10 +156,314 @@ pub fn render_popup(
                 let rect = render_list_popup(frame, rect, "Artists", items, 5, ui);
                 (rect, false)
             }
+            PopupState::TrackTableColumnList(_) => {
+                let items = TrackTableColumn::ALL
+                    .into_iter()
+                    .filter(|c| c.is_hideable())
+                    .map(|c| {
+                        let mark = if ui.hidden_track_table_columns.contains(&c) {
+                            "[ ]"
+                        } else {
+                            "[x]"
+                        };
+                        (format!("{mark} {}", c.name()), false)
+                    })
+                    .collect::<Vec<_>>();
+                let length = items.len() as u16 + 2; // 2 for top/bot paddings
+
+                let rect = render_list_popup(frame, rect, "Track Table Columns", items, length, ui);
+                (rect, false)
+            }
+            #[cfg(feature = "streaming")]
+            PopupState::PlaybackSettings(_) => {
+                let settings = state.player.read().integrated_player_settings();
+                let items = [
+                    ("Volume normalization", settings.normalization),
+                    ("Gapless playback", settings.gapless),
+                ]
+                .into_iter()
+                .map(|(name, enabled)| {
+                    let mark = if enabled { "[x]" } else { "[ ]" };
+                    (format!("{mark} {name}"), false)
+                })
+                .collect::<Vec<_>>();
+                let length = items.len() as u16 + 2; // 2 for top/bot paddings
+
+                let rect = render_list_popup(
+                    frame,
+                    rect,
+                    "Playback Settings (restarts the player)",
+                    items,
+                    length,
+                    ui,
+                );
+                (rect, false)
+            }
+            PopupState::ProfileList(profiles, _) => {
+                let configs = config::get_config();
+                let current_profile = configs.profile.as_ref();
+                let items = profiles
+                    .iter()
+                    .map(|p| {
+                        (
+                            p.clone().unwrap_or_else(|| "default".to_string()),
+                            p.as_ref() == current_profile,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let length = items.len() as u16 + 2; // 2 for top/bot paddings
+
+                let rect = render_list_popup(frame, rect, "Profiles", items, length, ui);
+                (rect, false)
+            }
+            PopupState::Diagnostics => {
+                let chunks =
+                    Layout::vertical([Constraint::Fill(0), Constraint::Length(22)]).split(rect);
+
+                let block_rect = construct_and_render_block(
+                    "Diagnostics",
+                    &ui.theme,
+                    Borders::ALL,
+                    frame,
+                    chunks[1],
+                );
+
+                let text = construct_diagnostics_text(&state.diagnostics.lock());
+                frame.render_widget(Paragraph::new(text), block_rect);
+                (chunks[0], false)
+            }
+            PopupState::Share { name, url } => {
+                let lines = construct_qr_code_lines(url);
+                let qr_width = lines.first().map(Line::width).unwrap_or_default();
+                let width = qr_width.max(url.len()) as u16 + 2; // 2 for borders
+                let height = lines.len() as u16 + 3; // 2 for borders and 1 for the link
+
+                let chunks =
+                    Layout::vertical([Constraint::Fill(0), Constraint::Length(height)]).split(rect);
+                let popup_rect = Layout::horizontal([Constraint::Length(width)])
+                    .flex(Flex::Center)
+                    .split(chunks[1])[0];
+
+                let block_rect = construct_and_render_block(
+                    &format!("Share {name}"),
+                    &ui.theme,
+                    Borders::ALL,
+                    frame,
+                    popup_rect,
+                );
+
+                let mut text = Text::from(lines);
+                text.push_line(Line::from(url.as_str()));
+                frame.render_widget(
+                    Paragraph::new(text).alignment(Alignment::Center),
+                    block_rect,
+                );
+                (chunks[0], false)
+            }
+            #[cfg(feature = "streaming")]
+            PopupState::ConfirmClearAudioCache => {
+                let chunks =
+                    Layout::vertical([Constraint::Fill(0), Constraint::Length(3)]).split(rect);
+                let rect = construct_and_render_block(
+                    "Clear Audio Cache",
+                    &ui.theme,
+                    Borders::ALL,
+                    frame,
+                    chunks[1],
+                );
+                frame.render_widget(
+                    Paragraph::new(
+                        "Remove all cached audio files? (enter to confirm, esc to cancel)",
+                    ),
+                    rect,
+                );
+                (chunks[0], false)
+            }
+            PopupState::TrackDetails(track) => {
+                let chunks =
+                    Layout::vertical([Constraint::Fill(0), Constraint::Length(9)]).split(rect);
+
+                let block_rect = construct_and_render_block(
+                    &format!("Details of {}", track.name),
+                    &ui.theme,
+                    Borders::ALL,
+                    frame,
+                    chunks[1],
+                );
+
+                let data = state.data.read();
+                let text = match data.caches.track_details.get(&track.id.uri()) {
+                    None => "Loading...".to_string(),
+                    Some(details) => construct_track_details_text(details),
+                };
+                frame.render_widget(Paragraph::new(text), block_rect);
+                (chunks[0], false)
+            }
         },
     }
 }
 
+/// constructs the text describing the application's diagnostics
+fn construct_diagnostics_text(diagnostics: &Diagnostics) -> String {
+    let mut lines = vec![];
+
+    let token_expiry = match diagnostics.token_expires_at {
+        Some(expires_at) => {
+            let remaining = expires_at - chrono::Utc::now();
+            if remaining > chrono::TimeDelta::zero() {
+                format!(
+                    "in {}m{}s",
+                    remaining.num_minutes(),
+                    remaining.num_seconds() % 60
+                )
+            } else {
+                "expired".to_string()
+            }
+        }
+        None => "unknown".to_string(),
+    };
+    lines.push(format!("Token expiry: {token_expiry}"));
+    lines.push(format!(
+        "Spotify API: {}",
+        match diagnostics.unreachable_since {
+            Some(since) => format!("unreachable for {}s", since.elapsed().as_secs()),
+            None => "reachable".to_string(),
+        }
+    ));
+    lines.push(format!(
+        "Playback updates: {}",
+        if diagnostics.is_connect_subscribed {
+            "Spotify Connect notifications"
+        } else {
+            "polling"
+        }
+    ));
+    lines.push(format!(
+        "Pending client requests: {}",
+        diagnostics.pending_requests
+    ));
+    lines.push(format!(
+        "Rate-limited responses: {}{}",
+        diagnostics.n_rate_limited_responses,
+        match diagnostics.last_retry_after_in_secs {
+            Some(secs) => format!(" (last retry after {secs}s)"),
+            None => String::new(),
+        }
+    ));
+
+    lines.push(String::new());
+    lines.push("Cache hit rates:".to_string());
+    if diagnostics.cache_lookups.is_empty() {
+        lines.push("  no lookups".to_string());
+    }
+    for (cache, (hits, misses)) in &diagnostics.cache_lookups {
+        let rate = *hits as f64 * 100.0 / (hits + misses) as f64;
+        lines.push(format!(
+            "  {cache}: {rate:.0}% ({hits} hits, {misses} misses)"
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push("Recent requests:".to_string());
+    if diagnostics.recent_requests.is_empty() {
+        lines.push("  no requests".to_string());
+    }
+    for stats in diagnostics.recent_requests.iter().rev() {
+        lines.push(format!(
+            "  {}: {}ms{}",
+            stats.request,
+            stats.latency.as_millis(),
+            if stats.succeeded { "" } else { " (failed)" }
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// constructs the lines of a QR code encoding `data`, in which each line renders
+/// two rows of the QR code's modules using the upper half block character
+fn construct_qr_code_lines(data: &str) -> Vec<Line<'static>> {
+    let code = match qrcode::QrCode::new(data) {
+        Ok(code) => code,
+        Err(err) => {
+            tracing::warn!("Failed to encode {data} as a QR code: {err:#}");
+            return vec![];
+        }
+    };
+
+    // the QR code is surrounded by a quiet zone of light modules
+    const QUIET_ZONE: usize = 2;
+    let width = code.width() + 2 * QUIET_ZONE;
+    let colors = code.to_colors();
+    let is_dark = |x: usize, y: usize| {
+        x >= QUIET_ZONE
+            && y >= QUIET_ZONE
+            && x < width - QUIET_ZONE
+            && y < width - QUIET_ZONE
+            && colors[(y - QUIET_ZONE) * code.width() + x - QUIET_ZONE] == qrcode::Color::Dark
+    };
+    let color = |dark: bool| if dark { Color::Black } else { Color::White };
+
+    (0..width)
+        .step_by(2)
+        .map(|y| {
+            Line::from(
+                (0..width)
+                    .map(|x| {
+                        let style = Style::default()
+                            .fg(color(is_dark(x, y)))
+                            .bg(color(y + 1 < width && is_dark(x, y + 1)));
+                        Span::styled("▀", style)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect()
+}
+
+/// constructs the text describing a track's details
+fn construct_track_details_text(details: &TrackDetails) -> String {
+    let mut lines = vec![
+        format!("Album: {}", details.album),
+        format!("Release date: {}", details.release_date),
+        format!("Popularity: {}/100", details.popularity),
+    ];
+    match details.features {
+        None => lines.push("Audio features: unavailable".to_string()),
+        Some(ref features) => {
+            const PITCH_CLASSES: [&str; 12] = [
+                "C",
+                "C♯/D♭",
+                "D",
+                "D♯/E♭",
+                "E",
+                "F",
+                "F♯/G♭",
+                "G",
+                "G♯/A♭",
+                "A",
+                "A♯/B♭",
+                "B",
+            ];
+            let key = usize::try_from(features.key)
+                .ok()
+                .and_then(|k| PITCH_CLASSES.get(k))
+                .copied()
+                .unwrap_or("unknown");
+            let mode = match features.mode {
+                rspotify_model::Modality::Major => "major",
+                rspotify_model::Modality::Minor => "minor",
+                rspotify_model::Modality::NoResult => "",
+            };
+            lines.push(format!("Tempo: {:.0} BPM", features.tempo));
+            lines.push(format!("Key: {key} {mode}").trim_end().to_string());
+            lines.push(format!("Energy: {:.2}", features.energy));
+            lines.push(format!("Danceability: {:.2}", features.danceability));
+        }
+    }
+    lines.join("\n")
+}
+
 /// A helper function to render a list popup
 fn render_list_popup(
     frame: &mut Frame,
@@ -0,0 +1,106 @@
+use std::{fmt::Write, path::PathBuf};
+
+use anyhow::{Context, Result};
+use crossterm::style::{Attribute, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use tui::buffer::{Buffer, Cell};
+
+use super::*;
+
+/// Export a rendered frame's buffer into a plain-text file and an ANSI file
+/// inside the application's cache folder.
+///
+/// The function returns the paths of the exported files.
+pub fn export_screenshot(buffer: &Buffer) -> Result<(PathBuf, PathBuf)> {
+    let folder = config::get_config().cache_folder.join("screenshots");
+    std::fs::create_dir_all(&folder).context("failed to create the screenshots folder")?;
+
+    let prefix = format!(
+        "spotify-player-{}",
+        chrono::Local::now().format("%y-%m-%d-%H-%M-%S")
+    );
+    let text_path = folder.join(format!("{prefix}.txt"));
+    let ansi_path = folder.join(format!("{prefix}.ansi"));
+
+    std::fs::write(&text_path, buffer_to_text(buffer))
+        .context("failed to write the plain-text screenshot")?;
+    std::fs::write(&ansi_path, buffer_to_ansi(buffer))
+        .context("failed to write the ANSI screenshot")?;
+
+    Ok((text_path, ansi_path))
+}
+
+/// Get the buffer's visible cells grouped by lines. The cells following a wide character
+/// are skipped because they are covered by such character.
+fn buffer_lines(buffer: &Buffer) -> impl Iterator<Item = Vec<&Cell>> {
+    let width = buffer.area.width as usize;
+    buffer.content.chunks(width.max(1)).map(|line| {
+        let mut cells = vec![];
+        let mut to_skip = 0;
+        for cell in line {
+            if to_skip > 0 {
+                to_skip -= 1;
+                continue;
+            }
+            to_skip = Span::raw(cell.symbol()).width().saturating_sub(1);
+            cells.push(cell);
+        }
+        cells
+    })
+}
+
+fn buffer_to_text(buffer: &Buffer) -> String {
+    let mut text = String::new();
+    for cells in buffer_lines(buffer) {
+        let line = cells.iter().map(|c| c.symbol()).collect::<String>();
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+fn buffer_to_ansi(buffer: &Buffer) -> String {
+    let mut ansi = String::new();
+    for cells in buffer_lines(buffer) {
+        let mut last_style = None;
+        for cell in cells {
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if last_style != Some(style) {
+                write_ansi_style(&mut ansi, cell);
+                last_style = Some(style);
+            }
+            ansi.push_str(cell.symbol());
+        }
+        let _ = write!(ansi, "{}", SetAttribute(Attribute::Reset));
+        ansi.push('\n');
+    }
+    ansi
+}
+
+/// Write the ANSI escape sequences representing a cell's style
+fn write_ansi_style(ansi: &mut String, cell: &Cell) {
+    let _ = write!(ansi, "{}", SetAttribute(Attribute::Reset));
+
+    let modifiers = [
+        (Modifier::BOLD, Attribute::Bold),
+        (Modifier::DIM, Attribute::Dim),
+        (Modifier::ITALIC, Attribute::Italic),
+        (Modifier::UNDERLINED, Attribute::Underlined),
+        (Modifier::SLOW_BLINK, Attribute::SlowBlink),
+        (Modifier::RAPID_BLINK, Attribute::RapidBlink),
+        (Modifier::REVERSED, Attribute::Reverse),
+        (Modifier::HIDDEN, Attribute::Hidden),
+        (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+    ];
+    for (modifier, attribute) in modifiers {
+        if cell.modifier.contains(modifier) {
+            let _ = write!(ansi, "{}", SetAttribute(attribute));
+        }
+    }
+
+    if cell.fg != Color::Reset {
+        let _ = write!(ansi, "{}", SetForegroundColor(cell.fg.into()));
+    }
+    if cell.bg != Color::Reset {
+        let _ = write!(ansi, "{}", SetBackgroundColor(cell.bg.into()));
+    }
+}
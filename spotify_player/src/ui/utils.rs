@@ -1,5 +1,12 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use unicode_width::UnicodeWidthChar as _;
+
 use super::*;
 
+/// whether any text was scrolled (marquee) in the last rendered frame
+static MARQUEE_ACTIVE: AtomicBool = AtomicBool::new(false);
+
 /// Construct and render a block.
 ///
 /// This function should only be used to render a window's borders and its title.
@@ -102,13 +109,110 @@ fn adjust_table_state(state: &mut TableState, len: usize) {
     }
 }
 
-pub fn render_table_window(
-    frame: &mut Frame,
-    widget: Table,
-    rect: Rect,
-    len: usize,
+/// Adjust the state of a table whose rows are only partially constructed (virtualized),
+/// based on the table's length and its number of visible rows.
+///
+/// The function updates the scroll offset of `state` to keep the selected row visible,
+/// sets up `visible_state` to be the state of the table constructed from visible rows,
+/// and returns the range of the visible rows.
+pub fn adjust_virtualized_table_state(
     state: &mut TableState,
-) {
+    visible_state: &mut TableState,
+    len: usize,
+    n_visible_rows: usize,
+) -> std::ops::Range<usize> {
     adjust_table_state(state, len);
-    frame.render_stateful_widget(widget, rect, state);
+
+    let mut offset = state.offset();
+    if let Some(selected) = state.selected() {
+        if selected < offset {
+            offset = selected;
+        } else if selected >= offset + n_visible_rows {
+            offset = (selected + 1).saturating_sub(n_visible_rows);
+        }
+    }
+    let offset = std::cmp::min(offset, len.saturating_sub(n_visible_rows));
+    *state.offset_mut() = offset;
+
+    visible_state.select(state.selected().and_then(|id| id.checked_sub(offset)));
+    offset..std::cmp::min(len, offset + n_visible_rows)
+}
+
+/// Construct a marquee (scrolling) version of a styled line so that it fits into `width` columns.
+///
+/// The line is returned unchanged if marquee is disabled or the line already fits.
+/// Otherwise, the line's content is shifted to the left by one character every
+/// `marquee_speed_in_ms` milliseconds, wrapping around after a small gap.
+/// Characters are fitted by their display width, so wide characters (e.g. CJK) take two columns.
+pub fn construct_marquee_line(line: Line<'static>, width: usize) -> Line<'static> {
+    const MARQUEE_GAP: &str = "   ";
+
+    let configs = config::get_config();
+    if !configs.app_config.enable_marquee || width == 0 || line.width() <= width {
+        return line;
+    }
+
+    // flatten the line into a list of styled characters
+    let chars = line
+        .spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .chain(MARQUEE_GAP.chars().map(|c| (c, Style::default())))
+        .collect::<Vec<_>>();
+
+    MARQUEE_ACTIVE.store(true, Ordering::Relaxed);
+    let offset = current_marquee_step() as usize % chars.len();
+
+    // group consecutive characters of the same style into a span
+    let mut spans: Vec<Span<'static>> = vec![];
+    let mut remaining = width;
+    for (c, style) in chars.iter().cycle().skip(offset) {
+        let c_width = c.width().unwrap_or(0);
+        if c_width > remaining {
+            // pad the line if a wide character doesn't fit into the remaining columns
+            spans.push(Span::raw(" ".repeat(remaining)));
+            break;
+        }
+        remaining -= c_width;
+        match spans.last_mut() {
+            Some(span) if span.style == *style => span.content.to_mut().push(*c),
+            _ => spans.push(Span::styled(c.to_string(), *style)),
+        }
+        if remaining == 0 {
+            break;
+        }
+    }
+
+    Line::from(spans).style(line.style)
+}
+
+fn current_marquee_step() -> u128 {
+    let elapsed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    elapsed / std::cmp::max(config::get_config().app_config.marquee_speed_in_ms, 1) as u128
+}
+
+/// Get the current marquee scroll step if any text was scrolled in the last rendered frame
+pub fn marquee_step() -> Option<u128> {
+    if MARQUEE_ACTIVE.load(Ordering::Relaxed) {
+        Some(current_marquee_step())
+    } else {
+        None
+    }
+}
+
+/// Reset the marquee state before rendering a new frame
+pub fn reset_marquee_state() {
+    MARQUEE_ACTIVE.store(false, Ordering::Relaxed);
+}
+
+/// Construct a marquee (scrolling) version of a text so that it fits into `width` columns.
+pub fn construct_marquee_text(text: String, width: usize) -> String {
+    construct_marquee_line(Line::raw(text), width)
+        .spans
+        .into_iter()
+        .map(|span| span.content)
+        .collect()
 }
@@ -42,7 +42,73 @@ pub fn get_track_album_image_url(track: &rspotify::model::FullTrack) -> Option<&
     }
 }
 
-pub fn parse_uri(uri: &str) -> Cow<str> {
+/// serializes a list of tracks into a CSV table
+pub fn tracks_to_csv(tracks: &[&crate::state::Track]) -> String {
+    fn escape(field: &str) -> Cow<'_, str> {
+        if field.contains([',', '"', '\n']) {
+            Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+        } else {
+            Cow::Borrowed(field)
+        }
+    }
+
+    let mut csv = String::from("#,Title,Artists,Album,Duration\n");
+    for (id, t) in tracks.iter().enumerate() {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            id + 1,
+            escape(&t.name),
+            escape(&t.artists_info()),
+            escape(&t.album_info()),
+            format_std_duration(&t.duration),
+        ));
+    }
+    csv
+}
+
+/// serializes a list of tracks into a Markdown table
+pub fn tracks_to_markdown(tracks: &[&crate::state::Track]) -> String {
+    fn escape(field: &str) -> String {
+        field.replace('|', "\\|")
+    }
+
+    let mut md = String::from("| # | Title | Artists | Album | Duration |\n");
+    md.push_str("|---|---|---|---|---|\n");
+    for (id, t) in tracks.iter().enumerate() {
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            id + 1,
+            escape(&t.name),
+            escape(&t.artists_info()),
+            escape(&t.album_info()),
+            format_std_duration(&t.duration),
+        ));
+    }
+    md
+}
+
+/// writes a serialized track table into a new file with the given extension
+/// inside the `exports` folder of the application's cache folder
+pub fn export_track_table(content: &str, extension: &str) -> anyhow::Result<std::path::PathBuf> {
+    use anyhow::Context as _;
+
+    let folder = crate::config::get_config().cache_folder.join("exports");
+    std::fs::create_dir_all(&folder).context("failed to create the exports folder")?;
+    let path = folder.join(format!(
+        "spotify-player-{}.{extension}",
+        chrono::Local::now().format("%y-%m-%d-%H-%M-%S")
+    ));
+    std::fs::write(&path, content).context("failed to write the exported track table")?;
+    Ok(path)
+}
+
+/// formats a `std` time duration into a "{minutes}:{seconds}" format
+fn format_std_duration(duration: &std::time::Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+pub fn parse_uri(uri: &str) -> Cow<'_, str> {
     let parts = uri.split(':').collect::<Vec<_>>();
     // The below URI probably has a format of `spotify:user:{user_id}:{type}:{id}`,
     // but `rspotify` library expects to receive an URI of format `spotify:{type}:{id}`.
@@ -54,3 +120,83 @@ pub fn parse_uri(uri: &str) -> Cow<str> {
         Cow::Borrowed(uri)
     }
 }
+
+/// gets the current process's command-line arguments with the `--profile` argument replaced by `profile`
+pub fn restart_args_with_profile(profile: Option<&str>) -> Vec<std::ffi::OsString> {
+    let mut args = vec![];
+    let mut old_args = std::env::args_os().skip(1);
+    while let Some(arg) = old_args.next() {
+        match arg.to_str() {
+            Some("--profile" | "-p") => {
+                old_args.next();
+            }
+            Some(a) if a.starts_with("--profile=") => {}
+            _ => args.push(arg),
+        }
+    }
+    if let Some(profile) = profile {
+        args.push("--profile".into());
+        args.push(profile.into());
+    }
+    args
+}
+
+/// restarts the application by running its executable with new command-line arguments
+pub fn restart_application(args: Vec<std::ffi::OsString>) -> anyhow::Result<()> {
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    command.args(args);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // `exec` only returns if the process cannot be replaced
+        Err(command.exec().into())
+    }
+    #[cfg(not(unix))]
+    {
+        command.spawn()?;
+        Ok(())
+    }
+}
+
+/// checks whether the official Spotify desktop client is installed
+fn is_spotify_client_installed() -> bool {
+    if cfg!(target_os = "macos") {
+        std::path::Path::new("/Applications/Spotify.app").exists()
+            || dirs_next::home_dir()
+                .is_some_and(|home| home.join("Applications/Spotify.app").exists())
+    } else if cfg!(target_os = "windows") {
+        dirs_next::data_dir().is_some_and(|data| data.join("Spotify/Spotify.exe").exists())
+    } else {
+        which::which("spotify").is_ok()
+    }
+}
+
+/// opens a Spotify item with the system's default handler, which is the official Spotify client
+/// (using the item's URI) if installed or the web browser (using the item's link) otherwise
+pub fn open_spotify_item(uri: &str, url: &str) -> anyhow::Result<()> {
+    let target = if is_spotify_client_installed() {
+        uri
+    } else {
+        url
+    };
+
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    let mut child = command
+        .arg(target)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    // wait for the opener in a separate thread to avoid blocking the application
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use parking_lot::Mutex;
+use rspotify::model::AudioAnalysisSegment;
+
+/// the number of frequency bands of a spectrum computed from the played audio
+#[cfg(feature = "streaming")]
+const N_BANDS: usize = 32;
+/// the frequency range (in Hz) covered by a spectrum computed from the played audio
+#[cfg(feature = "streaming")]
+const FREQUENCY_RANGE: (f64, f64) = (50.0, 16000.0);
+/// the sample rate of the audio played by the integrated player
+#[cfg(feature = "streaming")]
+const SAMPLE_RATE: f64 = 44100.0;
+/// the loudness range (in dB) mapped to the bars' heights
+const LOUDNESS_RANGE_DB: f64 = 60.0;
+/// the duration after which the spectrum of the played audio is considered outdated
+const SPECTRUM_MAX_AGE: std::time::Duration = std::time::Duration::from_millis(500);
+/// the duration between two consecutive frames of the visualizer
+const FRAME_DURATION: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// whether the visualizer is shown, the spectrum of the played audio is only computed if it is
+static IS_ENABLED: AtomicBool = AtomicBool::new(false);
+/// the spectrum of the audio recently played by the integrated player
+static SPECTRUM: Mutex<Option<(Vec<f64>, std::time::Instant)>> = parking_lot::const_mutex(None);
+
+/// enables or disables the visualizer
+pub fn set_enabled(enabled: bool) {
+    IS_ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        *SPECTRUM.lock() = None;
+    }
+}
+
+#[cfg(feature = "streaming")]
+/// updates the spectrum of the played audio from a packet of interleaved stereo samples
+pub fn update_spectrum(samples: &[f64]) {
+    if !IS_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    // down-mix the samples to mono
+    let samples = samples
+        .chunks_exact(2)
+        .map(|c| (c[0] + c[1]) / 2.0)
+        .collect::<Vec<_>>();
+    if samples.is_empty() {
+        return;
+    }
+
+    let (low, high) = FREQUENCY_RANGE;
+    let bands = (0..N_BANDS)
+        .map(|i| {
+            // the bands' frequencies are spread logarithmically like the human hearing
+            let frequency = low * (high / low).powf(i as f64 / (N_BANDS - 1) as f64);
+            let magnitude = goertzel_magnitude(&samples, frequency) / samples.len() as f64;
+            loudness_to_height(20.0 * magnitude.max(f64::MIN_POSITIVE).log10())
+        })
+        .collect::<Vec<_>>();
+
+    let mut spectrum = SPECTRUM.lock();
+    let bands = match spectrum.take() {
+        // smooth the spectrum to avoid flickering bars
+        Some((prev, _)) => prev
+            .into_iter()
+            .zip(bands)
+            .map(|(prev, cur)| cur.max(prev * 0.8))
+            .collect(),
+        None => bands,
+    };
+    *spectrum = Some((bands, std::time::Instant::now()));
+}
+
+#[cfg(feature = "streaming")]
+/// computes the magnitude of a frequency in the samples using the Goertzel algorithm
+fn goertzel_magnitude(samples: &[f64], frequency: f64) -> f64 {
+    let coeff = 2.0 * (2.0 * std::f64::consts::PI * frequency / SAMPLE_RATE).cos();
+    let (mut s1, mut s2) = (0.0, 0.0);
+    for sample in samples {
+        let s0 = sample + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    (s1 * s1 + s2 * s2 - coeff * s1 * s2).max(0.0).sqrt()
+}
+
+/// maps a loudness (in dB) to a bar's height between 0 and 1
+fn loudness_to_height(loudness: f64) -> f64 {
+    ((loudness + LOUDNESS_RANGE_DB) / LOUDNESS_RANGE_DB).clamp(0.0, 1.0)
+}
+
+/// gets the spectrum of the audio recently played by the integrated player (if any)
+pub fn played_spectrum() -> Option<Vec<f64>> {
+    SPECTRUM
+        .lock()
+        .as_ref()
+        .filter(|(_, time)| time.elapsed() < SPECTRUM_MAX_AGE)
+        .map(|(bands, _)| bands.clone())
+}
+
+/// approximates the spectrum at a playback's position from the track's audio analysis,
+/// in which each bar represents the intensity of a pitch class scaled by the loudness
+pub fn approximated_spectrum(
+    segments: &[AudioAnalysisSegment],
+    progress: chrono::Duration,
+) -> Option<Vec<f64>> {
+    let position = progress.num_milliseconds() as f32 / 1000.0;
+    let id = segments.partition_point(|s| s.time_interval.start <= position);
+    let segment = segments.get(id.checked_sub(1)?)?;
+    let height = loudness_to_height(f64::from(segment.loudness_max));
+    Some(
+        segment
+            .pitches
+            .iter()
+            .map(|pitch| f64::from(*pitch) * height)
+            .collect(),
+    )
+}
+
+/// gets the current frame of the visualizer, which is used
+/// by the UI to determine whether the visualizer needs to be redrawn
+pub fn frame_step() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+        / FRAME_DURATION.as_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rspotify::model::TimeInterval;
+
+    fn segment(start: f32, loudness_max: f32, pitches: Vec<f32>) -> AudioAnalysisSegment {
+        AudioAnalysisSegment {
+            time_interval: TimeInterval {
+                start,
+                duration: 1.0,
+                confidence: 1.0,
+            },
+            loudness_start: loudness_max,
+            loudness_max_time: 0.0,
+            loudness_max,
+            loudness_end: None,
+            pitches,
+            timbre: vec![],
+        }
+    }
+
+    #[test]
+    fn loudness_is_mapped_to_bar_heights() {
+        assert_eq!(loudness_to_height(0.0), 1.0);
+        assert_eq!(loudness_to_height(-LOUDNESS_RANGE_DB / 2.0), 0.5);
+        assert_eq!(loudness_to_height(-LOUDNESS_RANGE_DB), 0.0);
+        // loudness outside of the range is clamped
+        assert_eq!(loudness_to_height(-2.0 * LOUDNESS_RANGE_DB), 0.0);
+        assert_eq!(loudness_to_height(10.0), 1.0);
+    }
+
+    #[test]
+    fn spectrum_is_approximated_from_the_playing_segment() {
+        let segments = [
+            segment(0.0, 0.0, vec![1.0, 0.5]),
+            segment(1.0, -(LOUDNESS_RANGE_DB / 2.0) as f32, vec![1.0, 0.5]),
+        ];
+
+        let spectrum = |ms| approximated_spectrum(&segments, chrono::Duration::milliseconds(ms));
+        assert_eq!(spectrum(500), Some(vec![1.0, 0.5]));
+        assert_eq!(spectrum(1000), Some(vec![0.5, 0.25]));
+        assert_eq!(spectrum(5000), Some(vec![0.5, 0.25]));
+        assert_eq!(approximated_spectrum(&[], chrono::Duration::zero()), None);
+    }
+}